@@ -1,67 +1,226 @@
-use std::{env::temp_dir, fs::write, path::PathBuf};
+use std::{
+    env::{consts::OS as HOST_OS, temp_dir},
+    fs::{read_to_string, remove_dir_all, write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
 
+use clap::Parser;
 use guess_host_triple::guess_host_triple;
 use libpng_src::build_artifact;
+use similar::{ChangeTag, TextDiff};
 
-const MANUAL_BEGINNING: &str = "//! Cargo package for compiling [libpng](https://github.com/pnggroup/libpng) and vendoring it as **static** library.
-//!
-//! Main goal of the package is providing static library for linking with other C code, like versions of [Leptonica](http://www.leptonica.org/).
-//! It provides just rudimentary FFI bindings. More sophisticated bindings would be provided in a separate crate.
-//! If you need to bind **libpng** with the Rust code directly, you should write your own bindings.
-//!
-//! Does not translate C macros from **libpng**.
-//!
-//! Rust FFI bindings not documented. For information on underlying C functions and constants is provided here:
-//! https://github.com/pnggroup/libpng/blob/libpng16/libpng-manual.txt
+include!("../../libpng-vendored-sys/bindgen_blocklist.rs");
 
-#![allow(non_camel_case_types)]
-#![allow(non_upper_case_globals)]
-#![allow(non_snake_case)]
+/// Maximum number of changed lines printed by `--check`.
+const MAX_DIFF_LINES: usize = 20;
 
-use libc::{time_t, tm, FILE};
+/// Generates FFI bindings for 'libpng-vendored-sys'.
+///
+/// Without arguments regenerates all binding families which can be generated on this host.
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Cli {
+    /// Target triple passed to Clang, all families for this host if omitted.
+    #[arg(long)]
+    target: Option<String>,
+    /// Output file, defaults to the family module in 'libpng-vendored-sys/src/bindings/'.
+    #[arg(long, requires = "target")]
+    output: Option<PathBuf>,
+    /// Header from libpng include directory to allowlist, may be repeated.
+    #[arg(long = "allowlist-header", default_value = "png.h")]
+    allowlist_headers: Vec<String>,
+    /// Extra argument passed to Clang, may be repeated.
+    #[arg(long = "clang-arg", allow_hyphen_values = true)]
+    clang_args: Vec<String>,
+    /// Directory for building libpng headers, a temporary one if omitted.
+    #[arg(long)]
+    working_dir: Option<PathBuf>,
+    /// Don't write anything, exit with non-zero code if the existing bindings are stale.
+    #[arg(long)]
+    check: bool,
+}
+
+/// Family of targets sharing the same bindings, see 'libpng-vendored-sys/src/bindings/mod.rs'.
+struct BindingsFamily {
+    /// Module file name in 'libpng-vendored-sys/src/bindings/'.
+    module: &'static str,
+    /// Representative target for generation, passed to Clang.
+    target: &'static str,
+    /// Host OS able to generate the bindings, system headers for the target are required.
+    host_os: &'static str,
+}
+
+const FAMILIES: [BindingsFamily; 2] = [
+    BindingsFamily {
+        module: "unix_64",
+        target: "x86_64-unknown-linux-gnu",
+        host_os: "linux",
+    },
+    // Requires 32-bit C headers, e.g. 'gcc-multilib' package
+    BindingsFamily {
+        module: "unix_32",
+        target: "i686-unknown-linux-gnu",
+        host_os: "linux",
+    },
+];
+
+/// A single generation job: target and destination file.
+struct Job {
+    target: String,
+    output: PathBuf,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let jobs = match jobs(&cli) {
+        Ok(jobs) => jobs,
+        Err(message) => {
+            eprintln!("{message}");
+            return ExitCode::FAILURE;
+        }
+    };
 
-#[cfg(feature = \"link-libz\")]
-#[allow(clippy::single_component_path_imports)]
-#[allow(unused_imports)]
-// Used for linking only
-use libz_sys;
+    let host_target = guess_host_triple().expect("Cannot detect this hosts target");
 
-mod c_macro_helpers;
-pub use c_macro_helpers::*;
+    let working_dir = cli
+        .working_dir
+        .clone()
+        .unwrap_or_else(|| temp_dir().join(format!("bindgen-helper-{}", std::process::id())));
 
-";
+    // Headers don't depend on the target, 'pnglibconf.h' is the same for all supported targets
+    let artifact_info = build_artifact(host_target, &working_dir).unwrap();
 
-fn main() {
-    let target = guess_host_triple().expect("Cannot detect this hosts target");
+    let mut stale_count = 0;
 
-    let working_dir = temp_dir().join("bindgen-helper");
+    for job in jobs {
+        let bindings = generate(&cli, &job.target, &artifact_info.include_dir);
 
-    let artifact_info = build_artifact(target, &working_dir).unwrap();
-    let png_h_path = artifact_info.include_dir.join("png.h");
+        if cli.check {
+            if !check(&job.output, &bindings) {
+                stale_count += 1;
+            }
+        } else {
+            write(&job.output, bindings).unwrap();
+            println!("Generated '{}' for {}", job.output.display(), job.target);
+        }
+    }
 
-    let dest_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    if cli.working_dir.is_none() {
+        remove_dir_all(&working_dir).unwrap_or_else(|_| println!("Cannot clean working directory"));
+    }
+
+    if stale_count > 0 {
+        eprintln!("{stale_count} binding file(s) are stale, run 'cargo run -p bindgen-helper'");
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn jobs(cli: &Cli) -> Result<Vec<Job>, String> {
+    let Some(target) = &cli.target else {
+        return Ok(FAMILIES
+            .iter()
+            .filter(|family| family.host_os == HOST_OS)
+            .map(|family| Job {
+                target: family.target.to_string(),
+                output: family_path(family),
+            })
+            .collect());
+    };
+
+    let output = match &cli.output {
+        Some(output) => output.clone(),
+        None => FAMILIES
+            .iter()
+            .find(|family| family.target == target)
+            .map(family_path)
+            .ok_or_else(|| {
+                format!("No bindings family for target {target}, specify '--output' explicitly")
+            })?,
+    };
+
+    Ok(vec![Job {
+        target: target.clone(),
+        output,
+    }])
+}
+
+fn family_path(family: &BindingsFamily) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
         .parent()
         .unwrap()
         .join("libpng-vendored-sys")
         .join("src")
-        .join("lib.rs");
+        .join("bindings")
+        .join(format!("{}.rs", family.module))
+}
 
-    let bindings = bindgen::builder()
+fn generate(cli: &Cli, target: &str, include_dir: &Path) -> String {
+    let png_h_path = include_dir.join("png.h");
+
+    let builder = bindgen::builder()
         .header(png_h_path.to_string_lossy())
-        .allowlist_file(png_h_path.to_string_lossy())
-        .blocklist_item("FILE")
-        .blocklist_item("time_t")
-        .blocklist_item("tm")
-        .blocklist_item("fpos_t")
-        .blocklist_item("__sFILE")
-        .blocklist_item("__sFILEX")
-        .blocklist_item("__sbuf")
-        .blocklist_item("__darwin_time_t")
-        .blocklist_item("__darwin_off_t")
-        .blocklist_item("__int64_t")
+        .layout_tests(true)
+        .clang_arg(format!("--target={target}"))
+        .clang_arg(format!("-I{}", include_dir.to_string_lossy()))
+        .clang_args(&cli.clang_args);
+
+    let builder = cli
+        .allowlist_headers
+        .iter()
+        .fold(builder, |builder, header| {
+            builder.allowlist_file(include_dir.join(header).to_string_lossy())
+        });
+
+    BINDGEN_BLOCKLIST
+        .iter()
+        .fold(builder, |builder, item| builder.blocklist_item(*item))
         .generate()
         .unwrap()
-        .to_string();
+        .to_string()
+}
+
+/// Compares generated bindings with the existing file, prints a diff summary if they differ.
+fn check(path: &Path, generated: &str) -> bool {
+    let existing = read_to_string(path).unwrap_or_default();
+
+    if existing == generated {
+        println!("'{}' is up to date", path.display());
+        return true;
+    }
+
+    let diff = TextDiff::from_lines(existing.as_str(), generated);
+    let changes: Vec<_> = diff
+        .iter_all_changes()
+        .filter(|change| change.tag() != ChangeTag::Equal)
+        .collect();
+
+    let removed = changes
+        .iter()
+        .filter(|change| change.tag() == ChangeTag::Delete)
+        .count();
+    let added = changes.len() - removed;
+
+    eprintln!(
+        "'{}' is stale: {added} line(s) added, {removed} line(s) removed",
+        path.display()
+    );
+
+    for change in changes.iter().take(MAX_DIFF_LINES) {
+        let sign = match change.tag() {
+            ChangeTag::Delete => "-",
+            _ => "+",
+        };
+
+        eprint!("{sign} {change}");
+    }
+
+    if changes.len() > MAX_DIFF_LINES {
+        eprintln!("... and {} more", changes.len() - MAX_DIFF_LINES);
+    }
 
-    write(dest_path, format!("{MANUAL_BEGINNING}{bindings}")).unwrap();
+    false
 }
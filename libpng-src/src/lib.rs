@@ -11,13 +11,22 @@
 //! - Android (cross-compilation from Linux, macOS or Windows hosts): `armv7-linux-androideabi`, `aarch64-linux-android`,
 //! `i686-linux-android`, `x86_64-linux-android`
 
+mod lto;
+mod sanitizer;
+
+pub use lto::{cross_language_lto_from_env, CROSS_LANGUAGE_LTO_ENV_VAR};
+pub use sanitizer::{Sanitizer, SANITIZER_ENV_VAR};
+
 use std::{
-    env::consts::{ARCH as HOST_ARCH, OS as HOST_OS},
+    env::{
+        consts::{ARCH as HOST_ARCH, OS as HOST_OS},
+        var,
+    },
     error::Error,
     ffi::OsString,
-    fs::{self, copy, create_dir, create_dir_all, remove_dir_all},
+    fs::{self, copy, create_dir, create_dir_all, read_to_string, remove_dir_all, write},
     path::{Path, PathBuf},
-    process::Command,
+    process::{Command, Output},
     str::FromStr,
     vec::Vec,
 };
@@ -37,6 +46,22 @@ pub struct Artifacts {
     pub link_name: String,
 }
 
+/// Additional build configuration for [build_artifact_with_options](build_artifact_with_options)
+/// and [compile_lib_with_options](compile_lib_with_options).
+#[derive(Clone, Debug, Default)]
+pub struct BuildOptions {
+    /// Emit `cargo:warning=` lines summarizing a failed build.
+    /// Makes sense only when called from a build script.
+    pub cargo_warnings: bool,
+    /// Compile **libpng** with sanitizer instrumentation, see [Sanitizer](Sanitizer) for supported targets.
+    /// Use [Sanitizer::from_env](Sanitizer::from_env) to match the sanitizer of the current Cargo build.
+    pub sanitizer: Option<Sanitizer>,
+    /// Compile **libpng** with `-flto=thin` into LLVM bitcode archive for linking with `-Clinker-plugin-lto`.
+    /// Requires Clang as C compiler with the same LLVM major version as `rustc`, and `llvm-ar` with `ld.lld` on Linux.
+    /// Use [cross_language_lto_from_env](cross_language_lto_from_env) to match the current Cargo build.
+    pub cross_language_lto: bool,
+}
+
 /// Returns the path to the source directory without any modifications.
 ///
 /// Use it to generate bindings to the **libpng** if needed.
@@ -108,9 +133,20 @@ pub fn source_path() -> PathBuf {
 ///         └->lib/ ... Static library - add to link search path.
 /// ```
 pub fn build_artifact(target_str: &str, working_dir: &Path) -> Result<Artifacts, Box<dyn Error>> {
+    build_artifact_with_options(target_str, working_dir, &BuildOptions::default())
+}
+
+/// Same as [build_artifact](build_artifact), but with additional build configuration.
+///
+/// If the build fails, CMake logs are kept in the 'build/' subdirectory of the working directory.
+pub fn build_artifact_with_options(
+    target_str: &str,
+    working_dir: &Path,
+    options: &BuildOptions,
+) -> Result<Artifacts, Box<dyn Error>> {
     let build_dir = working_dir.join("build");
 
-    let library_path = compile_lib(target_str, &build_dir)?;
+    let library_path = compile_lib_with_options(target_str, &build_dir, options)?;
     let library_filename = library_path
         .file_name()
         .map(|os| os.to_string_lossy())
@@ -178,6 +214,58 @@ pub fn build_artifact(target_str: &str, working_dir: &Path) -> Result<Artifacts,
 /// }
 /// ```
 pub fn compile_lib(target_str: &str, working_dir: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    compile_lib_with_options(target_str, working_dir, &BuildOptions::default())
+}
+
+/// Same as [compile_lib](compile_lib), but with additional build configuration.
+///
+/// Full output of CMake configure and build steps is written to
+/// [CONFIGURE_LOG_NAME](CONFIGURE_LOG_NAME) and [BUILD_LOG_NAME](BUILD_LOG_NAME) files in the working directory.
+/// On failure the error contains paths to these logs and the tail of the CMake error log, if any.
+pub fn compile_lib_with_options(
+    target_str: &str,
+    working_dir: &Path,
+    options: &BuildOptions,
+) -> Result<PathBuf, Box<dyn Error>> {
+    compile_lib_impl(target_str, working_dir, options).inspect_err(|err| {
+        if options.cargo_warnings {
+            emit_cargo_warnings(target_str, &err.to_string());
+        }
+    })
+}
+
+/// C flags of sanitizer and cross-language LTO `options`, **libpng** is compiled with them in addition to `CFLAGS`.
+///
+/// C code linked together with **libpng** should be compiled with them too,
+/// otherwise it would be left without sanitizer instrumentation or out of LLVM bitcode.
+pub fn c_flags(target_str: &str, options: &BuildOptions) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut c_flags = vec![];
+
+    if let Some(sanitizer) = options.sanitizer {
+        c_flags.append(&mut sanitizer.c_flags(target_str, c_compiler_is_clang())?);
+    }
+
+    if options.cross_language_lto {
+        c_flags.push(String::from(lto::LTO_C_FLAG));
+    }
+
+    Ok(c_flags)
+}
+
+/// Name of the file with CMake configure step output, see [compile_lib_with_options](compile_lib_with_options).
+pub const CONFIGURE_LOG_NAME: &str = "libpng-src-configure.log";
+
+/// Name of the file with CMake build step output, see [compile_lib_with_options](compile_lib_with_options).
+pub const BUILD_LOG_NAME: &str = "libpng-src-build.log";
+
+/// Number of trailing lines of the CMake error log included into the error message.
+const CMAKE_ERROR_LOG_TAIL_LINES: usize = 40;
+
+fn compile_lib_impl(
+    target_str: &str,
+    working_dir: &Path,
+    options: &BuildOptions,
+) -> Result<PathBuf, Box<dyn Error>> {
     if !allowed_targets_for_host().contains(&target_str) {
         return Err(format!(
             "Unsupported target: {target_str}, for host OS: {HOST_OS}, arch: {HOST_ARCH}"
@@ -192,14 +280,20 @@ pub fn compile_lib(target_str: &str, working_dir: &Path) -> Result<PathBuf, Box<
 
     let source_path = source_path();
 
-    let mut cmake_args = cmake_options(target_str)?;
+    let mut cmake_args = cmake_options(target_str, options)?;
     cmake_args.push(source_path.into_os_string());
 
-    execute("cmake", &cmake_args, working_dir)?;
+    execute("cmake", &cmake_args, working_dir, CONFIGURE_LOG_NAME).map_err(|err| {
+        match cmake_error_log_tail(working_dir) {
+            Some(tail) => format!("{err}\n{tail}").into(),
+            None => err,
+        }
+    })?;
     execute(
         "cmake",
         &["--build", ".", "--config", "Release"].map(OsString::from),
         working_dir,
+        BUILD_LOG_NAME,
     )?;
 
     artifact_path(working_dir)
@@ -255,13 +349,45 @@ fn androd_targets() -> Vec<&'static str> {
     ]
 }
 
-fn cmake_options(target_str: &str) -> Result<Vec<OsString>, Box<dyn Error>> {
+fn cmake_options(
+    target_str: &str,
+    build_options: &BuildOptions,
+) -> Result<Vec<OsString>, Box<dyn Error>> {
     let mut options = common_cmake_options();
     options.append(&mut target_specific_cmake_options(target_str)?);
 
+    if build_options.cross_language_lto {
+        options.append(&mut lto::lto_cmake_options(target_str, &c_compiler())?);
+    }
+
+    let c_flags = c_flags(target_str, build_options)?;
+
+    if !c_flags.is_empty() {
+        let mut all_flags: Vec<String> = var("CFLAGS").into_iter().collect();
+        all_flags.extend(c_flags);
+
+        options.push(OsString::from(format!(
+            "-DCMAKE_C_FLAGS={}",
+            all_flags.join(" ")
+        )));
+    }
+
     Ok(options)
 }
 
+/// The C compiler CMake would pick up: `CC` environment variable or the default `cc`.
+fn c_compiler() -> String {
+    var("CC").unwrap_or_else(|_| String::from("cc"))
+}
+
+fn c_compiler_is_clang() -> bool {
+    Command::new(c_compiler())
+        .arg("--version")
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).contains("clang"))
+        .unwrap_or(false)
+}
+
 fn common_cmake_options() -> Vec<OsString> {
     vec![
         OsString::from("-DPNG_SHARED=OFF"),
@@ -352,27 +478,96 @@ fn windows_specific_cmake_options() -> Result<Vec<OsString>, Box<dyn Error>> {
     Ok(vec![include_param, lib_param])
 }
 
-fn execute(command: &str, args: &[OsString], cwd: &Path) -> Result<(), Box<dyn Error>> {
-    let output = Command::new(command).current_dir(cwd).args(args).output()?;
+/// Executes the command and writes its complete stdout and stderr into `log_name` file in `cwd`.
+fn execute(
+    command: &str,
+    args: &[OsString],
+    cwd: &Path,
+    log_name: &str,
+) -> Result<(), Box<dyn Error>> {
+    let output = Command::new(command)
+        .current_dir(cwd)
+        .args(args)
+        .output()
+        .map_err(|err| {
+            format!(
+                "Cannot run command '{}' in {}: {err}",
+                command_line(command, args),
+                cwd.display()
+            )
+        })?;
+    let log_path = cwd.join(log_name);
+
+    write(&log_path, log_content(command, args, &output))?;
 
     if !output.status.success() {
         Err(format!(
-            "Command '{}' failed with status code {}\nError: {}",
-            command,
+            "Command '{}' failed with status code {}\nFull log: {}\nError: {}",
+            command_line(command, args),
             output.status.code().unwrap_or(-1),
+            log_path.display(),
             String::from_utf8_lossy(&output.stderr)
         ))?;
     }
 
+    println!(
+        "Executed '{}' successfully, log: {}",
+        command_line(command, args),
+        log_path.display()
+    );
+
+    Ok(())
+}
+
+fn command_line(command: &str, args: &[OsString]) -> String {
     let args_vec: Vec<&str> = args
         .iter()
         .map(|a| a.to_str().unwrap_or("!error!"))
         .collect();
 
-    println!("Executed '{} {}' successfully", command, args_vec.join(" "));
-    println!("{}", String::from_utf8_lossy(&output.stdout));
+    format!("{} {}", command, args_vec.join(" "))
+}
 
-    Ok(())
+fn log_content(command: &str, args: &[OsString], output: &Output) -> String {
+    format!(
+        "$ {}\nStatus code: {}\n\n--- stdout ---\n{}\n--- stderr ---\n{}",
+        command_line(command, args),
+        output.status.code().unwrap_or(-1),
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )
+}
+
+/// Returns the tail of the CMake error log with a header line, if the log exists.
+/// CMake 3.26+ replaced 'CMakeError.log' with 'CMakeConfigureLog.yaml'.
+fn cmake_error_log_tail(working_dir: &Path) -> Option<String> {
+    let log_path = ["CMakeError.log", "CMakeConfigureLog.yaml"]
+        .iter()
+        .map(|name| working_dir.join("CMakeFiles").join(name))
+        .find(|path| path.is_file())?;
+
+    let content = read_to_string(&log_path).ok()?;
+
+    Some(format!(
+        "Tail of {}:\n{}",
+        log_path.display(),
+        tail_lines(&content, CMAKE_ERROR_LOG_TAIL_LINES)
+    ))
+}
+
+fn tail_lines(text: &str, count: usize) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines.len().saturating_sub(count);
+
+    lines[start..].join("\n")
+}
+
+fn emit_cargo_warnings(target_str: &str, error_message: &str) {
+    println!("cargo:warning='libpng-src' failed to build libpng for target {target_str}");
+
+    for line in error_message.lines().filter(|line| !line.trim().is_empty()) {
+        println!("cargo:warning={line}");
+    }
 }
 
 fn artifact_path(working_dir: &Path) -> Result<PathBuf, Box<dyn Error>> {
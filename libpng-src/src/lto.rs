@@ -0,0 +1,137 @@
+use std::{env::var, error::Error, ffi::OsString, process::Command};
+
+/// Environment variable for explicit cross-language LTO selection (`1` or `0`),
+/// see [cross_language_lto_from_env](cross_language_lto_from_env).
+pub const CROSS_LANGUAGE_LTO_ENV_VAR: &str = "LIBPNG_SRC_CROSS_LANGUAGE_LTO";
+
+/// Detects in a build script environment whether **libpng** should be compiled to LLVM bitcode.
+///
+/// [CROSS_LANGUAGE_LTO_ENV_VAR](CROSS_LANGUAGE_LTO_ENV_VAR) takes precedence,
+/// otherwise checks if `-Clinker-plugin-lto` is present in `CARGO_ENCODED_RUSTFLAGS`.
+pub fn cross_language_lto_from_env() -> bool {
+    if let Ok(value) = var(CROSS_LANGUAGE_LTO_ENV_VAR) {
+        return value == "1" || value == "true";
+    }
+
+    var("CARGO_ENCODED_RUSTFLAGS")
+        .map(|flags| rustflags_request_linker_plugin_lto(&flags))
+        .unwrap_or(false)
+}
+
+/// `CARGO_ENCODED_RUSTFLAGS` are separated with `0x1f` character,
+/// codegen options may be passed both as `-Cname` and `-C name`.
+pub(crate) fn rustflags_request_linker_plugin_lto(encoded_flags: &str) -> bool {
+    let flags: Vec<&str> = encoded_flags.split('\x1f').collect();
+
+    flags.iter().enumerate().any(|(index, flag)| {
+        let option = match flag.strip_prefix("-C") {
+            Some("") => flags.get(index + 1).copied().unwrap_or_default(),
+            Some(option) => option,
+            None => flag.strip_prefix("--codegen=").unwrap_or_default(),
+        };
+
+        option == "linker-plugin-lto" || option.starts_with("linker-plugin-lto=")
+    })
+}
+
+/// C flag for compiling to ThinLTO bitcode.
+pub(crate) const LTO_C_FLAG: &str = "-flto=thin";
+
+/// Returns CMake options for compiling to ThinLTO bitcode archive with [LTO_C_FLAG].
+/// Requires Clang with the same LLVM major version as `rustc`.
+pub(crate) fn lto_cmake_options(
+    target_str: &str,
+    compiler: &str,
+) -> Result<Vec<OsString>, Box<dyn Error>> {
+    if !target_str.contains("linux-gnu") && !target_str.contains("apple") {
+        return Err(format!("Cross-language LTO is not supported for target: {target_str}").into());
+    }
+
+    let clang_version_output = command_stdout(compiler, "--version")?;
+
+    let clang_major = clang_major_version(&clang_version_output).ok_or_else(|| {
+        format!("Cross-language LTO requires Clang as C compiler, set CC=clang, got: {compiler}")
+    })?;
+
+    // `-flto=thin` in CMAKE_C_FLAGS applies to every target, so only the static library is built
+    let mut cmake_options = vec![
+        OsString::from("-DPNG_EXECUTABLES=OFF"),
+        OsString::from("-DPNG_TOOLS=OFF"),
+    ];
+
+    // Apple Clang has its own versioning, the system linker handles bitcode on its own
+    if !target_str.contains("apple") {
+        let rustc = var("RUSTC").unwrap_or_else(|_| String::from("rustc"));
+        let rustc_major = rustc_llvm_major_version(&command_stdout(&rustc, "-vV")?)
+            .ok_or("Cannot detect LLVM version of rustc")?;
+
+        if clang_major != rustc_major {
+            return Err(format!(
+                "Cross-language LTO requires matching LLVM versions, Clang: {clang_major}, rustc: {rustc_major}"
+            )
+            .into());
+        }
+
+        // GNU ar cannot index symbols of bitcode objects without the LTO plugin
+        let llvm_ar = find_tool(&[format!("llvm-ar-{clang_major}"), String::from("llvm-ar")])
+            .ok_or("Cross-language LTO requires 'llvm-ar' in PATH")?;
+        let llvm_ranlib = find_tool(&[
+            format!("llvm-ranlib-{clang_major}"),
+            String::from("llvm-ranlib"),
+        ])
+        .ok_or("Cross-language LTO requires 'llvm-ranlib' in PATH")?;
+
+        // Programs of CMake checks are linked from bitcode objects as well, which GNU ld cannot do
+        let lld = find_tool(&[format!("ld.lld-{clang_major}"), String::from("ld.lld")])
+            .ok_or("Cross-language LTO requires 'ld.lld' in PATH")?;
+        let mut linker_flags: Vec<String> = var("LDFLAGS").into_iter().collect();
+        linker_flags.push(format!("-fuse-ld={}", lld.trim_start_matches("ld.")));
+
+        cmake_options.push(OsString::from(format!("-DCMAKE_AR={llvm_ar}")));
+        cmake_options.push(OsString::from(format!("-DCMAKE_RANLIB={llvm_ranlib}")));
+        cmake_options.push(OsString::from(format!(
+            "-DCMAKE_EXE_LINKER_FLAGS={}",
+            linker_flags.join(" ")
+        )));
+    }
+
+    Ok(cmake_options)
+}
+
+pub(crate) fn clang_major_version(version_output: &str) -> Option<u32> {
+    let version = version_output
+        .lines()
+        .next()?
+        .split("clang version ")
+        .nth(1)?;
+
+    major_version(version)
+}
+
+pub(crate) fn rustc_llvm_major_version(verbose_version_output: &str) -> Option<u32> {
+    let version = verbose_version_output
+        .lines()
+        .find_map(|line| line.strip_prefix("LLVM version: "))?;
+
+    major_version(version)
+}
+
+fn major_version(version: &str) -> Option<u32> {
+    version.trim().split('.').next()?.parse().ok()
+}
+
+fn command_stdout(command: &str, arg: &str) -> Result<String, Box<dyn Error>> {
+    let output = Command::new(command)
+        .arg(arg)
+        .output()
+        .map_err(|err| format!("Cannot execute '{command} {arg}': {err}"))?;
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn find_tool(candidates: &[String]) -> Option<String> {
+    candidates
+        .iter()
+        .find(|tool| Command::new(tool).arg("--version").output().is_ok())
+        .cloned()
+}
@@ -0,0 +1,124 @@
+use std::{env::var, error::Error, fmt, str::FromStr};
+
+/// Environment variable for explicit sanitizer selection, see [Sanitizer::from_env](Sanitizer::from_env).
+pub const SANITIZER_ENV_VAR: &str = "LIBPNG_SRC_SANITIZER";
+
+/// Sanitizer instrumentation for compiled **libpng** code.
+///
+/// Supported combinations:
+/// - Linux (`*-unknown-linux-gnu`): [Address](Sanitizer::Address), [Undefined](Sanitizer::Undefined),
+///   [Memory](Sanitizer::Memory) (the last one requires Clang as C compiler).
+/// - macOS and iOS: [Address](Sanitizer::Address), [Undefined](Sanitizer::Undefined).
+///
+/// Windows and Android targets are not supported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sanitizer {
+    /// AddressSanitizer, matches `-Zsanitizer=address`.
+    Address,
+    /// UndefinedBehaviorSanitizer in trap mode, doesn't need a runtime library.
+    /// Has no `rustc` counterpart, select it via [SANITIZER_ENV_VAR](SANITIZER_ENV_VAR).
+    Undefined,
+    /// MemorySanitizer, matches `-Zsanitizer=memory`.
+    Memory,
+}
+
+impl Sanitizer {
+    /// Detects sanitizer in a build script environment.
+    ///
+    /// [SANITIZER_ENV_VAR](SANITIZER_ENV_VAR) takes precedence (`none` disables instrumentation),
+    /// otherwise the value is taken from `CARGO_CFG_SANITIZE`, set by Cargo for `-Zsanitizer=...` builds.
+    /// Sanitizers without C instrumentation counterpart (like `leak`) are ignored.
+    pub fn from_env() -> Result<Option<Self>, Box<dyn Error>> {
+        if let Ok(value) = var(SANITIZER_ENV_VAR) {
+            if value.is_empty() || value == "none" {
+                return Ok(None);
+            }
+
+            return Ok(Some(value.parse()?));
+        }
+
+        Ok(var("CARGO_CFG_SANITIZE")
+            .ok()
+            .and_then(|value| Self::from_cargo_cfg(&value)))
+    }
+
+    /// Picks the first supported sanitizer from comma-separated `CARGO_CFG_SANITIZE` value.
+    pub fn from_cargo_cfg(value: &str) -> Option<Self> {
+        value.split(',').map(str::trim).find_map(|name| match name {
+            "address" => Some(Self::Address),
+            "memory" => Some(Self::Memory),
+            _ => None,
+        })
+    }
+
+    /// Short name, as used by `-fsanitize=` and `-Zsanitizer=`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Address => "address",
+            Self::Undefined => "undefined",
+            Self::Memory => "memory",
+        }
+    }
+
+    pub(crate) fn c_flags(
+        &self,
+        target_str: &str,
+        compiler_is_clang: bool,
+    ) -> Result<Vec<String>, Box<dyn Error>> {
+        let is_linux = target_str.contains("linux-gnu");
+        let is_apple = target_str.contains("apple");
+
+        if !is_linux && !is_apple {
+            return Err(
+                format!("Sanitizer '{self}' is not supported for target: {target_str}").into(),
+            );
+        }
+
+        let flags = match self {
+            Self::Address => vec!["-fsanitize=address", "-fno-omit-frame-pointer"],
+            Self::Undefined if compiler_is_clang => {
+                vec!["-fsanitize=undefined", "-fsanitize-trap=undefined"]
+            }
+            Self::Undefined => vec!["-fsanitize=undefined", "-fsanitize-undefined-trap-on-error"],
+            Self::Memory => {
+                if !is_linux {
+                    return Err(format!(
+                        "Sanitizer '{self}' is supported for Linux targets only, got: {target_str}"
+                    )
+                    .into());
+                }
+
+                if !compiler_is_clang {
+                    return Err(format!("Sanitizer '{self}' requires Clang, set CC=clang").into());
+                }
+
+                vec![
+                    "-fsanitize=memory",
+                    "-fsanitize-memory-track-origins",
+                    "-fno-omit-frame-pointer",
+                ]
+            }
+        };
+
+        Ok(flags.into_iter().map(String::from).collect())
+    }
+}
+
+impl fmt::Display for Sanitizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Sanitizer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "address" | "asan" => Ok(Self::Address),
+            "undefined" | "ubsan" => Ok(Self::Undefined),
+            "memory" | "msan" => Ok(Self::Memory),
+            _ => Err(format!("Unknown sanitizer: {s}")),
+        }
+    }
+}
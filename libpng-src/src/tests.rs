@@ -1,6 +1,6 @@
 use std::{
     env::temp_dir,
-    fs::{copy, create_dir_all, remove_dir_all},
+    fs::{copy, create_dir_all, read_to_string, remove_dir_all},
 };
 
 use super::*;
@@ -11,21 +11,144 @@ fn test_source_path() {
     assert!(path.exists());
 }
 
+/// Empty temporary directory, unique for the test process.
+fn test_dir(name: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let dir = temp_dir().join(format!("libpng-src-test-{name}-{}", std::process::id()));
+
+    if dir.exists() {
+        remove_dir_all(&dir)?;
+    }
+    create_dir_all(&dir)?;
+
+    Ok(dir)
+}
+
 #[cfg(not(target_os = "windows"))]
 #[test]
 fn test_execute_command_ok() -> Result<(), Box<dyn Error>> {
-    execute("echo", &[OsString::from("test")], &temp_dir())
+    let tmp_dir = test_dir("execute-ok")?;
+
+    execute("echo", &[OsString::from("test")], &tmp_dir, "echo.log")?;
+
+    remove_dir_all(&tmp_dir)?;
+
+    Ok(())
 }
 
 #[cfg(target_os = "windows")]
 #[test]
 fn test_execute_command_ok() -> Result<(), Box<dyn Error>> {
-    execute("systeminfo", &[], &temp_dir())
+    let tmp_dir = test_dir("execute-ok")?;
+
+    execute("systeminfo", &[], &tmp_dir, "systeminfo.log")?;
+
+    remove_dir_all(&tmp_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_execute_command_fail() -> Result<(), Box<dyn Error>> {
+    let tmp_dir = test_dir("execute-fail")?;
+
+    assert!(execute(
+        "ls",
+        &[OsString::from("non-existent-dir")],
+        &tmp_dir,
+        "ls.log"
+    )
+    .is_err());
+
+    remove_dir_all(&tmp_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_execute_missing_command() -> Result<(), Box<dyn Error>> {
+    let tmp_dir = test_dir("execute-missing")?;
+
+    let err = execute(
+        "libpng-src-missing-command",
+        &[OsString::from("--version")],
+        &tmp_dir,
+        "missing.log",
+    )
+    .unwrap_err();
+
+    assert!(err
+        .to_string()
+        .contains("'libpng-src-missing-command --version'"));
+
+    remove_dir_all(&tmp_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_execute_writes_log() -> Result<(), Box<dyn Error>> {
+    let tmp_dir = test_dir("execute-logged")?;
+
+    execute("cargo", &[OsString::from("--version")], &tmp_dir, "ok.log")?;
+
+    let log = read_to_string(tmp_dir.join("ok.log"))?;
+    assert!(log.contains("$ cargo --version"));
+    assert!(log.contains("cargo "));
+
+    remove_dir_all(&tmp_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_execute_fail_contains_log_path() -> Result<(), Box<dyn Error>> {
+    let tmp_dir = test_dir("execute-logged-fail")?;
+
+    let err = execute(
+        "cargo",
+        &[OsString::from("non-existent-subcommand")],
+        &tmp_dir,
+        "fail.log",
+    )
+    .unwrap_err();
+
+    let log_path = tmp_dir.join("fail.log");
+    assert!(err.to_string().contains(&log_path.display().to_string()));
+    assert!(log_path.is_file());
+
+    remove_dir_all(&tmp_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_cmake_error_log_tail() -> Result<(), Box<dyn Error>> {
+    let tmp_dir = temp_dir().join("libpng-src-test-cmake-error-log");
+    create_dir_all(tmp_dir.join("CMakeFiles"))?;
+
+    assert!(cmake_error_log_tail(&tmp_dir).is_none());
+
+    let content: Vec<String> = (0..100).map(|i| format!("line {i}")).collect();
+    write(
+        tmp_dir.join("CMakeFiles/CMakeError.log"),
+        content.join("\n"),
+    )?;
+
+    let tail = cmake_error_log_tail(&tmp_dir).unwrap();
+    assert!(tail.contains("CMakeError.log"));
+    assert!(tail.contains("line 99"));
+    assert!(!tail.contains("line 59\n"));
+
+    remove_dir_all(&tmp_dir)?;
+
+    Ok(())
 }
 
 #[test]
-fn test_execute_command_fail() {
-    assert!(execute("ls", &[OsString::from("non-existent-dir")], &temp_dir()).is_err())
+fn test_tail_lines() {
+    assert_eq!(tail_lines("a\nb\nc", 2), "b\nc");
+    assert_eq!(tail_lines("a\nb\nc", 5), "a\nb\nc");
+    assert_eq!(tail_lines("", 5), "");
 }
 
 #[test]
@@ -59,13 +182,19 @@ fn test_native() -> Result<(), Box<dyn Error>> {
         )?;
     }
 
-    execute("cmake", &cmake_args, &tmp_dir)?;
+    execute("cmake", &cmake_args, &tmp_dir, "configure.log")?;
     execute(
         "cmake",
         &["--build", ".", "--config", "Debug"].map(OsString::from),
         &tmp_dir,
+        "build.log",
+    )?;
+    execute(
+        "ctest",
+        &["-C", "Debug"].map(OsString::from),
+        &tmp_dir,
+        "ctest.log",
     )?;
-    execute("ctest", &["-C", "Debug"].map(OsString::from), &tmp_dir)?;
 
     remove_dir_all(&tmp_dir)?;
 
@@ -100,3 +229,144 @@ fn test_link_name_win() {
     assert_combination("libpng_static.lib", "libpng_static");
     assert_combination("libpng16_static.lib", "libpng16_static");
 }
+
+#[test]
+fn test_sanitizer_from_cargo_cfg() {
+    assert_eq!(
+        Sanitizer::from_cargo_cfg("address"),
+        Some(Sanitizer::Address)
+    );
+    assert_eq!(Sanitizer::from_cargo_cfg("memory"), Some(Sanitizer::Memory));
+    assert_eq!(
+        Sanitizer::from_cargo_cfg("leak,address"),
+        Some(Sanitizer::Address)
+    );
+    assert_eq!(Sanitizer::from_cargo_cfg("leak"), None);
+    assert_eq!(Sanitizer::from_cargo_cfg(""), None);
+}
+
+#[test]
+fn test_sanitizer_from_str() {
+    assert_eq!("address".parse(), Ok(Sanitizer::Address));
+    assert_eq!("ubsan".parse(), Ok(Sanitizer::Undefined));
+    assert_eq!("msan".parse(), Ok(Sanitizer::Memory));
+    assert!("thread".parse::<Sanitizer>().is_err());
+}
+
+#[test]
+fn test_sanitizer_c_flags() {
+    let linux = "x86_64-unknown-linux-gnu";
+
+    let flags = Sanitizer::Address.c_flags(linux, false).unwrap();
+    assert!(flags.contains(&String::from("-fsanitize=address")));
+
+    let flags = Sanitizer::Undefined.c_flags(linux, false).unwrap();
+    assert!(flags.contains(&String::from("-fsanitize-undefined-trap-on-error")));
+
+    let flags = Sanitizer::Undefined.c_flags(linux, true).unwrap();
+    assert!(flags.contains(&String::from("-fsanitize-trap=undefined")));
+
+    assert!(Sanitizer::Memory.c_flags(linux, false).is_err());
+    assert!(Sanitizer::Memory.c_flags(linux, true).is_ok());
+    assert!(Sanitizer::Memory
+        .c_flags("aarch64-apple-darwin", true)
+        .is_err());
+    assert!(Sanitizer::Address
+        .c_flags("aarch64-apple-ios", true)
+        .is_ok());
+    assert!(Sanitizer::Address
+        .c_flags("aarch64-linux-android", true)
+        .is_err());
+    assert!(Sanitizer::Address
+        .c_flags("x86_64-pc-windows-msvc", false)
+        .is_err());
+}
+
+#[test]
+fn test_cmake_options_sanitizer() {
+    let options = BuildOptions {
+        sanitizer: Some(Sanitizer::Address),
+        ..BuildOptions::default()
+    };
+
+    let cmake_args = cmake_options("x86_64-unknown-linux-gnu", &options).unwrap();
+    let c_flags_arg = cmake_args
+        .iter()
+        .filter_map(|arg| arg.to_str())
+        .find(|arg| arg.starts_with("-DCMAKE_C_FLAGS="))
+        .unwrap();
+
+    assert!(c_flags_arg.contains("-fsanitize=address"));
+
+    let cmake_args = cmake_options("x86_64-unknown-linux-gnu", &BuildOptions::default()).unwrap();
+
+    assert!(!cmake_args
+        .iter()
+        .filter_map(|arg| arg.to_str())
+        .any(|arg| arg.starts_with("-DCMAKE_C_FLAGS=")));
+}
+
+#[test]
+fn test_rustflags_request_linker_plugin_lto() {
+    assert!(lto::rustflags_request_linker_plugin_lto(
+        "-Clinker-plugin-lto"
+    ));
+    assert!(lto::rustflags_request_linker_plugin_lto(
+        "-Copt-level=3\x1f-C\x1flinker-plugin-lto"
+    ));
+    assert!(lto::rustflags_request_linker_plugin_lto(
+        "--codegen=linker-plugin-lto=/path/to/plugin.so"
+    ));
+    assert!(!lto::rustflags_request_linker_plugin_lto("-Clto=thin"));
+    assert!(!lto::rustflags_request_linker_plugin_lto(""));
+}
+
+#[test]
+fn test_clang_major_version() {
+    assert_eq!(
+        lto::clang_major_version("clang version 18.1.8\nTarget: x86_64-pc-linux-gnu"),
+        Some(18)
+    );
+    assert_eq!(
+        lto::clang_major_version("Ubuntu clang version 14.0.0-1ubuntu1.1"),
+        Some(14)
+    );
+    assert_eq!(
+        lto::clang_major_version("Apple clang version 15.0.0 (clang-1500.3.9.4)"),
+        Some(15)
+    );
+    assert_eq!(
+        lto::clang_major_version("gcc (Debian 12.2.0-14) 12.2.0"),
+        None
+    );
+}
+
+#[test]
+fn test_rustc_llvm_major_version() {
+    let output = "rustc 1.80.0 (051478957 2024-07-21)\nbinary: rustc\nhost: x86_64-unknown-linux-gnu\nLLVM version: 18.1.7";
+
+    assert_eq!(lto::rustc_llvm_major_version(output), Some(18));
+    assert_eq!(lto::rustc_llvm_major_version("rustc 1.80.0"), None);
+}
+
+#[test]
+fn test_c_flags() {
+    let linux = "x86_64-unknown-linux-gnu";
+
+    assert!(c_flags(linux, &BuildOptions::default()).unwrap().is_empty());
+
+    let options = BuildOptions {
+        sanitizer: Some(Sanitizer::Address),
+        cross_language_lto: true,
+        ..BuildOptions::default()
+    };
+    let flags = c_flags(linux, &options).unwrap();
+
+    assert!(flags.contains(&String::from("-fsanitize=address")));
+    assert!(flags.contains(&String::from("-flto=thin")));
+}
+
+#[test]
+fn test_lto_unsupported_target() {
+    assert!(lto::lto_cmake_options("x86_64-pc-windows-msvc", "cl").is_err());
+}
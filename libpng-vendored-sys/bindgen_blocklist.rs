@@ -0,0 +1,19 @@
+// Shared by 'build.rs' and 'bindgen-helper', included via `include!`.
+// Items provided by the `libc` crate or platform-specific internals of them.
+const BINDGEN_BLOCKLIST: &[&str] = &[
+    "FILE",
+    "time_t",
+    "tm",
+    "fpos_t",
+    "__sFILE",
+    "__sFILEX",
+    "__sbuf",
+    "__darwin_time_t",
+    "__darwin_off_t",
+    "__int64_t",
+    // Platform-specific, replaced with opaque definitions in 'src/bindings/mod.rs'
+    "jmp_buf",
+    "__jmp_buf_tag",
+    "_JBTYPE",
+    "png_longjmp_ptr",
+];
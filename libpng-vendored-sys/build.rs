@@ -1,16 +1,106 @@
-use std::{env::var, path::PathBuf};
+use std::{
+    env::var,
+    path::{Path, PathBuf},
+};
 
-use libpng_src::build_artifact;
+use libpng_src::{
+    build_artifact_with_options, c_flags, cross_language_lto_from_env, BuildOptions, Sanitizer,
+    CROSS_LANGUAGE_LTO_ENV_VAR, SANITIZER_ENV_VAR,
+};
 
 fn main() {
     let target = var("TARGET").unwrap();
     let out_dir = var("OUT_DIR").map(PathBuf::from).unwrap();
 
-    let artifact_info = build_artifact(&target, &out_dir).unwrap();
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed={SANITIZER_ENV_VAR}");
+    println!("cargo:rerun-if-env-changed={CROSS_LANGUAGE_LTO_ENV_VAR}");
+    println!("cargo:rustc-check-cfg=cfg(libpng_sanitize, values(\"address\", \"undefined\", \"memory\"))");
+    println!("cargo:rustc-check-cfg=cfg(libpng_cross_language_lto)");
+
+    let options = BuildOptions {
+        cargo_warnings: true,
+        sanitizer: Sanitizer::from_env().unwrap(),
+        cross_language_lto: cross_language_lto_from_env(),
+    };
+
+    if let Some(sanitizer) = options.sanitizer {
+        println!("cargo:rustc-cfg=libpng_sanitize=\"{sanitizer}\"");
+    }
+
+    if options.cross_language_lto {
+        println!("cargo:rustc-cfg=libpng_cross_language_lto");
+    }
+
+    let artifact_info = build_artifact_with_options(&target, &out_dir, &options).unwrap();
+
+    println!(
+        "cargo:rustc-env=LIBPNG_VENDORED_SYS_LIB_DIR={}",
+        artifact_info.lib_dir.to_string_lossy()
+    );
 
     println!(
         "cargo:rustc-link-search=native={}",
         artifact_info.lib_dir.to_string_lossy()
     );
     println!("cargo:rustc-link-lib=static={}", artifact_info.link_name);
+
+    // `DEP_PNG_INCLUDE` for build scripts of dependents
+    println!(
+        "cargo:include={}",
+        artifact_info.include_dir.to_string_lossy()
+    );
+
+    compile_shim(
+        &artifact_info.include_dir,
+        &c_flags(&target, &options).unwrap(),
+    );
+
+    #[cfg(feature = "bindgen")]
+    generate_bindings(&artifact_info.include_dir, &out_dir);
+}
+
+/// C helpers linked into the library, see 'shim/png_rs_shim.c'.
+/// Compiled with sanitizer and LTO flags of **libpng**, as it copies its buffers and is linked together with it.
+fn compile_shim(include_dir: &Path, c_flags: &[String]) {
+    println!("cargo:rerun-if-changed=shim");
+
+    let mut build = cc::Build::new();
+
+    for flag in c_flags {
+        build.flag(flag);
+    }
+
+    build
+        .file("shim/png_rs_shim.c")
+        .include("shim")
+        .include(include_dir)
+        .compile("png_rs_shim");
+}
+
+#[cfg(feature = "bindgen")]
+include!("bindgen_blocklist.rs");
+
+/// Generates bindings for the actual target, included by 'src/lib.rs' instead of the pre-generated ones.
+/// bindgen passes the target of the build script to clang itself. Layout tests are kept,
+/// like in the pre-generated bindings of 'bindgen-helper'.
+#[cfg(feature = "bindgen")]
+fn generate_bindings(include_dir: &Path, out_dir: &Path) {
+    println!("cargo:rerun-if-changed=bindgen_blocklist.rs");
+
+    let png_h_path = include_dir.join("png.h");
+
+    let builder = bindgen::builder()
+        .header(png_h_path.to_string_lossy())
+        .allowlist_file(png_h_path.to_string_lossy())
+        .layout_tests(true)
+        .clang_arg(format!("-I{}", include_dir.to_string_lossy()));
+
+    BINDGEN_BLOCKLIST
+        .iter()
+        .fold(builder, |builder, item| builder.blocklist_item(*item))
+        .generate()
+        .unwrap()
+        .write_to_file(out_dir.join("bindings.rs"))
+        .unwrap();
 }
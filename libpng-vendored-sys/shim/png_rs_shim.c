@@ -0,0 +1,204 @@
+/* Small C helpers for 'libpng-vendored-sys', compiled alongside libpng. */
+
+#include <setjmp.h>
+#include <stddef.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "png.h"
+#include "png_rs_shim.h"
+
+/* Size of the platform 'jmp_buf', used by 'png_jmpbuf' Rust translation. */
+size_t png_rs_jmp_buf_size(void)
+{
+   return sizeof(jmp_buf);
+}
+
+/* Stores the message in the error context and jumps back to 'png_rs_try'.
+ * Without an active 'png_rs_try' the jump buffer is stale or unset, so the process is aborted instead. */
+void png_rs_error_fn(png_structp png_ptr, png_const_charp message)
+{
+   png_rs_error_context *context = (png_rs_error_context *)png_get_error_ptr(png_ptr);
+
+   if (context == NULL || context->depth <= 0)
+      abort();
+
+   strncpy(context->message, message != NULL ? message : "", PNG_RS_MESSAGE_SIZE - 1);
+   context->message[PNG_RS_MESSAGE_SIZE - 1] = '\0';
+
+   png_longjmp(png_ptr, 1);
+}
+
+/* Forwards the warning to the context callback, if any. */
+void png_rs_warning_fn(png_structp png_ptr, png_const_charp message)
+{
+   png_rs_error_context *context = (png_rs_error_context *)png_get_error_ptr(png_ptr);
+
+   if (context != NULL && context->warning != NULL)
+      context->warning(context->user_data, message != NULL ? message : "");
+}
+
+/* libpng jumps to its own buffer on errors while the struct is created, counted as a try. */
+png_structp png_rs_create_read_struct(png_rs_error_context *context)
+{
+   png_structp png_ptr;
+
+   if (context == NULL)
+      return NULL;
+
+   context->depth++;
+   png_ptr = png_create_read_struct(PNG_LIBPNG_VER_STRING, context, png_rs_error_fn,
+      png_rs_warning_fn);
+   context->depth--;
+
+   return png_ptr;
+}
+
+png_structp png_rs_create_write_struct(png_rs_error_context *context)
+{
+   png_structp png_ptr;
+
+   if (context == NULL)
+      return NULL;
+
+   context->depth++;
+   png_ptr = png_create_write_struct(PNG_LIBPNG_VER_STRING, context, png_rs_error_fn,
+      png_rs_warning_fn);
+   context->depth--;
+
+   return png_ptr;
+}
+
+static void png_rs_read_data(png_structp png_ptr, png_bytep data, size_t length)
+{
+   png_rs_io *io = (png_rs_io *)png_get_io_ptr(png_ptr);
+
+   if (io == NULL || io->read == NULL || io->read(io->user_data, data, length) != PNG_RS_OK)
+      png_error(png_ptr, "Read error");
+}
+
+/* Installs a read function calling 'io->read', which never jumps over Rust frames itself. */
+void png_rs_set_read_fn(png_structrp png_ptr, png_rs_io *io)
+{
+   png_set_read_fn(png_ptr, io, png_rs_read_data);
+}
+
+static void png_rs_write_data(png_structp png_ptr, png_bytep data, size_t length)
+{
+   png_rs_io *io = (png_rs_io *)png_get_io_ptr(png_ptr);
+
+   if (io == NULL || io->write == NULL || io->write(io->user_data, data, length) != PNG_RS_OK)
+      png_error(png_ptr, "Write error");
+}
+
+static void png_rs_flush_data(png_structp png_ptr)
+{
+   png_rs_io *io = (png_rs_io *)png_get_io_ptr(png_ptr);
+
+   if (io != NULL && io->flush != NULL && io->flush(io->user_data) != PNG_RS_OK)
+      png_error(png_ptr, "Flush error");
+}
+
+/* Installs write and flush functions calling 'io->write' and 'io->flush'.
+ * The flush function is always set, since the default one treats the I/O pointer as 'FILE *'. */
+void png_rs_set_write_fn(png_structrp png_ptr, png_rs_io *io)
+{
+   png_set_write_fn(png_ptr, io, png_rs_write_data, png_rs_flush_data);
+}
+
+static void png_rs_progressive_status(png_structp png_ptr, int status)
+{
+   if (status != PNG_RS_OK)
+      png_error(png_ptr, "Progressive callback error");
+}
+
+/* Pausing here is safe, libpng is between chunks. */
+static void png_rs_progressive_info(png_structp png_ptr, png_infop info_ptr)
+{
+   png_rs_progressive *progressive = (png_rs_progressive *)png_get_progressive_ptr(png_ptr);
+   int status;
+
+   (void)info_ptr;
+
+   if (progressive == NULL || progressive->info == NULL)
+      return;
+
+   status = progressive->info(progressive->user_data);
+
+   if (status == PNG_RS_PAUSE)
+      progressive->remaining = png_process_data_pause(png_ptr, 0);
+   else
+      png_rs_progressive_status(png_ptr, status);
+}
+
+/* Combines the new row into the row set by 'info', 'row' gets NULL if the pass has no pixels in the row. */
+static void png_rs_progressive_row(png_structp png_ptr, png_bytep new_row, png_uint_32 row_num,
+   int pass)
+{
+   png_rs_progressive *progressive = (png_rs_progressive *)png_get_progressive_ptr(png_ptr);
+   png_bytep row = NULL;
+
+   if (progressive == NULL)
+      return;
+
+   if (new_row != NULL)
+   {
+      if (progressive->rows == NULL)
+         png_error(png_ptr, "Progressive rows are not set");
+
+      row = progressive->rows + (size_t)row_num * progressive->row_stride;
+      png_progressive_combine_row(png_ptr, row, new_row);
+   }
+
+   if (progressive->row != NULL)
+      png_rs_progressive_status(png_ptr, progressive->row(progressive->user_data, row, row_num, pass));
+}
+
+static void png_rs_progressive_end(png_structp png_ptr, png_infop info_ptr)
+{
+   png_rs_progressive *progressive = (png_rs_progressive *)png_get_progressive_ptr(png_ptr);
+
+   (void)info_ptr;
+
+   if (progressive != NULL && progressive->end != NULL)
+      png_rs_progressive_status(png_ptr, progressive->end(progressive->user_data));
+}
+
+/* Installs progressive callbacks calling 'progressive' ones, which never jump over Rust frames themselves. */
+void png_rs_set_progressive_read_fn(png_structrp png_ptr, png_rs_progressive *progressive)
+{
+   png_set_progressive_read_fn(png_ptr, progressive, png_rs_progressive_info, png_rs_progressive_row,
+      png_rs_progressive_end);
+}
+
+/* Calls 'callback' inside 'setjmp', returns PNG_RS_ERROR if it was interrupted by 'png_error'.
+ * libpng keeps a single jump buffer, it's saved and restored, so calls may be nested inside callbacks.
+ * The nesting depth is kept in the error context for 'png_rs_error_fn'.
+ * Returns PNG_RS_ERROR right away on NULL arguments or error pointer, which must be a 'png_rs_error_context'. */
+int png_rs_try(png_structrp png_ptr, png_rs_callback callback, void *data)
+{
+   jmp_buf saved;
+   png_rs_error_context *context;
+   int status = PNG_RS_OK;
+
+   if (png_ptr == NULL || callback == NULL)
+      return PNG_RS_ERROR;
+
+   context = (png_rs_error_context *)png_get_error_ptr(png_ptr);
+
+   if (context == NULL)
+      return PNG_RS_ERROR;
+
+   memcpy(saved, png_jmpbuf(png_ptr), sizeof(jmp_buf));
+   context->depth++;
+
+   if (setjmp(png_jmpbuf(png_ptr)))
+      status = PNG_RS_ERROR;
+   else
+      callback(data);
+
+   context->depth--;
+   memcpy(png_jmpbuf(png_ptr), saved, sizeof(jmp_buf));
+
+   return status;
+}
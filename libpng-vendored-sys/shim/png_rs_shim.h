@@ -0,0 +1,77 @@
+/* Declarations for 'png_rs_shim.c', mirrored in 'src/error_shim.rs'. */
+
+#ifndef PNG_RS_SHIM_H
+#define PNG_RS_SHIM_H
+
+#include <stddef.h>
+
+#include "png.h"
+
+#define PNG_RS_OK 0
+#define PNG_RS_ERROR 1
+#define PNG_RS_PAUSE 2
+
+#define PNG_RS_MESSAGE_SIZE 256
+
+typedef void (*png_rs_callback)(void *data);
+typedef int (*png_rs_read_callback)(void *user_data, png_bytep data, size_t length);
+typedef int (*png_rs_write_callback)(void *user_data, png_const_bytep data, size_t length);
+typedef int (*png_rs_flush_callback)(void *user_data);
+typedef void (*png_rs_warning_callback)(void *user_data, png_const_charp message);
+typedef int (*png_rs_info_callback)(void *user_data);
+typedef int (*png_rs_row_callback)(void *user_data, png_bytep row, png_uint_32 row_num, int pass);
+typedef int (*png_rs_end_callback)(void *user_data);
+
+/* Passed as libpng error pointer, must outlive the png struct.
+ * 'depth' counts active 'png_rs_try' calls, errors outside of them abort the process. */
+typedef struct png_rs_error_context
+{
+   char message[PNG_RS_MESSAGE_SIZE];
+   png_rs_warning_callback warning;
+   void *user_data;
+   int depth;
+} png_rs_error_context;
+
+/* Passed as libpng I/O pointer, must outlive the png struct.
+ * Callbacks return PNG_RS_OK or PNG_RS_ERROR, the latter is converted into 'png_error'. */
+typedef struct png_rs_io
+{
+   png_rs_read_callback read;
+   png_rs_write_callback write;
+   png_rs_flush_callback flush;
+   void *user_data;
+} png_rs_io;
+
+/* Passed as libpng progressive pointer, must outlive the png struct.
+ * Callbacks return PNG_RS_OK or PNG_RS_ERROR for 'png_error'. 'info' may return PNG_RS_PAUSE
+ * for 'png_process_data_pause', storing the number of unprocessed input bytes in 'remaining'.
+ * Other callbacks cannot pause, libpng 1.6 processes pending image data twice then.
+ * 'info' must start reading rows with 'png_read_update_info', nesting 'png_rs_try', and set 'rows' and 'row_stride'.
+ * Rows are combined into 'rows + row_num * row_stride' before 'row' is called.
+ * 'row_stride' of 0 reuses a single row, enough for non-interlaced images. */
+typedef struct png_rs_progressive
+{
+   png_rs_info_callback info;
+   png_rs_row_callback row;
+   png_rs_end_callback end;
+   png_bytep rows;
+   size_t row_stride;
+   size_t remaining;
+   void *user_data;
+} png_rs_progressive;
+
+size_t png_rs_jmp_buf_size(void);
+
+void png_rs_error_fn(png_structp png_ptr, png_const_charp message);
+void png_rs_warning_fn(png_structp png_ptr, png_const_charp message);
+
+png_structp png_rs_create_read_struct(png_rs_error_context *context);
+png_structp png_rs_create_write_struct(png_rs_error_context *context);
+
+void png_rs_set_read_fn(png_structrp png_ptr, png_rs_io *io);
+void png_rs_set_write_fn(png_structrp png_ptr, png_rs_io *io);
+void png_rs_set_progressive_read_fn(png_structrp png_ptr, png_rs_progressive *progressive);
+
+int png_rs_try(png_structrp png_ptr, png_rs_callback callback, void *data);
+
+#endif /* PNG_RS_SHIM_H */
@@ -0,0 +1,29 @@
+//! Pre-generated bindings are selected by target pointer width and OS family,
+//! see 'bindgen-helper' for the families and representative targets.
+
+use libc::{c_int, time_t, tm, FILE};
+
+/// Opaque C `jmp_buf`, its layout is platform-specific and it is used only behind a pointer.
+#[repr(C)]
+pub struct jmp_buf {
+    _unused: [u8; 0],
+}
+
+pub type png_longjmp_ptr = Option<unsafe extern "C" fn(env: *mut jmp_buf, val: c_int)>;
+
+#[cfg(all(not(feature = "bindgen"), unix, target_pointer_width = "64"))]
+include!("unix_64.rs");
+
+#[cfg(all(not(feature = "bindgen"), unix, target_pointer_width = "32"))]
+include!("unix_32.rs");
+
+// `c_long`, `time_t` and `jmp_buf` differ on Windows, bindings must be generated for the actual target
+#[cfg(all(not(feature = "bindgen"), windows))]
+compile_error!("No pre-generated bindings for Windows targets, enable 'bindgen' feature");
+
+// 'libpng-src' does not build libpng for wasm32, `libc` lacks `FILE` and `time_t` for wasm32-unknown-unknown
+#[cfg(target_family = "wasm")]
+compile_error!("wasm32 targets are not supported");
+
+#[cfg(feature = "bindgen")]
+include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
@@ -1,45 +1,248 @@
+//! Manual translations of **libpng** function-like macros and casted constants, dropped by bindgen.
+//! Assume default 'pnglibconf.h' configuration, the same as used for building the vendored library.
 #![allow(non_snake_case)]
+use libc::c_int;
+
 use crate::{
-    png_image, PNG_FORMAT_FLAG_ALPHA, PNG_FORMAT_FLAG_COLOR, PNG_FORMAT_FLAG_COLORMAP,
-    PNG_FORMAT_FLAG_LINEAR,
+    jmp_buf, png_alloc_size_t, png_byte, png_fixed_point, png_image, png_int_32,
+    png_set_longjmp_fn, png_structrp, png_uint_16, png_uint_32, PNG_FORMAT_FLAG_ALPHA,
+    PNG_FORMAT_FLAG_COLOR, PNG_FORMAT_FLAG_COLORMAP, PNG_FORMAT_FLAG_LINEAR,
 };
 
-pub fn PNG_IMAGE_FAILED(png_cntrl: &png_image) -> bool {
+pub const PNG_UINT_31_MAX: png_uint_32 = 0x7fffffff;
+pub const PNG_UINT_32_MAX: png_uint_32 = png_uint_32::MAX;
+pub const PNG_SIZE_MAX: usize = usize::MAX;
+pub const PNG_FP_MAX: png_fixed_point = 0x7fffffff;
+pub const PNG_FP_MIN: png_fixed_point = -PNG_FP_MAX;
+/// From 'pnglibconf.h'.
+pub const PNG_ZBUF_SIZE: usize = 8192;
+
+// Simplified API
+
+pub const fn PNG_IMAGE_FAILED(png_cntrl: &png_image) -> bool {
     (png_cntrl.warning_or_error & 0x03) > 1
 }
 
-pub fn PNG_IMAGE_SAMPLE_CHANNELS(fmt: u32) -> usize {
+pub const fn PNG_IMAGE_SAMPLE_CHANNELS(fmt: u32) -> usize {
     ((fmt & (PNG_FORMAT_FLAG_COLOR | PNG_FORMAT_FLAG_ALPHA)) + 1) as usize
 }
 
-pub fn PNG_IMAGE_SAMPLE_COMPONENT_SIZE(fmt: u32) -> usize {
+pub const fn PNG_IMAGE_SAMPLE_COMPONENT_SIZE(fmt: u32) -> usize {
     (((fmt & PNG_FORMAT_FLAG_LINEAR) >> 2) + 1) as usize
 }
 
-pub fn PNG_IMAGE_PIXEL_(test: impl FnOnce(u32) -> usize, fmt: u32) -> usize {
+pub const fn PNG_IMAGE_SAMPLE_SIZE(fmt: u32) -> usize {
+    PNG_IMAGE_SAMPLE_CHANNELS(fmt) * PNG_IMAGE_SAMPLE_COMPONENT_SIZE(fmt)
+}
+
+pub const fn PNG_IMAGE_MAXIMUM_COLORMAP_COMPONENTS(fmt: u32) -> usize {
+    PNG_IMAGE_SAMPLE_CHANNELS(fmt) * 256
+}
+
+/// Takes `test(fmt)` already evaluated, since function pointers cannot be called in `const fn`.
+pub const fn PNG_IMAGE_PIXEL_(test: usize, fmt: u32) -> usize {
     if (fmt & PNG_FORMAT_FLAG_COLORMAP) > 0 {
         1
     } else {
-        test(fmt)
+        test
     }
 }
 
-pub fn PNG_IMAGE_PIXEL_CHANNELS(fmt: u32) -> usize {
-    PNG_IMAGE_PIXEL_(PNG_IMAGE_SAMPLE_CHANNELS, fmt)
+pub const fn PNG_IMAGE_PIXEL_CHANNELS(fmt: u32) -> usize {
+    PNG_IMAGE_PIXEL_(PNG_IMAGE_SAMPLE_CHANNELS(fmt), fmt)
+}
+
+pub const fn PNG_IMAGE_PIXEL_COMPONENT_SIZE(fmt: u32) -> usize {
+    PNG_IMAGE_PIXEL_(PNG_IMAGE_SAMPLE_COMPONENT_SIZE(fmt), fmt)
 }
 
-pub fn PNG_IMAGE_PIXEL_COMPONENT_SIZE(fmt: u32) -> usize {
-    PNG_IMAGE_PIXEL_(PNG_IMAGE_SAMPLE_COMPONENT_SIZE, fmt)
+pub const fn PNG_IMAGE_PIXEL_SIZE(fmt: u32) -> usize {
+    PNG_IMAGE_PIXEL_(PNG_IMAGE_SAMPLE_SIZE(fmt), fmt)
 }
 
-pub fn PNG_IMAGE_ROW_STRIDE(image: &png_image) -> usize {
+pub const fn PNG_IMAGE_ROW_STRIDE(image: &png_image) -> usize {
     PNG_IMAGE_PIXEL_CHANNELS(image.format) * (image.width as usize)
 }
 
-pub fn PNG_IMAGE_BUFFER_SIZE(image: &png_image, row_stride: usize) -> usize {
+pub const fn PNG_IMAGE_BUFFER_SIZE(image: &png_image, row_stride: usize) -> usize {
     PNG_IMAGE_PIXEL_COMPONENT_SIZE(image.format) * (image.height as usize) * row_stride
 }
 
-pub fn PNG_IMAGE_SIZE(image: &png_image) -> usize {
+pub const fn PNG_IMAGE_SIZE(image: &png_image) -> usize {
     PNG_IMAGE_BUFFER_SIZE(image, PNG_IMAGE_ROW_STRIDE(image))
 }
+
+pub const fn PNG_IMAGE_COLORMAP_SIZE(image: &png_image) -> usize {
+    PNG_IMAGE_SAMPLE_SIZE(image.format) * (image.colormap_entries as usize)
+}
+
+pub const fn PNG_IMAGE_DATA_SIZE(image: &png_image) -> usize {
+    PNG_IMAGE_SIZE(image) + (image.height as usize)
+}
+
+pub const fn PNG_ZLIB_MAX_SIZE(b: png_alloc_size_t) -> png_alloc_size_t {
+    // Unsigned arithmetic of the C macro wraps around
+    b.wrapping_add(b.wrapping_add(7) >> 3)
+        .wrapping_add(b.wrapping_add(63) >> 6)
+        .wrapping_add(11)
+}
+
+pub const fn PNG_IMAGE_COMPRESSED_SIZE_MAX(image: &png_image) -> png_alloc_size_t {
+    PNG_ZLIB_MAX_SIZE(PNG_IMAGE_DATA_SIZE(image))
+}
+
+pub const fn PNG_IMAGE_PNG_SIZE_MAX_(image: &png_image, image_size: usize) -> png_alloc_size_t {
+    let colormap_entries = image.colormap_entries as usize;
+
+    let colormap_size = if (image.format & PNG_FORMAT_FLAG_COLORMAP) > 0 {
+        let trns_size = if (image.format & PNG_FORMAT_FLAG_ALPHA) > 0 {
+            12 + colormap_entries
+        } else {
+            0
+        };
+
+        12 + 3 * colormap_entries + trns_size
+    } else {
+        0
+    };
+
+    (8 + 25 + 16 + 44 + 12 + colormap_size + 12) + (12 * (image_size / PNG_ZBUF_SIZE)) + image_size
+}
+
+pub const fn PNG_IMAGE_PNG_SIZE_MAX(image: &png_image) -> png_alloc_size_t {
+    PNG_IMAGE_PNG_SIZE_MAX_(image, PNG_IMAGE_COMPRESSED_SIZE_MAX(image))
+}
+
+// Interlace passes
+
+pub const fn PNG_PASS_START_ROW(pass: u32) -> u32 {
+    ((1 & !pass) << (3 - (pass >> 1))) & 7
+}
+
+pub const fn PNG_PASS_START_COL(pass: u32) -> u32 {
+    ((1 & pass) << (3 - ((pass + 1) >> 1))) & 7
+}
+
+pub const fn PNG_PASS_ROW_OFFSET(pass: u32) -> u32 {
+    if pass > 2 {
+        8 >> ((pass - 1) >> 1)
+    } else {
+        8
+    }
+}
+
+pub const fn PNG_PASS_COL_OFFSET(pass: u32) -> u32 {
+    1 << ((7 - pass) >> 1)
+}
+
+pub const fn PNG_PASS_ROW_SHIFT(pass: u32) -> u32 {
+    if pass > 2 {
+        (8 - pass) >> 1
+    } else {
+        3
+    }
+}
+
+pub const fn PNG_PASS_COL_SHIFT(pass: u32) -> u32 {
+    if pass > 1 {
+        (7 - pass) >> 1
+    } else {
+        3
+    }
+}
+
+// Unsigned arithmetic of the C macros below wraps around
+
+pub const fn PNG_PASS_ROWS(height: u32, pass: u32) -> u32 {
+    height
+        .wrapping_add((1 << PNG_PASS_ROW_SHIFT(pass)) - 1)
+        .wrapping_sub(PNG_PASS_START_ROW(pass))
+        >> PNG_PASS_ROW_SHIFT(pass)
+}
+
+pub const fn PNG_PASS_COLS(width: u32, pass: u32) -> u32 {
+    width
+        .wrapping_add((1 << PNG_PASS_COL_SHIFT(pass)) - 1)
+        .wrapping_sub(PNG_PASS_START_COL(pass))
+        >> PNG_PASS_COL_SHIFT(pass)
+}
+
+pub const fn PNG_ROW_FROM_PASS_ROW(y_in: u32, pass: u32) -> u32 {
+    (y_in << PNG_PASS_ROW_SHIFT(pass)).wrapping_add(PNG_PASS_START_ROW(pass))
+}
+
+pub const fn PNG_COL_FROM_PASS_COL(x_in: u32, pass: u32) -> u32 {
+    (x_in << PNG_PASS_COL_SHIFT(pass)).wrapping_add(PNG_PASS_START_COL(pass))
+}
+
+pub const fn PNG_PASS_MASK(pass: u32, off: u32) -> u32 {
+    ((0x110145AF >> (((7 - off) - pass) << 2)) & 0xF)
+        | ((0x01145AF0 >> (((7 - off) - pass) << 2)) & 0xF0)
+}
+
+pub const fn PNG_ROW_IN_INTERLACE_PASS(y: u32, pass: u32) -> bool {
+    ((PNG_PASS_MASK(pass, 0) >> (y & 7)) & 1) == 1
+}
+
+pub const fn PNG_COL_IN_INTERLACE_PASS(x: u32, pass: u32) -> bool {
+    ((PNG_PASS_MASK(pass, 1) >> (x & 7)) & 1) == 1
+}
+
+/// From 'pngpriv.h', number of bytes for `width` pixels of `pixel_bits` size.
+pub const fn PNG_ROWBYTES(pixel_bits: u32, width: u32) -> usize {
+    if pixel_bits >= 8 {
+        (width as usize) * ((pixel_bits as usize) >> 3)
+    } else {
+        ((width as usize) * (pixel_bits as usize) + 7) >> 3
+    }
+}
+
+// Alpha compositing, 'no division' variant as `PNG_READ_COMPOSITE_NODIV_SUPPORTED` is on by default
+
+pub const fn png_composite(fg: png_byte, alpha: png_byte, bg: png_byte) -> png_byte {
+    let temp = (fg as u32) * (alpha as u32) + (bg as u32) * (255 - alpha as u32) + 128;
+
+    (((temp + (temp >> 8)) >> 8) & 0xff) as png_byte
+}
+
+pub const fn png_composite_16(fg: png_uint_16, alpha: png_uint_16, bg: png_uint_16) -> png_uint_16 {
+    let temp = (fg as u32) * (alpha as u32) + (bg as u32) * (65535 - alpha as u32) + 32768;
+
+    (0xffff & ((temp + (temp >> 16)) >> 16)) as png_uint_16
+}
+
+// Integer reading, `PNG_USE_READ_MACROS` variants
+
+pub const fn PNG_get_uint_32(buf: &[png_byte; 4]) -> png_uint_32 {
+    png_uint_32::from_be_bytes(*buf)
+}
+
+pub const fn PNG_get_uint_16(buf: &[png_byte; 2]) -> png_uint_16 {
+    png_uint_16::from_be_bytes(*buf)
+}
+
+pub const fn PNG_get_int_32(buf: &[png_byte; 4]) -> png_int_32 {
+    let value = PNG_get_uint_32(buf);
+
+    if (buf[0] & 0x80) != 0 {
+        -((((value ^ 0xffffffff).wrapping_add(1)) & 0x7fffffff) as png_int_32)
+    } else {
+        value as png_int_32
+    }
+}
+
+// Error handling
+
+extern "C" {
+    fn longjmp(env: *mut jmp_buf, val: c_int);
+    fn png_rs_jmp_buf_size() -> usize;
+}
+
+/// Returns the `jmp_buf` of `png_ptr` for `setjmp`, or null on `jmp_buf` size mismatch.
+///
+/// # Safety
+/// `png_ptr` must be a valid pointer to a **libpng** read or write structure.
+/// `setjmp` cannot be called from Rust, so the result is usable only from C code.
+pub unsafe fn png_jmpbuf(png_ptr: png_structrp) -> *mut jmp_buf {
+    png_set_longjmp_fn(png_ptr, Some(longjmp), png_rs_jmp_buf_size())
+}
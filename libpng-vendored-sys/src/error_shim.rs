@@ -0,0 +1,156 @@
+//! Bindings for the C trampoline from 'shim/png_rs_shim.c', compiled alongside **libpng**.
+//!
+//! **libpng** reports fatal errors with `longjmp` to the `setjmp` point of the caller.
+//! `setjmp` cannot be called from Rust, so [png_rs_try] calls a callback inside `setjmp` on the C side
+//! and converts `png_error` into [PNG_RS_ERROR] return code with the message stored in [png_rs_error_context].
+//!
+//! Frames of the callback are skipped by `longjmp` on error, so they must not own values with destructors.
+//! Rust callbacks passed to **libpng** itself must return normally and call **libpng** only via nested [png_rs_try],
+//! so I/O and progressive callbacks are wrapped by [png_rs_io] and [png_rs_progressive],
+//! which report failures with a return code.
+#![allow(non_camel_case_types)]
+use std::ffi::CStr;
+
+use libc::{c_char, c_int, c_void};
+
+use crate::{png_bytep, png_const_bytep, png_const_charp, png_structp, png_structrp, png_uint_32};
+
+pub const PNG_RS_OK: c_int = 0;
+pub const PNG_RS_ERROR: c_int = 1;
+pub const PNG_RS_PAUSE: c_int = 2;
+
+pub const PNG_RS_MESSAGE_SIZE: usize = 256;
+
+pub type png_rs_callback = Option<unsafe extern "C" fn(data: *mut c_void)>;
+pub type png_rs_read_callback =
+    Option<unsafe extern "C" fn(user_data: *mut c_void, data: png_bytep, length: usize) -> c_int>;
+pub type png_rs_write_callback = Option<
+    unsafe extern "C" fn(user_data: *mut c_void, data: png_const_bytep, length: usize) -> c_int,
+>;
+pub type png_rs_flush_callback = Option<unsafe extern "C" fn(user_data: *mut c_void) -> c_int>;
+pub type png_rs_warning_callback =
+    Option<unsafe extern "C" fn(user_data: *mut c_void, message: png_const_charp)>;
+pub type png_rs_info_callback = Option<unsafe extern "C" fn(user_data: *mut c_void) -> c_int>;
+pub type png_rs_row_callback = Option<
+    unsafe extern "C" fn(
+        user_data: *mut c_void,
+        row: png_bytep,
+        row_num: png_uint_32,
+        pass: c_int,
+    ) -> c_int,
+>;
+pub type png_rs_end_callback = Option<unsafe extern "C" fn(user_data: *mut c_void) -> c_int>;
+
+/// Passed as **libpng** error pointer, must outlive the png struct and keep its address.
+/// `depth` counts active [png_rs_try] calls, `png_error` outside of them aborts the process.
+#[repr(C)]
+#[derive(Debug)]
+pub struct png_rs_error_context {
+    pub message: [c_char; PNG_RS_MESSAGE_SIZE],
+    pub warning: png_rs_warning_callback,
+    pub user_data: *mut c_void,
+    pub depth: c_int,
+}
+
+impl png_rs_error_context {
+    pub fn new(warning: png_rs_warning_callback, user_data: *mut c_void) -> Self {
+        Self {
+            message: [0; PNG_RS_MESSAGE_SIZE],
+            warning,
+            user_data,
+            depth: 0,
+        }
+    }
+
+    /// The last message passed to `png_error`, empty if there was none.
+    pub fn message(&self) -> &CStr {
+        // `png_rs_error_fn` always terminates the message
+        unsafe { CStr::from_ptr(self.message.as_ptr()) }
+    }
+}
+
+impl Default for png_rs_error_context {
+    fn default() -> Self {
+        Self::new(None, std::ptr::null_mut())
+    }
+}
+
+/// Passed as **libpng** I/O pointer, must outlive the png struct and keep its address.
+/// Callbacks return [PNG_RS_OK] or [PNG_RS_ERROR], the latter is converted into `png_error` on the C side.
+#[repr(C)]
+#[derive(Debug)]
+pub struct png_rs_io {
+    pub read: png_rs_read_callback,
+    pub write: png_rs_write_callback,
+    pub flush: png_rs_flush_callback,
+    pub user_data: *mut c_void,
+}
+
+/// Passed as **libpng** progressive pointer, must outlive the png struct and keep its address.
+/// Callbacks return [PNG_RS_OK] or [PNG_RS_ERROR] for `png_error`.
+/// `info` may return [PNG_RS_PAUSE] for `png_process_data_pause`, which stores the number of unprocessed
+/// input bytes in `remaining`. Other callbacks cannot pause, **libpng** 1.6 processes pending image data twice then.
+///
+/// `info` must start reading rows with `png_read_update_info`, nesting [png_rs_try], and set `rows` and `row_stride`.
+/// New rows are combined into `rows + row_num * row_stride` before `row` is called,
+/// which gets null if the pass has no pixels in the row. `row_stride` of 0 reuses a single row.
+#[repr(C)]
+#[derive(Debug)]
+pub struct png_rs_progressive {
+    pub info: png_rs_info_callback,
+    pub row: png_rs_row_callback,
+    pub end: png_rs_end_callback,
+    pub rows: png_bytep,
+    pub row_stride: usize,
+    pub remaining: usize,
+    pub user_data: *mut c_void,
+}
+
+extern "C" {
+    pub fn png_rs_error_fn(png_ptr: png_structp, message: png_const_charp);
+    pub fn png_rs_warning_fn(png_ptr: png_structp, message: png_const_charp);
+
+    /// `png_create_read_struct` with [png_rs_error_fn] and [png_rs_warning_fn] handlers,
+    /// null if `context` is null.
+    pub fn png_rs_create_read_struct(context: *mut png_rs_error_context) -> png_structp;
+    /// `png_create_write_struct` with [png_rs_error_fn] and [png_rs_warning_fn] handlers,
+    /// null if `context` is null.
+    pub fn png_rs_create_write_struct(context: *mut png_rs_error_context) -> png_structp;
+
+    /// `png_set_read_fn` with a function calling `io.read`.
+    pub fn png_rs_set_read_fn(png_ptr: png_structrp, io: *mut png_rs_io);
+    /// `png_set_write_fn` with functions calling `io.write` and `io.flush`, the latter may be `None`.
+    pub fn png_rs_set_write_fn(png_ptr: png_structrp, io: *mut png_rs_io);
+    /// `png_set_progressive_read_fn` with functions calling `progressive` callbacks.
+    pub fn png_rs_set_progressive_read_fn(
+        png_ptr: png_structrp,
+        progressive: *mut png_rs_progressive,
+    );
+
+    /// Calls `callback` inside `setjmp`, returns [PNG_RS_ERROR] if it was interrupted by `png_error`.
+    /// May be nested inside **libpng** callbacks, the outer jump buffer is saved and restored.
+    /// Returns [PNG_RS_ERROR] without calling `callback` if `png_ptr`, `callback` or the error pointer is null.
+    /// The error pointer is not checked otherwise, it must be the [png_rs_error_context] of the struct creation.
+    pub fn png_rs_try(png_ptr: png_structrp, callback: png_rs_callback, data: *mut c_void)
+        -> c_int;
+}
+
+/// Runs `f` via [png_rs_try], returns [PNG_RS_OK] or [PNG_RS_ERROR].
+///
+/// `Copy` bound guarantees that captured values have no destructors to skip.
+///
+/// # Safety
+/// `png_ptr` must be created by [png_rs_create_read_struct] or [png_rs_create_write_struct].
+/// `f` must not create values with destructors, since its frame is skipped by `longjmp` on error.
+/// Typically it only calls **libpng** functions with raw pointers.
+pub unsafe fn png_rs_try_fn<F: FnOnce() + Copy>(png_ptr: png_structrp, f: F) -> c_int {
+    unsafe extern "C" fn trampoline<F: FnOnce() + Copy>(data: *mut c_void) {
+        (*(data as *const F))()
+    }
+
+    png_rs_try(
+        png_ptr,
+        Some(trampoline::<F>),
+        &f as *const F as *mut c_void,
+    )
+}
@@ -0,0 +1,249 @@
+use std::ptr::null_mut;
+
+use libc::c_int;
+
+use libpng_vendored_sys::*;
+use macro_test_helper::*;
+
+const PASSES: std::ops::Range<u32> = 0..7;
+const SAMPLE_VALUES: [u32; 11] = [0, 1, 2, 7, 8, 9, 63, 1000, 65535, u32::MAX - 7, u32::MAX];
+
+#[test]
+fn test_constants() {
+    unsafe {
+        assert_eq!(PNG_UINT_31_MAX, png_rs_test_uint_31_max());
+        assert_eq!(PNG_UINT_32_MAX, png_rs_test_uint_32_max());
+        assert_eq!(PNG_SIZE_MAX, png_rs_test_size_max());
+        assert_eq!(PNG_FP_MAX, png_rs_test_fp_max());
+        assert_eq!(PNG_FP_MIN, png_rs_test_fp_min());
+        assert_eq!(PNG_ZBUF_SIZE, png_rs_test_zbuf_size());
+    }
+}
+
+#[test]
+fn test_format_macros() {
+    // All combinations of PNG_FORMAT_FLAG_* bits
+    for fmt in 0..128 {
+        unsafe {
+            assert_eq!(
+                PNG_IMAGE_SAMPLE_CHANNELS(fmt),
+                png_rs_test_image_sample_channels(fmt)
+            );
+            assert_eq!(
+                PNG_IMAGE_SAMPLE_COMPONENT_SIZE(fmt),
+                png_rs_test_image_sample_component_size(fmt)
+            );
+            assert_eq!(
+                PNG_IMAGE_SAMPLE_SIZE(fmt),
+                png_rs_test_image_sample_size(fmt)
+            );
+            assert_eq!(
+                PNG_IMAGE_MAXIMUM_COLORMAP_COMPONENTS(fmt),
+                png_rs_test_image_maximum_colormap_components(fmt)
+            );
+            assert_eq!(
+                PNG_IMAGE_PIXEL_CHANNELS(fmt),
+                png_rs_test_image_pixel_channels(fmt)
+            );
+            assert_eq!(
+                PNG_IMAGE_PIXEL_COMPONENT_SIZE(fmt),
+                png_rs_test_image_pixel_component_size(fmt)
+            );
+            assert_eq!(PNG_IMAGE_PIXEL_SIZE(fmt), png_rs_test_image_pixel_size(fmt));
+        }
+    }
+}
+
+#[test]
+fn test_image_macros() {
+    for fmt in 0..128 {
+        for warning_or_error in 0..4 {
+            let image = image(fmt, 123, 45, 17, warning_or_error);
+
+            unsafe {
+                assert_eq!(
+                    PNG_IMAGE_FAILED(&image),
+                    png_rs_test_image_failed(&image) != 0
+                );
+                assert_eq!(
+                    PNG_IMAGE_ROW_STRIDE(&image),
+                    png_rs_test_image_row_stride(&image)
+                );
+                assert_eq!(
+                    PNG_IMAGE_BUFFER_SIZE(&image, 1000),
+                    png_rs_test_image_buffer_size(&image, 1000)
+                );
+                assert_eq!(PNG_IMAGE_SIZE(&image), png_rs_test_image_size(&image));
+                assert_eq!(
+                    PNG_IMAGE_COLORMAP_SIZE(&image),
+                    png_rs_test_image_colormap_size(&image)
+                );
+                assert_eq!(
+                    PNG_IMAGE_DATA_SIZE(&image),
+                    png_rs_test_image_data_size(&image)
+                );
+                assert_eq!(
+                    PNG_IMAGE_COMPRESSED_SIZE_MAX(&image),
+                    png_rs_test_image_compressed_size_max(&image)
+                );
+                assert_eq!(
+                    PNG_IMAGE_PNG_SIZE_MAX(&image),
+                    png_rs_test_image_png_size_max(&image)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_zlib_max_size() {
+    // Wraps around like the unsigned arithmetic of C
+    for b in [0, 1, 7, 8, 63, 64, 1000, usize::MAX - 63, usize::MAX] {
+        assert_eq!(PNG_ZLIB_MAX_SIZE(b), unsafe {
+            png_rs_test_zlib_max_size(b)
+        });
+    }
+}
+
+#[test]
+fn test_pass_macros() {
+    for pass in PASSES {
+        let c_pass = pass as c_int;
+
+        unsafe {
+            assert_eq!(PNG_PASS_START_ROW(pass), png_rs_test_pass_start_row(c_pass));
+            assert_eq!(PNG_PASS_START_COL(pass), png_rs_test_pass_start_col(c_pass));
+            assert_eq!(
+                PNG_PASS_ROW_OFFSET(pass),
+                png_rs_test_pass_row_offset(c_pass)
+            );
+            assert_eq!(
+                PNG_PASS_COL_OFFSET(pass),
+                png_rs_test_pass_col_offset(c_pass)
+            );
+            assert_eq!(PNG_PASS_ROW_SHIFT(pass), png_rs_test_pass_row_shift(c_pass));
+            assert_eq!(PNG_PASS_COL_SHIFT(pass), png_rs_test_pass_col_shift(c_pass));
+
+            for value in SAMPLE_VALUES {
+                assert_eq!(
+                    PNG_PASS_ROWS(value, pass),
+                    png_rs_test_pass_rows(value, c_pass)
+                );
+                assert_eq!(
+                    PNG_PASS_COLS(value, pass),
+                    png_rs_test_pass_cols(value, c_pass)
+                );
+                assert_eq!(
+                    PNG_ROW_FROM_PASS_ROW(value, pass),
+                    png_rs_test_row_from_pass_row(value, c_pass)
+                );
+                assert_eq!(
+                    PNG_COL_FROM_PASS_COL(value, pass),
+                    png_rs_test_col_from_pass_col(value, c_pass)
+                );
+                assert_eq!(
+                    PNG_ROW_IN_INTERLACE_PASS(value, pass),
+                    png_rs_test_row_in_interlace_pass(value, c_pass) != 0
+                );
+                assert_eq!(
+                    PNG_COL_IN_INTERLACE_PASS(value, pass),
+                    png_rs_test_col_in_interlace_pass(value, c_pass) != 0
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_rowbytes() {
+    for pixel_bits in [1, 2, 4, 8, 16, 24, 32, 48, 64] {
+        for width in SAMPLE_VALUES {
+            assert_eq!(PNG_ROWBYTES(pixel_bits, width), unsafe {
+                png_rs_test_rowbytes(pixel_bits, width)
+            });
+        }
+    }
+}
+
+#[test]
+fn test_composite() {
+    for fg in (0..=255).step_by(5) {
+        for alpha in 0..=255 {
+            for bg in (0..=255).step_by(15) {
+                assert_eq!(png_composite(fg, alpha, bg), unsafe {
+                    png_rs_test_composite(fg, alpha, bg)
+                });
+            }
+        }
+    }
+
+    for fg in (0..=65535).step_by(4369) {
+        for alpha in (0..=65535).step_by(257) {
+            for bg in (0..=65535).step_by(13107) {
+                assert_eq!(png_composite_16(fg, alpha, bg), unsafe {
+                    png_rs_test_composite_16(fg, alpha, bg)
+                });
+            }
+        }
+    }
+}
+
+#[test]
+fn test_get_integers() {
+    let buffers: [[u8; 4]; 6] = [
+        [0, 0, 0, 0],
+        [0, 0, 0, 1],
+        [0x12, 0x34, 0x56, 0x78],
+        [0x7f, 0xff, 0xff, 0xff],
+        [0x80, 0, 0, 0],
+        [0xff, 0xff, 0xff, 0xff],
+    ];
+
+    for buf in buffers {
+        unsafe {
+            assert_eq!(PNG_get_uint_32(&buf), png_rs_test_get_uint_32(buf.as_ptr()));
+            assert_eq!(PNG_get_int_32(&buf), png_rs_test_get_int_32(buf.as_ptr()));
+            assert_eq!(
+                PNG_get_uint_16(&[buf[0], buf[1]]),
+                png_rs_test_get_uint_16(buf.as_ptr())
+            );
+        }
+    }
+}
+
+#[test]
+fn test_png_jmpbuf() {
+    unsafe {
+        let mut png_ptr = png_create_read_struct(
+            PNG_LIBPNG_VER_STRING.as_ptr() as *const _,
+            null_mut(),
+            None,
+            None,
+        );
+        assert!(!png_ptr.is_null());
+
+        assert!(!png_jmpbuf(png_ptr).is_null());
+
+        png_destroy_read_struct(&mut png_ptr, null_mut(), null_mut());
+    }
+}
+
+fn image(
+    format: png_uint_32,
+    width: png_uint_32,
+    height: png_uint_32,
+    colormap_entries: png_uint_32,
+    warning_or_error: png_uint_32,
+) -> png_image {
+    png_image {
+        opaque: null_mut(),
+        version: PNG_IMAGE_VERSION,
+        width,
+        height,
+        format,
+        flags: 0,
+        colormap_entries,
+        warning_or_error,
+        message: [0; 64],
+    }
+}
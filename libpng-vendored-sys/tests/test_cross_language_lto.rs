@@ -0,0 +1,27 @@
+//! Runs only when **libpng** is compiled into LLVM bitcode, e.g.:
+//! `CC=clang RUSTFLAGS="-Clinker-plugin-lto -Clinker=clang -Clink-arg=-fuse-ld=lld" cargo test --release -p libpng-vendored-sys`
+#![cfg(all(target_os = "linux", libpng_cross_language_lto))]
+
+use std::{
+    fs::{read, read_dir},
+    path::PathBuf,
+};
+
+const LLVM_BITCODE_MAGIC: [u8; 4] = [b'B', b'C', 0xC0, 0xDE];
+
+#[test]
+fn test_static_library_contains_bitcode() {
+    let lib_dir = PathBuf::from(env!("LIBPNG_VENDORED_SYS_LIB_DIR"));
+
+    let library_path = read_dir(lib_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.extension().is_some_and(|ext| ext == "a"))
+        .unwrap();
+
+    let library = read(library_path).unwrap();
+
+    assert!(library
+        .windows(LLVM_BITCODE_MAGIC.len())
+        .any(|window| window == LLVM_BITCODE_MAGIC));
+}
@@ -0,0 +1,208 @@
+use std::{env, ffi::CStr, fs::read, path::PathBuf, process::Command, ptr::null_mut};
+
+use libc::{c_int, c_void};
+
+use libpng_vendored_sys::{
+    png_bytep, png_const_charp, png_create_info_struct, png_destroy_read_struct, png_error,
+    png_get_image_height, png_get_image_width, png_get_io_ptr, png_infop, png_read_info,
+    png_rs_create_read_struct, png_rs_error_context, png_rs_try, png_rs_try_fn, png_set_read_fn,
+    png_structp, png_warning, PNG_RS_ERROR, PNG_RS_OK,
+};
+
+#[test]
+fn test_read_info() {
+    let data = read(test_image_path()).unwrap();
+    let reader = Reader::new(&data);
+
+    let status = unsafe {
+        let png_ptr = reader.png_ptr;
+        let info_ptr = reader.info_ptr;
+
+        png_rs_try_fn(png_ptr, || png_read_info(png_ptr, info_ptr))
+    };
+
+    assert_eq!(status, PNG_RS_OK);
+    assert!(reader.context.message().is_empty());
+
+    unsafe {
+        assert!(png_get_image_width(reader.png_ptr, reader.info_ptr) > 0);
+        assert!(png_get_image_height(reader.png_ptr, reader.info_ptr) > 0);
+    }
+}
+
+#[test]
+fn test_read_info_error() {
+    let data = b"definitely not a PNG file".to_vec();
+    let reader = Reader::new(&data);
+
+    let status = unsafe {
+        let png_ptr = reader.png_ptr;
+        let info_ptr = reader.info_ptr;
+
+        png_rs_try_fn(png_ptr, || png_read_info(png_ptr, info_ptr))
+    };
+
+    assert_eq!(status, PNG_RS_ERROR);
+    assert_eq!(reader.context.message().to_str().unwrap(), "Not a PNG file");
+}
+
+#[test]
+fn test_png_error() {
+    let reader = Reader::new(&[]);
+
+    let status = unsafe {
+        let png_ptr = reader.png_ptr;
+
+        png_rs_try_fn(png_ptr, || png_error(png_ptr, c"custom error".as_ptr()))
+    };
+
+    assert_eq!(status, PNG_RS_ERROR);
+    assert_eq!(reader.context.message(), c"custom error");
+}
+
+#[test]
+fn test_null_callback() {
+    let reader = Reader::new(&[]);
+
+    assert_eq!(
+        unsafe { png_rs_try(reader.png_ptr, None, null_mut()) },
+        PNG_RS_ERROR
+    );
+}
+
+#[test]
+fn test_warning() {
+    let reader = Reader::new(&[]);
+
+    let status = unsafe {
+        let png_ptr = reader.png_ptr;
+
+        png_rs_try_fn(png_ptr, || png_warning(png_ptr, c"custom warning".as_ptr()))
+    };
+
+    assert_eq!(status, PNG_RS_OK);
+    assert_eq!(reader.warnings, vec![String::from("custom warning")]);
+}
+
+#[test]
+fn test_nested_try() {
+    let reader = Reader::new(&[]);
+    let mut inner_status = PNG_RS_OK;
+    let inner_status_ptr: *mut c_int = &mut inner_status;
+
+    let status = unsafe {
+        let png_ptr = reader.png_ptr;
+
+        png_rs_try_fn(png_ptr, || {
+            *inner_status_ptr =
+                png_rs_try_fn(png_ptr, || png_error(png_ptr, c"inner error".as_ptr()));
+
+            // Jumps to the restored outer buffer
+            png_error(png_ptr, c"outer error".as_ptr())
+        })
+    };
+
+    assert_eq!(inner_status, PNG_RS_ERROR);
+    assert_eq!(status, PNG_RS_ERROR);
+    assert_eq!(reader.context.message(), c"outer error");
+    assert_eq!(reader.context.depth, 0);
+}
+
+#[test]
+fn test_error_outside_try() {
+    // Aborts instead of jumping to an unset buffer, checked in a child process
+    if env::var_os(ABORT_VAR).is_some() {
+        let reader = Reader::new(&[]);
+        unsafe { png_error(reader.png_ptr, c"no try".as_ptr()) };
+    }
+
+    let status = Command::new(env::current_exe().unwrap())
+        .args(["--exact", "test_error_outside_try", "--nocapture"])
+        .env(ABORT_VAR, "1")
+        .output()
+        .unwrap()
+        .status;
+
+    assert!(!status.success());
+    #[cfg(unix)]
+    assert_eq!(
+        std::os::unix::process::ExitStatusExt::signal(&status),
+        Some(libc::SIGABRT)
+    );
+}
+
+const ABORT_VAR: &str = "PNG_RS_TEST_ABORT";
+
+/// Read struct over a byte slice, boxed for stable addresses of the context and the input.
+struct Reader {
+    png_ptr: png_structp,
+    info_ptr: png_infop,
+    context: png_rs_error_context,
+    input: Vec<u8>,
+    position: usize,
+    warnings: Vec<String>,
+}
+
+impl Reader {
+    fn new(input: &[u8]) -> Box<Self> {
+        let mut reader = Box::new(Self {
+            png_ptr: null_mut(),
+            info_ptr: null_mut(),
+            context: png_rs_error_context::default(),
+            input: input.to_vec(),
+            position: 0,
+            warnings: vec![],
+        });
+
+        let reader_ptr = &mut *reader as *mut Reader as *mut c_void;
+        reader.context = png_rs_error_context::new(Some(warning_callback), reader_ptr);
+
+        unsafe {
+            reader.png_ptr = png_rs_create_read_struct(&mut reader.context);
+            assert!(!reader.png_ptr.is_null());
+
+            reader.info_ptr = png_create_info_struct(reader.png_ptr);
+            assert!(!reader.info_ptr.is_null());
+
+            png_set_read_fn(reader.png_ptr, reader_ptr, Some(read_callback));
+        }
+
+        reader
+    }
+}
+
+impl Drop for Reader {
+    fn drop(&mut self) {
+        unsafe { png_destroy_read_struct(&mut self.png_ptr, &mut self.info_ptr, null_mut()) };
+    }
+}
+
+/// Reads zeros past the end of input, **libpng** detects the broken data by itself.
+unsafe extern "C" fn read_callback(png_ptr: png_structp, data: png_bytep, length: usize) {
+    let reader = &mut *(png_get_io_ptr(png_ptr) as *mut Reader);
+    let output = std::slice::from_raw_parts_mut(data, length);
+
+    let available = reader
+        .input
+        .len()
+        .saturating_sub(reader.position)
+        .min(length);
+    output[..available].copy_from_slice(&reader.input[reader.position..][..available]);
+    output[available..].fill(0);
+
+    reader.position += available;
+}
+
+unsafe extern "C" fn warning_callback(user_data: *mut c_void, message: png_const_charp) {
+    let reader = &mut *(user_data as *mut Reader);
+
+    reader
+        .warnings
+        .push(CStr::from_ptr(message).to_string_lossy().into_owned());
+}
+
+fn test_image_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("test.png")
+}
@@ -0,0 +1,28 @@
+//! Runs only when **libpng** is instrumented with AddressSanitizer, e.g.:
+//! `RUSTFLAGS=-Zsanitizer=address cargo +nightly test -p libpng-vendored-sys --target x86_64-unknown-linux-gnu`
+#![cfg(all(target_os = "linux", libpng_sanitize = "address"))]
+
+use std::{
+    fs::{read, read_dir},
+    path::PathBuf,
+};
+
+// Instrumented memory accesses call either reporting or checking functions of the runtime
+const ASAN_SYMBOL_PREFIXES: [&[u8]; 2] = [b"__asan_report_", b"__asan_load"];
+
+#[test]
+fn test_static_library_is_instrumented() {
+    let lib_dir = PathBuf::from(env!("LIBPNG_VENDORED_SYS_LIB_DIR"));
+
+    let library_path = read_dir(lib_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.extension().is_some_and(|ext| ext == "a"))
+        .unwrap();
+
+    let library = read(library_path).unwrap();
+
+    assert!(ASAN_SYMBOL_PREFIXES.iter().any(|prefix| library
+        .windows(prefix.len())
+        .any(|window| window == *prefix)));
+}
@@ -0,0 +1,62 @@
+//! Runtime-independent parts of [tokio](crate::tokio) and [futures_io](crate::futures_io) adapters.
+use std::ops::ControlFlow;
+
+use crate::{ProgressiveDecoder, ProgressiveEvent, ReadOptions, Result};
+
+const BUFFER_SIZE: usize = 8 * 1024;
+
+/// Progressive decoder with a buffer for data read from an async reader.
+pub(crate) struct AsyncInput {
+    pub(crate) decoder: ProgressiveDecoder,
+    buffer: Box<[u8]>,
+    start: usize,
+    end: usize,
+}
+
+impl AsyncInput {
+    pub(crate) fn new(options: ReadOptions) -> Result<Self> {
+        Ok(Self {
+            decoder: ProgressiveDecoder::with_options(options)?,
+            buffer: vec![0; BUFFER_SIZE].into_boxed_slice(),
+            start: 0,
+            end: 0,
+        })
+    }
+
+    /// Whether all read data is fed to the decoder.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Buffer to read into when it [is empty](AsyncInput::is_empty).
+    pub(crate) fn buffer_mut(&mut self) -> &mut [u8] {
+        &mut self.buffer
+    }
+
+    pub(crate) fn fill(&mut self, length: usize) {
+        self.start = 0;
+        self.end = length;
+    }
+
+    /// Feeds read data, returns [ControlFlow::Break] if the handler paused decoding.
+    pub(crate) fn feed<F>(&mut self, mut handler: F) -> Result<ControlFlow<()>>
+    where
+        F: FnMut(ProgressiveEvent<'_>) -> ControlFlow<()>,
+    {
+        // Pauses inside image data take effect after the piece, so all input may be consumed
+        let mut paused = false;
+        self.start += self
+            .decoder
+            .feed(&self.buffer[self.start..self.end], |event| {
+                let flow = handler(event);
+                paused |= flow.is_break();
+                flow
+            })?;
+
+        if paused {
+            Ok(ControlFlow::Break(()))
+        } else {
+            Ok(ControlFlow::Continue(()))
+        }
+    }
+}
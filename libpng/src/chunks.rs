@@ -0,0 +1,376 @@
+use std::{
+    any::Any,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    ptr::null_mut,
+    slice,
+    str::FromStr,
+};
+
+use libc::c_int;
+use libpng_vendored_sys::{
+    png_get_unknown_chunks, png_infop, png_set_keep_unknown_chunks, png_set_unknown_chunks,
+    png_structp, png_unknown_chunk, png_unknown_chunkp, PNG_AFTER_IDAT, PNG_HANDLE_CHUNK_ALWAYS,
+    PNG_HANDLE_CHUNK_AS_DEFAULT, PNG_HANDLE_CHUNK_IF_SAFE, PNG_HANDLE_CHUNK_NEVER, PNG_HAVE_IHDR,
+    PNG_HAVE_PLTE,
+};
+
+use crate::{Error, Result};
+
+/// Chunks read and written by **libpng** itself, which cannot be handled as unknown.
+const STRUCTURAL_CHUNKS: [&[u8; 4]; 4] = [b"IHDR", b"PLTE", b"IDAT", b"IEND"];
+
+/// Validated 4-letter chunk type, property bits are the case of its letters.
+/// ```
+/// use libpng::ChunkName;
+///
+/// let name: ChunkName = "prVt".parse()?;
+/// assert!(!name.is_critical());
+/// assert!(!name.is_public());
+/// assert!(name.is_safe_to_copy());
+/// # Ok::<(), libpng::Error>(())
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChunkName([u8; 4]);
+
+impl ChunkName {
+    /// Name of ASCII letters with an uppercase third one, the reserved bit must be zero.
+    pub fn new(name: [u8; 4]) -> Result<Self> {
+        if !name.iter().all(u8::is_ascii_alphabetic) || !name[2].is_ascii_uppercase() {
+            return Err(Error::InvalidInput(format!(
+                "Chunk name must be 4 ASCII letters with an uppercase third one, got {:?}",
+                String::from_utf8_lossy(&name)
+            )));
+        }
+
+        Ok(Self(name))
+    }
+
+    pub const fn bytes(self) -> [u8; 4] {
+        self.0
+    }
+
+    /// Uppercase first letter, decoders must understand the chunk to display the image.
+    pub const fn is_critical(self) -> bool {
+        self.0[0].is_ascii_uppercase()
+    }
+
+    /// Uppercase second letter, the chunk is defined by the PNG specification or registered.
+    pub const fn is_public(self) -> bool {
+        self.0[1].is_ascii_uppercase()
+    }
+
+    /// Lowercase fourth letter, editors may copy the chunk after modifying critical chunks.
+    pub const fn is_safe_to_copy(self) -> bool {
+        self.0[3].is_ascii_lowercase()
+    }
+
+    /// Whether **libpng** handles the chunk as a part of image structure: IHDR, PLTE, IDAT or IEND.
+    pub fn is_structural(self) -> bool {
+        STRUCTURAL_CHUNKS.contains(&&self.0)
+    }
+}
+
+impl FromStr for ChunkName {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        let bytes = name.as_bytes().try_into().map_err(|_| {
+            Error::InvalidInput(format!("Chunk name must be 4 letters long, got {name:?}"))
+        })?;
+
+        Self::new(bytes)
+    }
+}
+
+impl fmt::Display for ChunkName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Letters only
+        f.write_str(&String::from_utf8_lossy(&self.0))
+    }
+}
+
+impl fmt::Debug for ChunkName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ChunkName({self})")
+    }
+}
+
+/// Position of an unknown chunk relative to critical chunks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChunkLocation {
+    /// After IHDR, before PLTE.
+    BeforePlte,
+    /// After PLTE if any, before IDAT.
+    BeforeIdat,
+    /// After IDAT, before IEND.
+    AfterIdat,
+}
+
+impl ChunkLocation {
+    /// Location of `PNG_HAVE_*` mode bits, the latest one wins.
+    fn from_png(location: u8) -> Self {
+        let location = u32::from(location);
+
+        if location & PNG_AFTER_IDAT != 0 {
+            Self::AfterIdat
+        } else if location & PNG_HAVE_PLTE != 0 {
+            Self::BeforeIdat
+        } else {
+            Self::BeforePlte
+        }
+    }
+
+    fn to_png(self) -> u8 {
+        let location = match self {
+            Self::BeforePlte => PNG_HAVE_IHDR,
+            Self::BeforeIdat => PNG_HAVE_PLTE,
+            Self::AfterIdat => PNG_AFTER_IDAT,
+        };
+
+        location as u8
+    }
+}
+
+/// Chunk not interpreted by **libpng**, either of an unknown type or configured to be handled as unknown.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UnknownChunk {
+    pub name: ChunkName,
+    pub data: Vec<u8>,
+    pub location: ChunkLocation,
+}
+
+impl UnknownChunk {
+    pub fn new(name: ChunkName, data: Vec<u8>, location: ChunkLocation) -> Self {
+        Self {
+            name,
+            data,
+            location,
+        }
+    }
+
+    /// Checks that the chunk can be written, which is done by encoders as well.
+    pub fn validate(&self) -> Result<()> {
+        if self.name.is_structural() {
+            return Err(Error::InvalidInput(format!(
+                "Chunk {} is written by libpng itself",
+                self.name
+            )));
+        }
+
+        // PNG chunk length limit
+        if self.data.len() > i32::MAX as usize {
+            return Err(Error::InvalidInput(format!(
+                "Chunk {} data of {} bytes is too large",
+                self.name,
+                self.data.len()
+            )));
+        }
+
+        Ok(())
+    }
+
+    unsafe fn from_png(chunk: &png_unknown_chunk) -> Option<Self> {
+        let name = ChunkName::new(chunk.name[..4].try_into().ok()?).ok()?;
+
+        let data = if chunk.data.is_null() || chunk.size == 0 {
+            Vec::new()
+        } else {
+            slice::from_raw_parts(chunk.data, chunk.size).to_vec()
+        };
+
+        Some(Self::new(
+            name,
+            data,
+            ChunkLocation::from_png(chunk.location),
+        ))
+    }
+}
+
+/// Whether **libpng** keeps unknown chunks, `PNG_HANDLE_CHUNK_*` values.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum KeepPolicy {
+    /// The default policy for all unknown chunks, which discards them unless set otherwise.
+    #[default]
+    Default,
+    /// Discards the chunks.
+    Never,
+    /// Keeps ancillary chunks only, whether safe to copy or not, like **libpng** 1.6 does.
+    IfSafe,
+    /// Keeps the chunks, including unknown critical ones, which otherwise fail decoding.
+    Always,
+}
+
+impl KeepPolicy {
+    fn to_png(self) -> c_int {
+        let keep = match self {
+            Self::Default => PNG_HANDLE_CHUNK_AS_DEFAULT,
+            Self::Never => PNG_HANDLE_CHUNK_NEVER,
+            Self::IfSafe => PNG_HANDLE_CHUNK_IF_SAFE,
+            Self::Always => PNG_HANDLE_CHUNK_ALWAYS,
+        };
+
+        keep as c_int
+    }
+}
+
+/// Handler of unknown chunks, returns whether the chunk is handled. See [ReadOptions::with_user_chunk_callback](crate::ReadOptions::with_user_chunk_callback).
+pub(crate) type UserChunkCallback = Box<dyn FnMut(&UnknownChunk) -> Result<bool> + Send>;
+
+/// User chunk callback with its outcome, passed to `png_set_read_user_chunk_fn` as user data.
+pub(crate) struct UserChunkState {
+    callback: UserChunkCallback,
+    pub(crate) error: Option<Error>,
+    pub(crate) panic: Option<Box<dyn Any + Send>>,
+}
+
+impl UserChunkState {
+    pub(crate) fn new(callback: UserChunkCallback) -> Self {
+        Self {
+            callback,
+            error: None,
+            panic: None,
+        }
+    }
+}
+
+/// `png_user_chunk_ptr` running the callback of [UserChunkState] at `png_get_user_chunk_ptr`.
+pub(crate) unsafe extern "C" fn user_chunk_callback(
+    png_ptr: png_structp,
+    chunk: png_unknown_chunkp,
+) -> c_int {
+    let state = libpng_vendored_sys::png_get_user_chunk_ptr(png_ptr) as *mut UserChunkState;
+    if state.is_null() || chunk.is_null() {
+        return 0;
+    }
+    let state = &mut *state;
+
+    // libpng checks names before handling chunks, the check is kept for safety
+    let Some(chunk) = UnknownChunk::from_png(&*chunk) else {
+        return 0;
+    };
+
+    match panic::catch_unwind(AssertUnwindSafe(|| (state.callback)(&chunk))) {
+        Ok(Ok(handled)) => c_int::from(handled),
+        Ok(Err(error)) => {
+            state.error = Some(error);
+            -1
+        }
+        Err(panic) => {
+            state.panic = Some(panic);
+            -1
+        }
+    }
+}
+
+/// Names for `png_set_keep_unknown_chunks`, each followed by a zero byte.
+pub(crate) fn chunk_list<'a>(names: impl IntoIterator<Item = &'a ChunkName>) -> (Vec<u8>, c_int) {
+    let mut list = Vec::new();
+    let mut count = 0;
+
+    for name in names {
+        list.extend(name.0);
+        list.push(0);
+        count += 1;
+    }
+
+    (list, count)
+}
+
+/// Sets `keep` for `list` of [chunk_list], must be run via `png_rs_try`.
+pub(crate) unsafe fn set_keep(png_ptr: png_structp, keep: KeepPolicy, list: &[u8], count: c_int) {
+    png_set_keep_unknown_chunks(png_ptr, keep.to_png(), list.as_ptr(), count);
+}
+
+/// Unknown chunks kept in `info_ptr`.
+pub(crate) unsafe fn get_unknown_chunks(
+    png_ptr: png_structp,
+    info_ptr: png_infop,
+) -> Vec<UnknownChunk> {
+    let mut chunks: png_unknown_chunkp = null_mut();
+    let count = png_get_unknown_chunks(png_ptr, info_ptr, &mut chunks);
+    if count <= 0 || chunks.is_null() {
+        return Vec::new();
+    }
+
+    slice::from_raw_parts(chunks, count as usize)
+        .iter()
+        .filter_map(|chunk| UnknownChunk::from_png(chunk))
+        .collect()
+}
+
+/// **libpng** structures of [UnknownChunk]s for `png_set_unknown_chunks`, which copies them.
+pub(crate) struct PngUnknownChunks {
+    // Points to data of chunks
+    chunks: Vec<png_unknown_chunk>,
+    names: (Vec<u8>, c_int),
+}
+
+impl PngUnknownChunks {
+    pub(crate) fn new(chunks: &[UnknownChunk]) -> Result<Self> {
+        for chunk in chunks {
+            chunk.validate()?;
+        }
+
+        let png_chunks = chunks
+            .iter()
+            .map(|chunk| {
+                let [a, b, c, d] = chunk.name.0;
+
+                png_unknown_chunk {
+                    name: [a, b, c, d, 0],
+                    data: chunk.data.as_ptr() as *mut u8,
+                    size: chunk.data.len(),
+                    location: chunk.location.to_png(),
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            chunks: png_chunks,
+            names: chunk_list(chunks.iter().map(|chunk| &chunk.name)),
+        })
+    }
+
+    /// Sets chunks in `info_ptr` and makes **libpng** write them even if unsafe to copy,
+    /// must be run via `png_rs_try` while the source chunks are alive.
+    pub(crate) unsafe fn set(&self, png_ptr: png_structp, info_ptr: png_infop) {
+        if self.chunks.is_empty() {
+            return;
+        }
+
+        let (list, count) = &self.names;
+        set_keep(png_ptr, KeepPolicy::Always, list, *count);
+        png_set_unknown_chunks(
+            png_ptr,
+            info_ptr,
+            self.chunks.as_ptr(),
+            self.chunks.len() as c_int,
+        );
+    }
+}
+
+/// Names, declared lengths and available data of chunks in PNG data up to IEND, without decoding it.
+/// Data of the last chunk may be truncated.
+pub(crate) fn raw_chunks(png: &[u8]) -> impl Iterator<Item = ([u8; 4], usize, &[u8])> {
+    // Skips the signature, chunks are checked by libpng when decoding
+    let mut offset: usize = 8;
+    let mut ended = false;
+
+    std::iter::from_fn(move || {
+        if ended {
+            return None;
+        }
+        let header = png.get(offset..offset.checked_add(8)?)?;
+
+        let length = u32::from_be_bytes(header[..4].try_into().ok()?) as usize;
+        let name: [u8; 4] = header[4..].try_into().ok()?;
+        let data_start = offset + 8;
+        let data = &png[data_start..data_start.saturating_add(length).min(png.len())];
+
+        ended = &name == b"IEND";
+        offset = data_start.saturating_add(length).saturating_add(4);
+
+        Some((name, length, data))
+    })
+}
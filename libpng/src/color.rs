@@ -0,0 +1,425 @@
+use std::{
+    ffi::{CStr, CString},
+    ptr::null_mut,
+    slice,
+};
+
+use libc::c_int;
+use libpng_vendored_sys::{
+    png_bytep, png_charp, png_fixed_point, png_get_cHRM_fixed, png_get_gAMA_fixed, png_get_iCCP,
+    png_get_sRGB, png_infop, png_set_cHRM_fixed, png_set_gAMA_fixed, png_set_iCCP, png_set_sRGB,
+    png_set_sRGB_gAMA_and_cHRM, png_structp, png_uint_32, PNG_INFO_cHRM, PNG_INFO_gAMA,
+    PNG_INFO_iCCP, PNG_INFO_sRGB, PNG_sRGB_INTENT_ABSOLUTE, PNG_sRGB_INTENT_PERCEPTUAL,
+    PNG_sRGB_INTENT_RELATIVE, PNG_sRGB_INTENT_SATURATION, PNG_COMPRESSION_TYPE_BASE, PNG_FP_1,
+};
+
+use crate::{text::validate_keyword, Error, Result};
+
+/// File gamma of sRGB images, as written by `png_set_sRGB_gAMA_and_cHRM`.
+pub const SRGB_GAMMA: f64 = 0.45455;
+
+/// Chromaticities of sRGB primaries and D65 white point, as written by `png_set_sRGB_gAMA_and_cHRM`.
+pub const SRGB_CHROMATICITIES: Chromaticities = Chromaticities {
+    white: Xy::new(0.3127, 0.329),
+    red: Xy::new(0.64, 0.33),
+    green: Xy::new(0.3, 0.6),
+    blue: Xy::new(0.15, 0.06),
+};
+
+/// Range of gAMA values accepted by **libpng**, in fixed point.
+const GAMMA_RANGE: std::ops::RangeInclusive<png_fixed_point> = 16..=625_000_000;
+
+/// Color management chunks of a PNG image: iCCP, sRGB, cHRM and gAMA.
+///
+/// **libpng** 1.6 derives gamma and chromaticities from the sRGB chunk when reading,
+/// so images with sRGB are read like [ColorInfo::srgb]. The cICP chunk is not supported by **libpng** 1.6.
+/// ```
+/// use libpng::{ColorInfo, RenderingIntent, SRGB_GAMMA};
+///
+/// let info = ColorInfo::srgb(RenderingIntent::Perceptual);
+/// assert_eq!(info.gamma, Some(SRGB_GAMMA));
+/// assert!(info.validate().is_ok());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ColorInfo {
+    /// Embedded ICC profile of iCCP chunk, exclusive with [rendering_intent](ColorInfo::rendering_intent).
+    pub icc_profile: Option<IccProfile>,
+    /// Rendering intent of sRGB chunk, marking the image as sRGB.
+    pub rendering_intent: Option<RenderingIntent>,
+    /// Chromaticities of cHRM chunk.
+    pub chromaticities: Option<Chromaticities>,
+    /// File gamma of gAMA chunk, e.g. [SRGB_GAMMA], the inverse of the display exponent.
+    pub gamma: Option<f64>,
+}
+
+impl ColorInfo {
+    /// sRGB with matching gAMA and cHRM, written via `png_set_sRGB_gAMA_and_cHRM`.
+    pub fn srgb(intent: RenderingIntent) -> Self {
+        Self {
+            icc_profile: None,
+            rendering_intent: Some(intent),
+            chromaticities: Some(SRGB_CHROMATICITIES),
+            gamma: Some(SRGB_GAMMA),
+        }
+    }
+
+    /// Whether no chunk is present.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Checks values accepted by **libpng**, which is done by encoders as well.
+    ///
+    /// The ICC profile is checked by **libpng** on write, only its name and size are checked here.
+    pub fn validate(&self) -> Result<()> {
+        if self.icc_profile.is_some() && self.rendering_intent.is_some() {
+            return Err(Error::InvalidInput(String::from(
+                "iCCP and sRGB chunks cannot be combined",
+            )));
+        }
+
+        if let Some(profile) = &self.icc_profile {
+            validate_keyword(&profile.name)?;
+
+            if u32::try_from(profile.data.len()).is_err() {
+                return Err(Error::InvalidInput(format!(
+                    "ICC profile is too large: {} bytes",
+                    profile.data.len()
+                )));
+            }
+        }
+
+        if let Some(chromaticities) = &self.chromaticities {
+            chromaticities.to_xyz()?;
+        }
+
+        if let Some(gamma) = self.gamma {
+            if !to_fixed(gamma).is_some_and(|gamma| GAMMA_RANGE.contains(&gamma)) {
+                return Err(Error::InvalidInput(format!(
+                    "Gamma must be from 0.00016 to 6250, got {gamma}"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads color chunks from `info_ptr`.
+    pub(crate) unsafe fn from_png(png_ptr: png_structp, info_ptr: png_infop) -> Self {
+        let mut info = Self::default();
+
+        let mut name: png_charp = null_mut();
+        let mut compression: c_int = 0;
+        let mut profile: png_bytep = null_mut();
+        let mut length: png_uint_32 = 0;
+        if png_get_iCCP(
+            png_ptr,
+            info_ptr,
+            &mut name,
+            &mut compression,
+            &mut profile,
+            &mut length,
+        ) & PNG_INFO_iCCP
+            != 0
+            && !name.is_null()
+            && !profile.is_null()
+        {
+            info.icc_profile = Some(IccProfile {
+                name: CStr::from_ptr(name)
+                    .to_bytes()
+                    .iter()
+                    .map(|&b| char::from(b))
+                    .collect(),
+                data: slice::from_raw_parts(profile, length as usize).to_vec(),
+            });
+        }
+
+        let mut intent: c_int = 0;
+        if png_get_sRGB(png_ptr, info_ptr, &mut intent) & PNG_INFO_sRGB != 0 {
+            info.rendering_intent = RenderingIntent::from_png(intent);
+        }
+
+        let [mut white_x, mut white_y, mut red_x, mut red_y] = [0; 4];
+        let [mut green_x, mut green_y, mut blue_x, mut blue_y] = [0; 4];
+        if png_get_cHRM_fixed(
+            png_ptr,
+            info_ptr,
+            &mut white_x,
+            &mut white_y,
+            &mut red_x,
+            &mut red_y,
+            &mut green_x,
+            &mut green_y,
+            &mut blue_x,
+            &mut blue_y,
+        ) & PNG_INFO_cHRM
+            != 0
+        {
+            let point = |x, y| Xy::new(from_fixed(x), from_fixed(y));
+
+            info.chromaticities = Some(Chromaticities {
+                white: point(white_x, white_y),
+                red: point(red_x, red_y),
+                green: point(green_x, green_y),
+                blue: point(blue_x, blue_y),
+            });
+        }
+
+        let mut gamma: png_fixed_point = 0;
+        if png_get_gAMA_fixed(png_ptr, info_ptr, &mut gamma) & PNG_INFO_gAMA != 0 {
+            info.gamma = Some(from_fixed(gamma));
+        }
+
+        info
+    }
+
+    /// Profile name for [set](ColorInfo::set), after [validate](ColorInfo::validate).
+    pub(crate) fn profile_name(&self) -> CString {
+        let name = self.icc_profile.as_ref().map_or_else(Vec::new, |profile| {
+            profile.name.chars().map(|c| u32::from(c) as u8).collect()
+        });
+
+        CString::new(name).unwrap_or_default()
+    }
+
+    /// Sets color chunks in `info_ptr`, must be run via `png_rs_try` after [validate](ColorInfo::validate).
+    pub(crate) unsafe fn set(
+        &self,
+        png_ptr: png_structp,
+        info_ptr: png_infop,
+        profile_name: &CStr,
+    ) {
+        if let Some(profile) = &self.icc_profile {
+            png_set_iCCP(
+                png_ptr,
+                info_ptr,
+                profile_name.as_ptr(),
+                PNG_COMPRESSION_TYPE_BASE as c_int,
+                profile.data.as_ptr(),
+                profile.data.len() as png_uint_32,
+            );
+        }
+
+        if let Some(intent) = self.rendering_intent {
+            if self.chromaticities == Some(SRGB_CHROMATICITIES) && self.gamma == Some(SRGB_GAMMA) {
+                png_set_sRGB_gAMA_and_cHRM(png_ptr, info_ptr, intent.to_png());
+                return;
+            }
+        }
+
+        if let Some(gamma) = self.gamma.and_then(to_fixed) {
+            png_set_gAMA_fixed(png_ptr, info_ptr, gamma);
+        }
+
+        if let Some(Chromaticities {
+            white,
+            red,
+            green,
+            blue,
+        }) = self.chromaticities
+        {
+            let fixed = |value| to_fixed(value).unwrap_or_default();
+
+            png_set_cHRM_fixed(
+                png_ptr,
+                info_ptr,
+                fixed(white.x),
+                fixed(white.y),
+                fixed(red.x),
+                fixed(red.y),
+                fixed(green.x),
+                fixed(green.y),
+                fixed(blue.x),
+                fixed(blue.y),
+            );
+        }
+
+        if let Some(intent) = self.rendering_intent {
+            png_set_sRGB(png_ptr, info_ptr, intent.to_png());
+        }
+    }
+}
+
+/// ICC profile of iCCP chunk.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IccProfile {
+    /// Latin-1 name with the rules of text keywords.
+    pub name: String,
+    /// Uncompressed profile.
+    pub data: Vec<u8>,
+}
+
+/// Rendering intent of sRGB chunk, `PNG_sRGB_INTENT_*`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RenderingIntent {
+    Perceptual,
+    RelativeColorimetric,
+    Saturation,
+    AbsoluteColorimetric,
+}
+
+impl RenderingIntent {
+    fn from_png(intent: c_int) -> Option<Self> {
+        [
+            Self::Perceptual,
+            Self::RelativeColorimetric,
+            Self::Saturation,
+            Self::AbsoluteColorimetric,
+        ]
+        .into_iter()
+        .find(|known| known.to_png() == intent)
+    }
+
+    const fn to_png(self) -> c_int {
+        let intent = match self {
+            Self::Perceptual => PNG_sRGB_INTENT_PERCEPTUAL,
+            Self::RelativeColorimetric => PNG_sRGB_INTENT_RELATIVE,
+            Self::Saturation => PNG_sRGB_INTENT_SATURATION,
+            Self::AbsoluteColorimetric => PNG_sRGB_INTENT_ABSOLUTE,
+        };
+
+        intent as c_int
+    }
+}
+
+/// CIE xy chromaticity coordinates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Xy {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Xy {
+    pub const fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+}
+
+/// CIE XYZ tristimulus values.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Xyz {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Xyz {
+    pub const fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+}
+
+/// White point and primaries of cHRM chunk in xy coordinates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Chromaticities {
+    pub white: Xy,
+    pub red: Xy,
+    pub green: Xy,
+    pub blue: Xy,
+}
+
+/// XYZ of the red, green and blue end points, the white point is their sum with Y of 1.
+/// This is the form of `png_get_cHRM_XYZ` and ICC profile colorants.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Colorants {
+    pub red: Xyz,
+    pub green: Xyz,
+    pub blue: Xyz,
+}
+
+impl Chromaticities {
+    /// Converts to end points scaled so that white has Y of 1.
+    pub fn to_xyz(&self) -> Result<Colorants> {
+        let points = [self.white, self.red, self.green, self.blue];
+        if let Some(point) = points
+            .iter()
+            .find(|p| !(p.x >= 0.0 && p.y > 0.0 && p.x + p.y <= 1.0))
+        {
+            return Err(Error::InvalidInput(format!(
+                "Chromaticity must have non-negative x, positive y and x + y up to 1, got {point:?}"
+            )));
+        }
+
+        // Primaries with Y of 1, scaled by the factors mixing them into white
+        let unit = |p: Xy| [p.x / p.y, 1.0, (1.0 - p.x - p.y) / p.y];
+        let [red, green, blue, white] = [
+            unit(self.red),
+            unit(self.green),
+            unit(self.blue),
+            unit(self.white),
+        ];
+
+        let determinant = |a: [f64; 3], b: [f64; 3], c: [f64; 3]| {
+            a[0] * (b[1] * c[2] - b[2] * c[1]) - b[0] * (a[1] * c[2] - a[2] * c[1])
+                + c[0] * (a[1] * b[2] - a[2] * b[1])
+        };
+
+        let total = determinant(red, green, blue);
+        if total.abs() < 1e-12 {
+            return Err(Error::InvalidInput(String::from(
+                "Primaries must not be collinear",
+            )));
+        }
+
+        let scales = [
+            determinant(white, green, blue) / total,
+            determinant(red, white, blue) / total,
+            determinant(red, green, white) / total,
+        ];
+        if scales.iter().any(|scale| *scale <= 0.0) {
+            return Err(Error::InvalidInput(String::from(
+                "White point must lie inside the primaries",
+            )));
+        }
+
+        let scaled = |p: [f64; 3], scale: f64| Xyz::new(p[0] * scale, p[1] * scale, p[2] * scale);
+
+        Ok(Colorants {
+            red: scaled(red, scales[0]),
+            green: scaled(green, scales[1]),
+            blue: scaled(blue, scales[2]),
+        })
+    }
+
+    /// Converts end points, like `png_set_cHRM_XYZ` does.
+    pub fn from_xyz(colorants: &Colorants) -> Result<Self> {
+        let Colorants { red, green, blue } = *colorants;
+        let white = Xyz::new(
+            red.x + green.x + blue.x,
+            red.y + green.y + blue.y,
+            red.z + green.z + blue.z,
+        );
+
+        let xy = |p: Xyz| {
+            let sum = p.x + p.y + p.z;
+            if !(p.x >= 0.0 && p.y >= 0.0 && p.z >= 0.0 && sum > 0.0) {
+                return Err(Error::InvalidInput(format!(
+                    "XYZ must be non-negative with a positive sum, got {p:?}"
+                )));
+            }
+
+            Ok(Xy::new(p.x / sum, p.y / sum))
+        };
+
+        let chromaticities = Self {
+            white: xy(white)?,
+            red: xy(red)?,
+            green: xy(green)?,
+            blue: xy(blue)?,
+        };
+        chromaticities.to_xyz()?;
+
+        Ok(chromaticities)
+    }
+}
+
+fn to_fixed(value: f64) -> Option<png_fixed_point> {
+    let fixed = (value * f64::from(PNG_FP_1)).round();
+
+    (fixed.is_finite() && fixed >= f64::from(i32::MIN) && fixed <= f64::from(i32::MAX))
+        .then_some(fixed as png_fixed_point)
+}
+
+fn from_fixed(value: png_fixed_point) -> f64 {
+    f64::from(value) / f64::from(PNG_FP_1)
+}
@@ -0,0 +1,320 @@
+use std::{
+    borrow::Cow,
+    fs::{read, File},
+    io::Read,
+    path::Path,
+    ptr::{null, null_mut},
+};
+
+use libc::c_void;
+use libpng_vendored_sys::{
+    png_image, png_image_begin_read_from_memory, png_image_finish_read, png_image_free,
+    PNG_IMAGE_FLAG_16BIT_sRGB, PNG_IMAGE_COLORMAP_SIZE, PNG_IMAGE_FAILED, PNG_IMAGE_ROW_STRIDE,
+    PNG_IMAGE_VERSION, PNG_IMAGE_WARNING,
+};
+
+use crate::{
+    error::message_to_string, exif::find_exif_chunk, logging::log_warning, Color, Error, ExifInfo,
+    Image, Limits, Orientation, PixelFormat, Result, Warning,
+};
+
+/// Decodes a whole PNG image at once via the simplified **libpng** API.
+///
+/// The header is read on creation, output options are applied by [decode](Decoder::decode):
+/// ```no_run
+/// use libpng::{Decoder, PixelFormat};
+///
+/// let image = Decoder::from_path("image.png")?
+///     .with_format(PixelFormat::RGB)
+///     .decode()?;
+/// # Ok::<(), libpng::Error>(())
+/// ```
+pub struct Decoder<'a> {
+    // Boxed, since libpng keeps a pointer to it until `png_image_free`
+    image: Box<png_image>,
+    // Read by libpng until `png_image_finish_read`
+    input: Cow<'a, [u8]>,
+    format: PixelFormat,
+    background: Option<Color>,
+    row_stride: Option<usize>,
+    srgb_16_bit: bool,
+    exif_orientation: bool,
+    limits: Option<Limits>,
+}
+
+impl Decoder<'static> {
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_vec(read(path)?)
+    }
+
+    pub fn from_file(mut file: File) -> Result<Self> {
+        let mut input = vec![];
+        file.read_to_end(&mut input)?;
+
+        Self::from_vec(input)
+    }
+
+    pub fn from_vec(input: Vec<u8>) -> Result<Self> {
+        Self::new(Cow::Owned(input))
+    }
+}
+
+impl<'a> Decoder<'a> {
+    pub fn from_slice(input: &'a [u8]) -> Result<Self> {
+        Self::new(Cow::Borrowed(input))
+    }
+
+    fn new(input: Cow<'a, [u8]>) -> Result<Self> {
+        let mut image = empty_image();
+
+        let status = unsafe {
+            png_image_begin_read_from_memory(
+                &mut *image,
+                input.as_ptr() as *const c_void,
+                input.len(),
+            )
+        };
+
+        if status != 1 || PNG_IMAGE_FAILED(&image) {
+            return Err(Error::from_message(&image.message));
+        }
+        log_image_warning(&image);
+
+        // Unknown flags from future libpng versions are dropped
+        let format = PixelFormat::from_bits_truncate(image.format);
+
+        Ok(Self {
+            image,
+            input,
+            format,
+            background: None,
+            row_stride: None,
+            srgb_16_bit: false,
+            exif_orientation: false,
+            limits: None,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.image.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.image.height
+    }
+
+    /// Format closest to the encoded image, chosen by **libpng**. Default output format.
+    pub fn source_format(&self) -> Result<PixelFormat> {
+        PixelFormat::from_bits(self.image.format).ok_or_else(|| {
+            Error::InvalidInput(format!("Unknown source format: {:#x}", self.image.format))
+        })
+    }
+
+    /// Number of palette entries in the encoded image, zero if there is no palette.
+    pub fn source_colormap_entries(&self) -> u32 {
+        self.image.colormap_entries
+    }
+
+    /// Raw EXIF data of eXIf chunk before image data, parsed by [ExifInfo::parse].
+    pub fn exif(&self) -> Option<&[u8]> {
+        find_exif_chunk(&self.input)
+    }
+
+    pub fn with_format(mut self, format: PixelFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Color for compositing when alpha channel is removed, for grayscale output the green channel is used.
+    /// Without background the image is composited on black, except for 8-bit colormap output where it is required.
+    /// Ignored by **libpng** for linear formats.
+    pub fn with_background(mut self, background: Color) -> Self {
+        self.background = Some(background);
+        self
+    }
+
+    /// Size of output rows in bytes for padded data, the minimal one by default.
+    pub fn with_row_stride(mut self, row_stride: usize) -> Self {
+        self.row_stride = Some(row_stride);
+        self
+    }
+
+    /// `PNG_IMAGE_FLAG_16BIT_sRGB`, treats 16-bit images without gAMA or sRGB chunk as sRGB encoded instead of linear.
+    pub fn with_16_bit_srgb(mut self, srgb_16_bit: bool) -> Self {
+        self.srgb_16_bit = srgb_16_bit;
+        self
+    }
+
+    /// Rotates and flips decoded pixels by EXIF orientation, for upright display. Disabled by default.
+    /// Rows of transformed images have minimal size, ignoring [with_row_stride](Decoder::with_row_stride).
+    /// Missing or malformed EXIF data leaves the image unchanged.
+    pub fn with_exif_orientation(mut self, exif_orientation: bool) -> Self {
+        self.exif_orientation = exif_orientation;
+        self
+    }
+
+    /// Checks the image against `limits` on [decode](Decoder::decode), before pixels are allocated.
+    ///
+    /// The simplified API of **libpng** has no limits of its own: chunk sizes and counts are checked in the input,
+    /// text is not read, and the header is read on creation with default **libpng** limits.
+    /// Text and ICC profile size limits do not apply.
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    pub fn decode(mut self) -> Result<Image> {
+        if let Some(limits) = &self.limits {
+            limits.validate()?;
+            limits.check_dimensions(self.image.width, self.image.height)?;
+            limits.check_chunks(&self.input)?;
+        }
+
+        let orientation = match self.exif_orientation {
+            true => self
+                .exif()
+                .and_then(|exif| ExifInfo::parse(exif).ok())
+                .and_then(|info| info.orientation),
+            false => None,
+        };
+
+        self.image.format = self.format.bits();
+
+        if self.srgb_16_bit {
+            self.image.flags |= PNG_IMAGE_FLAG_16BIT_sRGB;
+        }
+
+        let component_size = self.format.component_size();
+        let min_row_stride = PNG_IMAGE_ROW_STRIDE(&self.image) * component_size;
+        let row_stride = self.row_stride.unwrap_or(min_row_stride);
+
+        if row_stride < min_row_stride || !row_stride.is_multiple_of(component_size) {
+            return Err(Error::InvalidInput(format!(
+                "Row stride {row_stride} must be a multiple of {component_size} and at least {min_row_stride}"
+            )));
+        }
+
+        let row_components = i32::try_from(row_stride / component_size)
+            .map_err(|_| Error::InvalidInput(format!("Row stride {row_stride} is too large")))?;
+
+        if let Some(limits) = &self.limits {
+            limits.check_decoded_bytes(row_stride, self.image.height)?;
+        }
+
+        // Colormap indices are bytes, linear colormap entries are 16-bit
+        let (data, colormap) = match (self.format.is_linear(), self.format.is_colormap()) {
+            (true, true) => {
+                let (data, colormap) = self.finish_read::<u8, u16>(row_components)?;
+                (data, to_ne_bytes(colormap))
+            }
+            (true, false) => {
+                let (data, colormap) = self.finish_read::<u16, u16>(row_components)?;
+                (to_ne_bytes(data), to_ne_bytes(colormap))
+            }
+            (false, _) => self.finish_read::<u8, u8>(row_components)?,
+        };
+
+        let image = Image {
+            width: self.image.width,
+            height: self.image.height,
+            format: self.format,
+            data,
+            row_stride,
+            colormap,
+        };
+
+        Ok(image.apply_orientation(orientation.unwrap_or(Orientation::Normal)))
+    }
+
+    /// Reads pixels and colormap into buffers of components, aligned for `D` and `C` respectively.
+    fn finish_read<D: Copy + Default, C: Copy + Default>(
+        &mut self,
+        row_components: i32,
+    ) -> Result<(Vec<D>, Vec<C>)> {
+        let component_size = std::mem::size_of::<C>();
+
+        let data_size = (self.image.height as usize)
+            .checked_mul(row_components as usize)
+            .ok_or_else(|| {
+                Error::InvalidInput(format!(
+                    "Image of {}x{} is too large",
+                    self.image.width, self.image.height
+                ))
+            })?;
+        let mut data = vec![D::default(); data_size];
+
+        let mut colormap = if self.format.is_colormap() {
+            // Palette images have at most 256 entries, libpng updates the count after reading
+            self.image.colormap_entries = 256;
+            vec![C::default(); PNG_IMAGE_COLORMAP_SIZE(&self.image) / component_size]
+        } else {
+            vec![]
+        };
+
+        let background = self.background.map(Color::to_png_color);
+
+        let status = unsafe {
+            png_image_finish_read(
+                &mut *self.image,
+                background
+                    .as_ref()
+                    .map_or(null(), |background| background as *const _),
+                data.as_mut_ptr() as *mut c_void,
+                row_components,
+                if colormap.is_empty() {
+                    null_mut()
+                } else {
+                    colormap.as_mut_ptr() as *mut c_void
+                },
+            )
+        };
+
+        if status != 1 || PNG_IMAGE_FAILED(&self.image) {
+            return Err(Error::from_message(&self.image.message));
+        }
+        log_image_warning(&self.image);
+
+        colormap.truncate(PNG_IMAGE_COLORMAP_SIZE(&self.image) / component_size);
+
+        Ok((data, colormap))
+    }
+}
+
+impl Drop for Decoder<'_> {
+    fn drop(&mut self) {
+        // Does nothing if libpng has already released the resources
+        unsafe { png_image_free(&mut *self.image) };
+    }
+}
+
+pub(crate) fn empty_image() -> Box<png_image> {
+    Box::new(png_image {
+        opaque: null_mut(),
+        version: PNG_IMAGE_VERSION,
+        width: 0,
+        height: 0,
+        format: 0,
+        flags: 0,
+        colormap_entries: 0,
+        warning_or_error: 0,
+        message: [0; 64],
+    })
+}
+
+/// The simplified API keeps only the last warning, without the chunk being read.
+fn log_image_warning(image: &png_image) {
+    if image.warning_or_error & PNG_IMAGE_WARNING != 0 {
+        log_warning(&Warning {
+            message: message_to_string(&image.message),
+            chunk: None,
+            row: None,
+        });
+    }
+}
+
+fn to_ne_bytes(components: Vec<u16>) -> Vec<u8> {
+    components
+        .into_iter()
+        .flat_map(|component| component.to_ne_bytes())
+        .collect()
+}
@@ -0,0 +1,298 @@
+use std::{ffi::CString, io::Write, path::Path, ptr::null};
+
+use libc::c_void;
+use libpng_vendored_sys::{
+    png_image, png_image_free, png_image_write_to_file, png_image_write_to_memory,
+    PNG_IMAGE_FAILED, PNG_IMAGE_FLAG_FAST, PNG_IMAGE_PNG_SIZE_MAX,
+};
+
+use crate::{decoder::empty_image, Error, Image, PixelFormat, Result};
+
+/// Encodes a whole image at once via the simplified **libpng** API.
+///
+/// ```no_run
+/// use libpng::{Encoder, PixelFormat};
+///
+/// let pixels = vec![0_u8; 64 * 64 * 3];
+///
+/// Encoder::new(&pixels, 64, 64, PixelFormat::RGB)
+///     .with_fast(true)
+///     .encode_to_path("image.png")?;
+/// # Ok::<(), libpng::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct Encoder<'a> {
+    data: &'a [u8],
+    width: u32,
+    height: u32,
+    format: PixelFormat,
+    row_stride: Option<usize>,
+    colormap: Option<&'a [u8]>,
+    fast: bool,
+    convert_to_8_bit: bool,
+}
+
+impl<'a> Encoder<'a> {
+    /// Encoder for rows of pixels in `format`, top to bottom.
+    /// Linear components are expected in native byte order.
+    pub fn new(data: &'a [u8], width: u32, height: u32, format: PixelFormat) -> Self {
+        Self {
+            data,
+            width,
+            height,
+            format,
+            row_stride: None,
+            colormap: None,
+            fast: false,
+            convert_to_8_bit: false,
+        }
+    }
+
+    /// Encoder for a decoded image, with its row stride and colormap.
+    pub fn from_image(image: &'a Image) -> Self {
+        let encoder = Self::new(&image.data, image.width, image.height, image.format)
+            .with_row_stride(image.row_stride);
+
+        if image.format.is_colormap() {
+            encoder.with_colormap(&image.colormap)
+        } else {
+            encoder
+        }
+    }
+
+    /// Size of input rows in bytes for padded data, the minimal one by default.
+    pub fn with_row_stride(mut self, row_stride: usize) -> Self {
+        self.row_stride = Some(row_stride);
+        self
+    }
+
+    /// Colormap entries for colormap formats, up to 256.
+    pub fn with_colormap(mut self, colormap: &'a [u8]) -> Self {
+        self.colormap = Some(colormap);
+        self
+    }
+
+    /// `PNG_IMAGE_FLAG_FAST`, emphasises speed over compression.
+    /// The result may be much larger, use it for intermediate files only.
+    pub fn with_fast(mut self, fast: bool) -> Self {
+        self.fast = fast;
+        self
+    }
+
+    /// Writes 8-bit sRGB encoded PNG for linear formats instead of 16-bit linear one.
+    /// Has no effect for 8-bit formats.
+    pub fn with_convert_to_8_bit(mut self, convert_to_8_bit: bool) -> Self {
+        self.convert_to_8_bit = convert_to_8_bit;
+        self
+    }
+
+    pub fn encode_to_vec(&self) -> Result<Vec<u8>> {
+        self.encode(|image, data, row_components, colormap| {
+            let mut output = vec![0_u8; PNG_IMAGE_PNG_SIZE_MAX(image)];
+
+            loop {
+                let mut output_size = output.len();
+
+                let status = unsafe {
+                    png_image_write_to_memory(
+                        image,
+                        output.as_mut_ptr() as *mut c_void,
+                        &mut output_size,
+                        self.convert_to_8_bit as i32,
+                        data,
+                        row_components,
+                        colormap,
+                    )
+                };
+
+                if status == 1 && !PNG_IMAGE_FAILED(image) {
+                    output.truncate(output_size);
+                    return Ok(output);
+                }
+
+                // The estimate may be exceeded for incompressible data, libpng reports the required size
+                if output_size > output.len() {
+                    output.resize(output_size, 0);
+                    image.warning_or_error = 0;
+                    continue;
+                }
+
+                return Err(Error::from_message(&image.message));
+            }
+        })
+    }
+
+    /// Writes directly to the file via `png_image_write_to_file`, the encoded image is not kept in memory.
+    /// File errors are reported by **libpng** as [Error::Png], non UTF-8 paths are supported on Unix only.
+    pub fn encode_to_path(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path_to_c_string(path.as_ref())?;
+
+        self.encode(|image, data, row_components, colormap| {
+            let status = unsafe {
+                png_image_write_to_file(
+                    image,
+                    path.as_ptr(),
+                    self.convert_to_8_bit as i32,
+                    data,
+                    row_components,
+                    colormap,
+                )
+            };
+
+            if status == 1 && !PNG_IMAGE_FAILED(image) {
+                Ok(())
+            } else {
+                Err(Error::from_message(&image.message))
+            }
+        })
+    }
+
+    pub fn encode_to_writer(&self, mut writer: impl Write) -> Result<()> {
+        Ok(writer.write_all(&self.encode_to_vec()?)?)
+    }
+
+    /// Validates input and calls `write` with the image, data, row stride in components and colormap.
+    fn encode<T>(
+        &self,
+        write: impl FnOnce(&mut png_image, *const c_void, i32, *const c_void) -> Result<T>,
+    ) -> Result<T> {
+        let row_components = self.validate()?;
+
+        // Linear components are read by libpng as 16-bit values, colormap indices are bytes
+        let aligned_data;
+        let data = if self.format.is_linear()
+            && !self.format.is_colormap()
+            && self.data.as_ptr().align_offset(2) != 0
+        {
+            aligned_data = to_ne_components(self.data);
+            aligned_data.as_ptr() as *const c_void
+        } else {
+            self.data.as_ptr() as *const c_void
+        };
+
+        let aligned_colormap;
+        let colormap = match self.colormap {
+            Some(colormap) if self.format.is_linear() && colormap.as_ptr().align_offset(2) != 0 => {
+                aligned_colormap = to_ne_components(colormap);
+                aligned_colormap.as_ptr() as *const c_void
+            }
+            Some(colormap) => colormap.as_ptr() as *const c_void,
+            None => null(),
+        };
+
+        let mut image = empty_image();
+        image.width = self.width;
+        image.height = self.height;
+        image.format = self.format.bits();
+        image.colormap_entries = self.colormap_entries() as u32;
+
+        if self.fast {
+            image.flags |= PNG_IMAGE_FLAG_FAST;
+        }
+
+        let result = write(&mut image, data, row_components, colormap);
+        unsafe { png_image_free(&mut *image) };
+
+        result
+    }
+
+    /// Checks buffer sizes, returns row stride in components.
+    fn validate(&self) -> Result<i32> {
+        if self.width == 0 || self.height == 0 {
+            return Err(Error::InvalidInput(format!(
+                "Image size must be positive, got {}x{}",
+                self.width, self.height
+            )));
+        }
+
+        let too_large = || {
+            Error::InvalidInput(format!(
+                "Image of {}x{} is too large",
+                self.width, self.height
+            ))
+        };
+
+        let component_size = self.format.component_size();
+        let min_row_stride = (self.width as usize)
+            .checked_mul(self.format.pixel_size())
+            .ok_or_else(too_large)?;
+        let row_stride = self.row_stride.unwrap_or(min_row_stride);
+
+        if row_stride < min_row_stride || !row_stride.is_multiple_of(component_size) {
+            return Err(Error::InvalidInput(format!(
+                "Row stride {row_stride} must be a multiple of {component_size} and at least {min_row_stride}"
+            )));
+        }
+
+        // Overflows on 32-bit targets would let short buffers through
+        let data_size = (self.height as usize - 1)
+            .checked_mul(row_stride)
+            .and_then(|size| size.checked_add(min_row_stride))
+            .ok_or_else(too_large)?;
+
+        if self.data.len() < data_size {
+            return Err(Error::InvalidInput(format!(
+                "Data is too short, expected at least {data_size} bytes, got {}",
+                self.data.len()
+            )));
+        }
+
+        match (self.format.is_colormap(), self.colormap) {
+            (true, None) => {
+                return Err(Error::InvalidInput(String::from(
+                    "Colormap is required for colormap formats",
+                )))
+            }
+            (false, Some(_)) => {
+                return Err(Error::InvalidInput(String::from(
+                    "Colormap is supported for colormap formats only",
+                )))
+            }
+            (true, Some(colormap)) => {
+                let entry_size = self.format.colormap_entry_size();
+
+                if colormap.is_empty()
+                    || colormap.len() > 256 * entry_size
+                    || !colormap.len().is_multiple_of(entry_size)
+                {
+                    return Err(Error::InvalidInput(format!(
+                        "Colormap must contain 1 to 256 entries of {entry_size} bytes, got {} bytes",
+                        colormap.len()
+                    )));
+                }
+            }
+            (false, None) => {}
+        }
+
+        i32::try_from(row_stride / component_size)
+            .map_err(|_| Error::InvalidInput(format!("Row stride {row_stride} is too large")))
+    }
+
+    fn colormap_entries(&self) -> usize {
+        self.colormap.map_or(0, |colormap| {
+            colormap.len() / self.format.colormap_entry_size()
+        })
+    }
+}
+
+#[cfg(unix)]
+fn path_to_c_string(path: &Path) -> Result<CString> {
+    use std::os::unix::ffi::OsStrExt;
+
+    CString::new(path.as_os_str().as_bytes())
+        .map_err(|_| Error::InvalidInput(format!("Path {path:?} contains a zero byte")))
+}
+
+#[cfg(not(unix))]
+fn path_to_c_string(path: &Path) -> Result<CString> {
+    path.to_str()
+        .and_then(|path| CString::new(path).ok())
+        .ok_or_else(|| Error::InvalidInput(format!("Path {path:?} is not valid for libpng")))
+}
+
+fn to_ne_components(bytes: &[u8]) -> Vec<u16> {
+    bytes
+        .chunks_exact(2)
+        .map(|component| u16::from_ne_bytes([component[0], component[1]]))
+        .collect()
+}
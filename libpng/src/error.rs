@@ -0,0 +1,62 @@
+use std::{error, ffi::CStr, fmt, io};
+
+use libc::c_char;
+
+/// Result type of the crate.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Errors reported by the crate.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing the underlying source or destination failed.
+    Io(io::Error),
+    /// **libpng** reported an error, with its message.
+    Png(String),
+    /// Arguments rejected before calling **libpng**.
+    InvalidInput(String),
+    /// The image exceeds [Limits](crate::Limits), reported before decoding it further.
+    LimitExceeded(String),
+}
+
+impl Error {
+    /// Error with the message from a zero-terminated **libpng** buffer.
+    pub(crate) fn from_message(message: &[c_char]) -> Self {
+        Self::Png(message_to_string(message))
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "I/O error: {err}"),
+            Self::Png(message) => write!(f, "libpng error: {message}"),
+            Self::InvalidInput(message) => write!(f, "Invalid input: {message}"),
+            Self::LimitExceeded(message) => write!(f, "Limit exceeded: {message}"),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Converts a fixed-size C message buffer, tolerating a missing terminator.
+pub(crate) fn message_to_string(message: &[c_char]) -> String {
+    let bytes: Vec<u8> = message.iter().map(|c| *c as u8).collect();
+
+    match CStr::from_bytes_until_nul(&bytes) {
+        Ok(message) => message.to_string_lossy().into_owned(),
+        Err(_) => String::from_utf8_lossy(&bytes).into_owned(),
+    }
+}
@@ -0,0 +1,318 @@
+use std::{ptr::null_mut, slice};
+
+use libc::c_int;
+use libpng_vendored_sys::{
+    png_bytep, png_get_eXIf_1, png_get_unknown_chunks, png_infop, png_set_keep_unknown_chunks,
+    png_structp, png_uint_32, png_unknown_chunkp, PNG_INFO_eXIf, PNG_HANDLE_CHUNK_ALWAYS,
+};
+
+use crate::{chunks::raw_chunks, Error, Result};
+
+const TAG_MAKE: u16 = 0x010f;
+const TAG_MODEL: u16 = 0x0110;
+const TAG_ORIENTATION: u16 = 0x0112;
+const TAG_DATE_TIME: u16 = 0x0132;
+const TAG_EXIF_IFD: u16 = 0x8769;
+const TAG_DATE_TIME_ORIGINAL: u16 = 0x9003;
+const TAG_LENS_MODEL: u16 = 0xa434;
+
+const TYPE_ASCII: u16 = 2;
+const TYPE_SHORT: u16 = 3;
+const TYPE_LONG: u16 = 4;
+
+/// Orientation of EXIF, the transform making the stored image upright.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    /// 1, stored upright.
+    Normal,
+    /// 2, mirrored left to right.
+    FlipHorizontal,
+    /// 3
+    Rotate180,
+    /// 4, mirrored top to bottom.
+    FlipVertical,
+    /// 5, mirrored across the top-left to bottom-right diagonal.
+    Transpose,
+    /// 6, rotated 90° clockwise for display.
+    Rotate90,
+    /// 7, mirrored across the top-right to bottom-left diagonal.
+    Transverse,
+    /// 8, rotated 270° clockwise for display.
+    Rotate270,
+}
+
+impl Orientation {
+    /// Orientation of the EXIF tag value from 1 to 8.
+    pub const fn from_exif(value: u16) -> Option<Self> {
+        match value {
+            1 => Some(Self::Normal),
+            2 => Some(Self::FlipHorizontal),
+            3 => Some(Self::Rotate180),
+            4 => Some(Self::FlipVertical),
+            5 => Some(Self::Transpose),
+            6 => Some(Self::Rotate90),
+            7 => Some(Self::Transverse),
+            8 => Some(Self::Rotate270),
+            _ => None,
+        }
+    }
+
+    pub const fn to_exif(self) -> u16 {
+        match self {
+            Self::Normal => 1,
+            Self::FlipHorizontal => 2,
+            Self::Rotate180 => 3,
+            Self::FlipVertical => 4,
+            Self::Transpose => 5,
+            Self::Rotate90 => 6,
+            Self::Transverse => 7,
+            Self::Rotate270 => 8,
+        }
+    }
+
+    /// Whether width and height are swapped by the transform.
+    pub const fn swaps_dimensions(self) -> bool {
+        matches!(
+            self,
+            Self::Transpose | Self::Rotate90 | Self::Transverse | Self::Rotate270
+        )
+    }
+
+    /// Position in a stored image of `width` by `height` pixels of the upright pixel at `x`, `y`.
+    pub(crate) const fn source(self, x: u32, y: u32, width: u32, height: u32) -> (u32, u32) {
+        let (right, bottom) = (width - 1, height - 1);
+
+        match self {
+            Self::Normal => (x, y),
+            Self::FlipHorizontal => (right - x, y),
+            Self::Rotate180 => (right - x, bottom - y),
+            Self::FlipVertical => (x, bottom - y),
+            Self::Transpose => (y, x),
+            Self::Rotate90 => (y, bottom - x),
+            Self::Transverse => (right - y, bottom - x),
+            Self::Rotate270 => (right - y, x),
+        }
+    }
+}
+
+/// Fields of EXIF data parsed by a minimal TIFF reader, from the first IFD and the EXIF IFD.
+///
+/// Only the listed tags are read, use a dedicated EXIF crate for others.
+/// ```
+/// use libpng::{ExifInfo, Orientation};
+///
+/// // Big-endian TIFF header and an IFD with orientation 6
+/// let exif = [
+///     b'M', b'M', 0, 42, 0, 0, 0, 8,
+///     0, 1, 0x01, 0x12, 0, 3, 0, 0, 0, 1, 0, 6, 0, 0,
+///     0, 0, 0, 0,
+/// ];
+///
+/// let info = ExifInfo::parse(&exif)?;
+/// assert_eq!(info.orientation, Some(Orientation::Rotate90));
+/// # Ok::<(), libpng::Error>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ExifInfo {
+    pub orientation: Option<Orientation>,
+    /// Capture time of DateTimeOriginal, like `2024:05:17 14:03:59`.
+    pub date_time_original: Option<String>,
+    /// Modification time of DateTime.
+    pub date_time: Option<String>,
+    /// Camera manufacturer.
+    pub make: Option<String>,
+    /// Camera model.
+    pub model: Option<String>,
+    pub lens_model: Option<String>,
+}
+
+impl ExifInfo {
+    /// Parses TIFF structured EXIF data of eXIf chunk, an `Exif\0\0` prefix of JPEG APP1 segments is skipped.
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let data = data.strip_prefix(b"Exif\0\0").unwrap_or(data);
+        let tiff = Tiff::new(data)?;
+
+        let mut info = Self::default();
+        let mut exif_ifd = None;
+
+        for entry in tiff.entries(tiff.u32(4)?)? {
+            match entry.tag {
+                TAG_MAKE => info.make = tiff.ascii(&entry),
+                TAG_MODEL => info.model = tiff.ascii(&entry),
+                TAG_DATE_TIME => info.date_time = tiff.ascii(&entry),
+                TAG_ORIENTATION => {
+                    info.orientation = tiff.short(&entry).and_then(Orientation::from_exif);
+                }
+                TAG_EXIF_IFD if entry.kind == TYPE_LONG && entry.count == 1 => {
+                    exif_ifd = Some(tiff.u32(entry.value_offset)?);
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(offset) = exif_ifd {
+            for entry in tiff.entries(offset)? {
+                match entry.tag {
+                    TAG_DATE_TIME_ORIGINAL => info.date_time_original = tiff.ascii(&entry),
+                    TAG_LENS_MODEL => info.lens_model = tiff.ascii(&entry),
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(info)
+    }
+}
+
+/// TIFF data with its byte order.
+struct Tiff<'a> {
+    data: &'a [u8],
+    big_endian: bool,
+}
+
+/// IFD entry, the value is stored in place if it fits into 4 bytes.
+struct Entry {
+    tag: u16,
+    kind: u16,
+    count: u32,
+    /// Position of the 4-byte value or offset field.
+    value_offset: usize,
+}
+
+impl<'a> Tiff<'a> {
+    fn new(data: &'a [u8]) -> Result<Self> {
+        let big_endian = match data.get(..4) {
+            Some(b"MM\0*") => true,
+            Some(b"II*\0") => false,
+            _ => return Err(invalid("missing TIFF header")),
+        };
+
+        Ok(Self { data, big_endian })
+    }
+
+    fn bytes<const N: usize>(&self, offset: usize) -> Result<[u8; N]> {
+        offset
+            .checked_add(N)
+            .and_then(|end| self.data.get(offset..end))
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| invalid("offset out of bounds"))
+    }
+
+    fn u16(&self, offset: usize) -> Result<u16> {
+        let bytes = self.bytes(offset)?;
+
+        Ok(match self.big_endian {
+            true => u16::from_be_bytes(bytes),
+            false => u16::from_le_bytes(bytes),
+        })
+    }
+
+    fn u32(&self, offset: usize) -> Result<usize> {
+        let bytes = self.bytes(offset)?;
+
+        Ok(match self.big_endian {
+            true => u32::from_be_bytes(bytes),
+            false => u32::from_le_bytes(bytes),
+        } as usize)
+    }
+
+    fn entries(&self, offset: usize) -> Result<Vec<Entry>> {
+        let count = usize::from(self.u16(offset)?);
+
+        (0..count)
+            .map(|index| {
+                let start = offset + 2 + index * 12;
+
+                Ok(Entry {
+                    tag: self.u16(start)?,
+                    kind: self.u16(start + 2)?,
+                    count: self.u32(start + 4)? as u32,
+                    value_offset: start + 8,
+                })
+            })
+            .collect()
+    }
+
+    fn short(&self, entry: &Entry) -> Option<u16> {
+        (entry.kind == TYPE_SHORT && entry.count >= 1)
+            .then(|| self.u16(entry.value_offset).ok())
+            .flatten()
+    }
+
+    /// ASCII value without the terminator and trailing spaces, `None` if malformed or empty.
+    fn ascii(&self, entry: &Entry) -> Option<String> {
+        if entry.kind != TYPE_ASCII {
+            return None;
+        }
+
+        let length = entry.count as usize;
+        let start = match length {
+            0..=4 => entry.value_offset,
+            _ => self.u32(entry.value_offset).ok()?,
+        };
+        let bytes = self.data.get(start..start.checked_add(length)?)?;
+
+        let value = bytes.split(|&b| b == 0).next().unwrap_or_default();
+        let value = String::from_utf8_lossy(value).trim_end().to_string();
+
+        (!value.is_empty()).then_some(value)
+    }
+}
+
+fn invalid(message: &str) -> Error {
+    Error::InvalidInput(format!("Invalid EXIF data: {message}"))
+}
+
+/// Checks EXIF data before writing, **libpng** drops eXIf chunks without a TIFF byte order on read.
+pub(crate) fn validate_exif(data: &[u8]) -> Result<()> {
+    if !(data.starts_with(b"MM") || data.starts_with(b"II")) {
+        return Err(invalid("must start with TIFF byte order"));
+    }
+
+    if u32::try_from(data.len()).is_err() {
+        return Err(invalid("too large"));
+    }
+
+    Ok(())
+}
+
+/// Keeps eXIf chunks as unknown chunks, since the progressive reader of some **libpng** versions skips them.
+pub(crate) unsafe fn keep_exif_chunk(png_ptr: png_structp) {
+    png_set_keep_unknown_chunks(
+        png_ptr,
+        PNG_HANDLE_CHUNK_ALWAYS as c_int,
+        c"eXIf".as_ptr().cast(),
+        1,
+    );
+}
+
+/// EXIF data of eXIf chunk read into `info_ptr`, either known or kept by [keep_exif_chunk].
+pub(crate) unsafe fn get_exif(png_ptr: png_structp, info_ptr: png_infop) -> Option<Vec<u8>> {
+    let mut length: png_uint_32 = 0;
+    let mut data: png_bytep = null_mut();
+
+    if png_get_eXIf_1(png_ptr, info_ptr, &mut length, &mut data) & PNG_INFO_eXIf != 0
+        && !data.is_null()
+    {
+        return Some(slice::from_raw_parts(data, length as usize).to_vec());
+    }
+
+    let mut chunks: png_unknown_chunkp = null_mut();
+    let count = png_get_unknown_chunks(png_ptr, info_ptr, &mut chunks);
+    if count <= 0 || chunks.is_null() {
+        return None;
+    }
+
+    slice::from_raw_parts(chunks, count as usize)
+        .iter()
+        .find(|chunk| chunk.name[..4] == *b"eXIf" && !chunk.data.is_null())
+        .map(|chunk| slice::from_raw_parts(chunk.data, chunk.size).to_vec())
+}
+
+/// Finds eXIf chunk data in PNG data without decoding it, for the simplified API.
+pub(crate) fn find_exif_chunk(png: &[u8]) -> Option<&[u8]> {
+    raw_chunks(png)
+        .find(|(name, _, _)| name == b"eXIf")
+        .filter(|(_, length, data)| data.len() == *length)
+        .map(|(_, _, data)| data)
+}
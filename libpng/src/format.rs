@@ -0,0 +1,206 @@
+use std::fmt;
+
+use libpng_vendored_sys::{
+    png_color, PNG_COLOR_TYPE_GRAY, PNG_COLOR_TYPE_GRAY_ALPHA, PNG_COLOR_TYPE_PALETTE,
+    PNG_COLOR_TYPE_RGB, PNG_COLOR_TYPE_RGB_ALPHA, PNG_FORMAT_ABGR, PNG_FORMAT_AG, PNG_FORMAT_ARGB,
+    PNG_FORMAT_BGR, PNG_FORMAT_BGRA, PNG_FORMAT_FLAG_AFIRST, PNG_FORMAT_FLAG_ALPHA,
+    PNG_FORMAT_FLAG_ASSOCIATED_ALPHA, PNG_FORMAT_FLAG_BGR, PNG_FORMAT_FLAG_COLOR,
+    PNG_FORMAT_FLAG_COLORMAP, PNG_FORMAT_FLAG_LINEAR, PNG_FORMAT_GA, PNG_FORMAT_GRAY,
+    PNG_FORMAT_LINEAR_RGB, PNG_FORMAT_LINEAR_RGB_ALPHA, PNG_FORMAT_LINEAR_Y,
+    PNG_FORMAT_LINEAR_Y_ALPHA, PNG_FORMAT_RGB, PNG_FORMAT_RGBA, PNG_IMAGE_PIXEL_CHANNELS,
+    PNG_IMAGE_PIXEL_COMPONENT_SIZE, PNG_IMAGE_PIXEL_SIZE, PNG_IMAGE_SAMPLE_SIZE,
+};
+
+/// All `PNG_FORMAT_FLAG_*` bits understood by the simplified API of **libpng** 1.6.
+const KNOWN_FLAGS: u32 = PNG_FORMAT_FLAG_ALPHA
+    | PNG_FORMAT_FLAG_COLOR
+    | PNG_FORMAT_FLAG_LINEAR
+    | PNG_FORMAT_FLAG_COLORMAP
+    | PNG_FORMAT_FLAG_BGR
+    | PNG_FORMAT_FLAG_AFIRST
+    | PNG_FORMAT_FLAG_ASSOCIATED_ALPHA;
+
+/// Pixel layout of decoded or encoded image data, wraps `PNG_FORMAT_*` values of the simplified API.
+///
+/// Components are 8-bit sRGB encoded, or 16-bit linear in native byte order for `LINEAR_*` formats.
+/// With [colormap](PixelFormat::colormap) each pixel is a single byte index into the colormap,
+/// the format of colormap entries is described by the other flags.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PixelFormat(u32);
+
+impl PixelFormat {
+    pub const GRAY: Self = Self(PNG_FORMAT_GRAY);
+    pub const GRAY_ALPHA: Self = Self(PNG_FORMAT_GA);
+    pub const ALPHA_GRAY: Self = Self(PNG_FORMAT_AG);
+    pub const RGB: Self = Self(PNG_FORMAT_RGB);
+    pub const BGR: Self = Self(PNG_FORMAT_BGR);
+    pub const RGBA: Self = Self(PNG_FORMAT_RGBA);
+    pub const ARGB: Self = Self(PNG_FORMAT_ARGB);
+    pub const BGRA: Self = Self(PNG_FORMAT_BGRA);
+    pub const ABGR: Self = Self(PNG_FORMAT_ABGR);
+    pub const LINEAR_GRAY: Self = Self(PNG_FORMAT_LINEAR_Y);
+    pub const LINEAR_GRAY_ALPHA: Self = Self(PNG_FORMAT_LINEAR_Y_ALPHA);
+    pub const LINEAR_RGB: Self = Self(PNG_FORMAT_LINEAR_RGB);
+    pub const LINEAR_RGBA: Self = Self(PNG_FORMAT_LINEAR_RGB_ALPHA);
+
+    /// Wraps raw `PNG_FORMAT_*` value, `None` for unknown flags.
+    pub const fn from_bits(bits: u32) -> Option<Self> {
+        if bits & !KNOWN_FLAGS != 0 {
+            None
+        } else {
+            Some(Self(bits))
+        }
+    }
+
+    /// Wraps raw `PNG_FORMAT_*` value, dropping unknown flags.
+    pub const fn from_bits_truncate(bits: u32) -> Self {
+        Self(bits & KNOWN_FLAGS)
+    }
+
+    /// Raw `PNG_FORMAT_*` value.
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// The same format with colormap indices instead of pixel components,
+    /// colormap entries keep the original layout.
+    pub const fn colormap(self) -> Self {
+        Self(self.0 | PNG_FORMAT_FLAG_COLORMAP)
+    }
+
+    pub const fn has_alpha(self) -> bool {
+        self.0 & PNG_FORMAT_FLAG_ALPHA != 0
+    }
+
+    pub const fn is_color(self) -> bool {
+        self.0 & PNG_FORMAT_FLAG_COLOR != 0
+    }
+
+    pub const fn is_linear(self) -> bool {
+        self.0 & PNG_FORMAT_FLAG_LINEAR != 0
+    }
+
+    pub const fn is_colormap(self) -> bool {
+        self.0 & PNG_FORMAT_FLAG_COLORMAP != 0
+    }
+
+    /// Number of components per pixel, 1 for colormap formats.
+    pub const fn channels(self) -> usize {
+        PNG_IMAGE_PIXEL_CHANNELS(self.0)
+    }
+
+    /// Size of a single component in bytes, 1 for colormap formats.
+    pub const fn component_size(self) -> usize {
+        PNG_IMAGE_PIXEL_COMPONENT_SIZE(self.0)
+    }
+
+    /// Size of a pixel in bytes.
+    pub const fn pixel_size(self) -> usize {
+        PNG_IMAGE_PIXEL_SIZE(self.0)
+    }
+
+    /// Size of a colormap entry in bytes, the same as pixel size without colormap flag.
+    pub const fn colormap_entry_size(self) -> usize {
+        PNG_IMAGE_SAMPLE_SIZE(self.0)
+    }
+}
+
+impl fmt::Debug for PixelFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let linear = if self.is_linear() { "LINEAR_" } else { "" };
+
+        let components = match (self.is_color(), self.has_alpha()) {
+            (false, false) => "GRAY",
+            (false, true) if self.0 & PNG_FORMAT_FLAG_AFIRST != 0 => "ALPHA_GRAY",
+            (false, true) => "GRAY_ALPHA",
+            (true, alpha) => {
+                let bgr = self.0 & PNG_FORMAT_FLAG_BGR != 0;
+                let afirst = self.0 & PNG_FORMAT_FLAG_AFIRST != 0;
+
+                match (bgr, alpha, afirst) {
+                    (false, false, _) => "RGB",
+                    (true, false, _) => "BGR",
+                    (false, true, false) => "RGBA",
+                    (false, true, true) => "ARGB",
+                    (true, true, false) => "BGRA",
+                    (true, true, true) => "ABGR",
+                }
+            }
+        };
+
+        let colormap = if self.is_colormap() { "_COLORMAP" } else { "" };
+
+        write!(f, "PixelFormat::{linear}{components}{colormap}")
+    }
+}
+
+/// 8-bit sRGB color, layout compatible with `png_color`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+impl Color {
+    pub const fn new(red: u8, green: u8, blue: u8) -> Self {
+        Self { red, green, blue }
+    }
+
+    pub(crate) const fn to_png_color(self) -> png_color {
+        png_color {
+            red: self.red,
+            green: self.green,
+            blue: self.blue,
+        }
+    }
+}
+
+/// Color type of encoded image data, `PNG_COLOR_TYPE_*` values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ColorType {
+    Gray,
+    GrayAlpha,
+    Rgb,
+    Rgba,
+    Palette,
+}
+
+impl ColorType {
+    pub(crate) const fn from_png(color_type: u8) -> Option<Self> {
+        match color_type as u32 {
+            PNG_COLOR_TYPE_GRAY => Some(Self::Gray),
+            PNG_COLOR_TYPE_GRAY_ALPHA => Some(Self::GrayAlpha),
+            PNG_COLOR_TYPE_RGB => Some(Self::Rgb),
+            PNG_COLOR_TYPE_RGB_ALPHA => Some(Self::Rgba),
+            PNG_COLOR_TYPE_PALETTE => Some(Self::Palette),
+            _ => None,
+        }
+    }
+
+    pub(crate) const fn to_png(self) -> u8 {
+        let color_type = match self {
+            Self::Gray => PNG_COLOR_TYPE_GRAY,
+            Self::GrayAlpha => PNG_COLOR_TYPE_GRAY_ALPHA,
+            Self::Rgb => PNG_COLOR_TYPE_RGB,
+            Self::Rgba => PNG_COLOR_TYPE_RGB_ALPHA,
+            Self::Palette => PNG_COLOR_TYPE_PALETTE,
+        };
+
+        color_type as u8
+    }
+
+    /// Number of samples per pixel, palette indices are a single sample.
+    pub const fn channels(self) -> u8 {
+        match self {
+            Self::Gray | Self::Palette => 1,
+            Self::GrayAlpha => 2,
+            Self::Rgb => 3,
+            Self::Rgba => 4,
+        }
+    }
+
+    pub const fn has_alpha(self) -> bool {
+        matches!(self, Self::GrayAlpha | Self::Rgba)
+    }
+}
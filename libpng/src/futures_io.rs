@@ -0,0 +1,243 @@
+//! Adapters for [futures-io](https://docs.rs/futures-io) [AsyncRead] and [AsyncWrite], enabled by `futures-io` feature.
+//!
+//! **libpng** never waits for I/O: [AsyncDecoder] feeds read data to a [ProgressiveDecoder](crate::ProgressiveDecoder),
+//! [AsyncEncoder] writes rows to a buffer of a [StreamEncoder] and sends it to the writer.
+use std::{future::poll_fn, io, ops::ControlFlow, pin::Pin};
+
+use futures_io::{AsyncRead, AsyncWrite};
+
+use crate::{
+    async_io::AsyncInput, ColorInfo, ModificationTime, PhysicalInfo, PixelInfo, ProgressiveEvent,
+    ReadOptions, Result, StreamEncoder, StreamHeader, StreamInfo, TextChunk, Transforms,
+    UnknownChunk, Warning,
+};
+
+/// Decodes a PNG image from an [AsyncRead], reporting [ProgressiveEvent]s.
+/// ```no_run
+/// # async fn example(file: impl futures_io::AsyncRead + Unpin) -> libpng::Result<()> {
+/// use std::ops::ControlFlow;
+///
+/// use libpng::{futures_io::AsyncDecoder, ProgressiveEvent};
+///
+/// let mut decoder = AsyncDecoder::new(file)?;
+///
+/// decoder
+///     .decode(|event| {
+///         if let ProgressiveEvent::Row { position, .. } = event {
+///             println!("Row {} of pass {}", position.y, position.pass);
+///         }
+///
+///         ControlFlow::Continue(())
+///     })
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct AsyncDecoder<R> {
+    reader: R,
+    input: AsyncInput,
+}
+
+impl<R: AsyncRead + Unpin> AsyncDecoder<R> {
+    pub fn new(reader: R) -> Result<Self> {
+        Self::with_options(reader, ReadOptions::new())
+    }
+
+    /// Decoder handling chunks according to `options`, see [ProgressiveDecoder::with_options](crate::ProgressiveDecoder::with_options).
+    pub fn with_options(reader: R, options: ReadOptions) -> Result<Self> {
+        Ok(Self {
+            reader,
+            input: AsyncInput::new(options)?,
+        })
+    }
+
+    /// Applies `transforms` when the header is read, see [ProgressiveDecoder::with_transforms](crate::ProgressiveDecoder::with_transforms).
+    pub fn with_transforms(mut self, transforms: Transforms) -> Self {
+        self.input.decoder = self.input.decoder.with_transforms(transforms);
+        self
+    }
+
+    /// Row info, available after the [Header](ProgressiveEvent::Header) event.
+    pub fn info(&self) -> Option<&StreamInfo> {
+        self.input.decoder.info()
+    }
+
+    /// Color chunks, available after the [Header](ProgressiveEvent::Header) event.
+    pub fn color_info(&self) -> ColorInfo {
+        self.input.decoder.color_info()
+    }
+
+    /// Physical chunks, available after the [Header](ProgressiveEvent::Header) event.
+    pub fn physical_info(&self) -> PhysicalInfo {
+        self.input.decoder.physical_info()
+    }
+
+    /// Palette and pixel chunks, available after the [Header](ProgressiveEvent::Header) event.
+    pub fn pixel_info(&self) -> PixelInfo {
+        self.input.decoder.pixel_info()
+    }
+
+    /// Time of tIME chunk, see [ProgressiveDecoder::modification_time](crate::ProgressiveDecoder::modification_time).
+    pub fn modification_time(&self) -> Option<ModificationTime> {
+        self.input.decoder.modification_time()
+    }
+
+    /// Raw EXIF data, available after the [Header](ProgressiveEvent::Header) event.
+    pub fn exif(&self) -> Option<Vec<u8>> {
+        self.input.decoder.exif()
+    }
+
+    /// Warnings so far, see [ProgressiveDecoder::warnings](crate::ProgressiveDecoder::warnings).
+    pub fn warnings(&self) -> Vec<Warning> {
+        self.input.decoder.warnings()
+    }
+
+    /// Unknown chunks kept so far, see [ProgressiveDecoder::unknown_chunks](crate::ProgressiveDecoder::unknown_chunks).
+    pub fn unknown_chunks(&self) -> Vec<UnknownChunk> {
+        self.input.decoder.unknown_chunks()
+    }
+
+    /// Text chunks read so far, see [ProgressiveDecoder::text](crate::ProgressiveDecoder::text).
+    pub fn text(&self) -> Vec<TextChunk> {
+        self.input.decoder.text()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.input.decoder.is_finished()
+    }
+
+    /// Reads and decodes data until the end of the image or until the handler pauses decoding,
+    /// in the latter case the next call continues from the pause.
+    pub async fn decode<F>(&mut self, mut handler: F) -> Result<()>
+    where
+        F: FnMut(ProgressiveEvent<'_>) -> ControlFlow<()>,
+    {
+        while !self.is_finished() {
+            if self.input.is_empty() {
+                let length = read(&mut self.reader, self.input.buffer_mut()).await?;
+                if length == 0 {
+                    return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+                }
+
+                self.input.fill(length);
+            }
+
+            if self.input.feed(&mut handler)?.is_break() {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the underlying reader. It may be positioned after the end of the image, since data is read in blocks.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// Encodes a non-interlaced PNG image row by row to an [AsyncWrite], see [StreamEncoder].
+/// ```no_run
+/// # async fn example(file: impl futures_io::AsyncWrite + Unpin) -> libpng::Result<()> {
+/// use libpng::{futures_io::AsyncEncoder, ColorType, StreamHeader};
+///
+/// let mut encoder = AsyncEncoder::new(file, StreamHeader::new(1024, 768, ColorType::Rgb))?;
+///
+/// let row = vec![0; encoder.row_bytes()];
+/// for _ in 0..768 {
+///     encoder.write_row(&row).await?;
+/// }
+///
+/// encoder.finish().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct AsyncEncoder<W> {
+    encoder: StreamEncoder<Vec<u8>>,
+    writer: W,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncEncoder<W> {
+    /// Validates the header, it's written along with the first row.
+    pub fn new(writer: W, header: StreamHeader) -> Result<Self> {
+        Ok(Self {
+            encoder: StreamEncoder::new(Vec::new(), header)?,
+            writer,
+        })
+    }
+
+    pub fn header(&self) -> &StreamHeader {
+        self.encoder.header()
+    }
+
+    /// Exact size of a row in bytes.
+    pub fn row_bytes(&self) -> usize {
+        self.encoder.row_bytes()
+    }
+
+    /// See [StreamEncoder::set_flush_interval].
+    pub fn set_flush_interval(&mut self, rows: u32) {
+        self.encoder.set_flush_interval(rows);
+    }
+
+    /// See [StreamEncoder::add_text].
+    pub fn add_text(&mut self, chunk: TextChunk) -> Result<()> {
+        self.encoder.add_text(chunk)
+    }
+
+    /// Writes the next row, which must be exactly [row_bytes](AsyncEncoder::row_bytes) long.
+    pub async fn write_row(&mut self, row: &[u8]) -> Result<()> {
+        self.encoder.write_row(row)?;
+        self.write_buffered().await
+    }
+
+    /// Flushes compressed data of the written rows and the writer itself.
+    pub async fn flush(&mut self) -> Result<()> {
+        self.encoder.flush()?;
+        self.write_buffered().await?;
+
+        Ok(flush(&mut self.writer).await?)
+    }
+
+    /// Writes the end of the image after all rows and returns the flushed writer.
+    pub async fn finish(mut self) -> Result<W> {
+        let buffer = self.encoder.finish()?;
+
+        write_all(&mut self.writer, &buffer).await?;
+        flush(&mut self.writer).await?;
+
+        Ok(self.writer)
+    }
+
+    async fn write_buffered(&mut self) -> Result<()> {
+        let buffer = self.encoder.writer_mut();
+
+        if !buffer.is_empty() {
+            write_all(&mut self.writer, buffer).await?;
+            buffer.clear();
+        }
+
+        Ok(())
+    }
+}
+
+async fn read<R: AsyncRead + Unpin>(reader: &mut R, buffer: &mut [u8]) -> io::Result<usize> {
+    poll_fn(|cx| Pin::new(&mut *reader).poll_read(cx, buffer)).await
+}
+
+async fn write_all<W: AsyncWrite + Unpin>(writer: &mut W, mut data: &[u8]) -> io::Result<()> {
+    while !data.is_empty() {
+        let written = poll_fn(|cx| Pin::new(&mut *writer).poll_write(cx, data)).await?;
+        if written == 0 {
+            return Err(io::ErrorKind::WriteZero.into());
+        }
+
+        data = &data[written..];
+    }
+
+    Ok(())
+}
+
+async fn flush<W: AsyncWrite + Unpin>(writer: &mut W) -> io::Result<()> {
+    poll_fn(|cx| Pin::new(&mut *writer).poll_flush(cx)).await
+}
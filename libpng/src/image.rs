@@ -0,0 +1,71 @@
+use crate::{Orientation, PixelFormat};
+
+/// Decoded image with owned pixel data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub format: PixelFormat,
+    /// Rows of pixels, top to bottom, each [row_stride](Image::row_stride) bytes long.
+    pub data: Vec<u8>,
+    /// Size of a row in bytes, may exceed `width * format.pixel_size()` if padding was requested.
+    pub row_stride: usize,
+    /// Colormap entries for colormap formats, empty otherwise.
+    pub colormap: Vec<u8>,
+}
+
+impl Image {
+    /// Pixels of row `y`, without padding.
+    ///
+    /// # Panics
+    /// If `y` is out of bounds.
+    pub fn row(&self, y: u32) -> &[u8] {
+        assert!(y < self.height, "Row {y} is out of bounds");
+
+        let start = (y as usize) * self.row_stride;
+        &self.data[start..start + (self.width as usize) * self.format.pixel_size()]
+    }
+
+    /// Number of colormap entries, zero for formats without colormap.
+    pub fn colormap_entries(&self) -> usize {
+        if self.colormap.is_empty() {
+            return 0;
+        }
+
+        self.colormap.len() / self.format.colormap_entry_size()
+    }
+
+    /// Image transformed by EXIF `orientation` to be displayed upright, with minimal row stride.
+    /// Width and height are swapped for rotations by 90° and transpositions.
+    pub fn apply_orientation(self, orientation: Orientation) -> Image {
+        if orientation == Orientation::Normal {
+            return self;
+        }
+
+        let (width, height) = match orientation.swaps_dimensions() {
+            true => (self.height, self.width),
+            false => (self.width, self.height),
+        };
+        let pixel_size = self.format.pixel_size();
+        let row_stride = (width as usize) * pixel_size;
+
+        let mut data = Vec::with_capacity(row_stride * (height as usize));
+        for y in 0..height {
+            for x in 0..width {
+                let (source_x, source_y) = orientation.source(x, y, self.width, self.height);
+                let start =
+                    (source_y as usize) * self.row_stride + (source_x as usize) * pixel_size;
+                data.extend_from_slice(&self.data[start..start + pixel_size]);
+            }
+        }
+
+        Image {
+            width,
+            height,
+            format: self.format,
+            data,
+            row_stride,
+            colormap: self.colormap,
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Safe Rust bindings for [libpng](https://github.com/pnggroup/libpng), vendored as a static library
+//! via [libpng-vendored-sys](https://crates.io/crates/libpng-vendored-sys).
+//!
+//! [Decoder] reads whole images via the simplified **libpng** API:
+//! ```no_run
+//! use libpng::{Decoder, PixelFormat};
+//!
+//! let image = Decoder::from_path("image.png")?
+//!     .with_format(PixelFormat::RGBA)
+//!     .decode()?;
+//!
+//! println!("{}x{}, {} bytes", image.width, image.height, image.data.len());
+//! # Ok::<(), libpng::Error>(())
+//! ```
+//!
+//! [Encoder] writes them back to memory, a file or any [Write](std::io::Write) implementation:
+//! ```no_run
+//! # let image = libpng::Decoder::from_path("image.png")?.decode()?;
+//! libpng::Encoder::from_image(&image).encode_to_path("copy.png")?;
+//! # Ok::<(), libpng::Error>(())
+//! ```
+//!
+//! [StreamDecoder] decodes large images row by row from any [Read](std::io::Read) implementation
+//! via the full **libpng** API, `png_error` is caught by a C trampoline from **libpng-vendored-sys**.
+//! [StreamEncoder] writes them row by row to any [Write](std::io::Write) implementation.
+//! [ProgressiveDecoder] is fed with data as it arrives and reports decoded rows via callbacks.
+//! Both decode rows in the encoded format unless [Transforms] are set, [ReadOptions] configure
+//! handling of unknown chunks, [Limits] for untrusted input and [Strictness] for damaged one.
+//!
+//! `tokio` and `futures-io` features enable modules with the same names, providing async adapters
+//! for progressive decoding and streaming encoding. `chrono` and `time` features enable conversions
+//! of [ModificationTime] from and to the types of the crates with the same names. `log` and `tracing`
+//! features forward **libpng** warnings to the crates with the same names, with the `libpng` target.
+
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+mod async_io;
+mod chunks;
+mod color;
+mod decoder;
+mod encoder;
+mod error;
+mod exif;
+mod format;
+#[cfg(feature = "futures-io")]
+pub mod futures_io;
+mod image;
+mod limits;
+mod logging;
+mod modification_time;
+mod physical;
+mod pixel;
+mod png_struct;
+mod progressive_decoder;
+mod read_options;
+mod stream_decoder;
+mod stream_encoder;
+mod strictness;
+mod text;
+#[cfg(feature = "tokio")]
+pub mod tokio;
+mod transforms;
+
+pub use chunks::{ChunkLocation, ChunkName, KeepPolicy, UnknownChunk};
+pub use color::{
+    Chromaticities, ColorInfo, Colorants, IccProfile, RenderingIntent, Xy, Xyz,
+    SRGB_CHROMATICITIES, SRGB_GAMMA,
+};
+pub use decoder::Decoder;
+pub use encoder::Encoder;
+pub use error::{Error, Result};
+pub use exif::{ExifInfo, Orientation};
+pub use format::{Color, ColorType, PixelFormat};
+pub use image::Image;
+pub use limits::Limits;
+pub use modification_time::ModificationTime;
+pub use physical::{
+    Calibration, Equation, Offset, OffsetUnit, PhysicalInfo, PhysicalScale, PixelsPerUnit,
+    ResolutionUnit, ScaleUnit,
+};
+pub use pixel::{
+    Background, Histogram, Palette, PixelInfo, SignificantBits, SuggestedPalette,
+    SuggestedPaletteEntry, TransparentColor,
+};
+pub use progressive_decoder::{ProgressiveDecoder, ProgressiveEvent};
+pub use read_options::ReadOptions;
+pub use stream_decoder::{RowPosition, StreamDecoder, StreamInfo};
+pub use stream_encoder::{StreamEncoder, StreamHeader};
+pub use strictness::{Strictness, Warning};
+pub use text::{TextChunk, TEXT_COMPRESSION_THRESHOLD};
+pub use transforms::{
+    AlphaMode, BackgroundColor, BackgroundGamma, FillerPosition, Gamma, Transforms,
+};
@@ -0,0 +1,277 @@
+use std::ptr::null_mut;
+
+use libc::c_int;
+use libpng_vendored_sys::{
+    png_bytep, png_charp, png_get_iCCP, png_get_image_height, png_get_image_width, png_get_text,
+    png_infop, png_set_chunk_cache_max, png_set_chunk_malloc_max, png_set_user_limits, png_structp,
+    png_textp, png_uint_32, PNG_INFO_iCCP,
+};
+
+use crate::{chunks::raw_chunks, ChunkName, Error, Result};
+
+/// `PNG_USER_WIDTH_MAX` and `PNG_USER_HEIGHT_MAX` of **libpng**, not exported by the bindings.
+const MAX_DIMENSION: u32 = 0x7fff_ffff;
+
+/// Default limits of **libpng**.
+const DEFAULT_MAX_DIMENSION: u32 = 1_000_000;
+const DEFAULT_MAX_CHUNK_COUNT: u32 = 1000;
+const DEFAULT_MAX_CHUNK_SIZE: usize = 8_000_000;
+
+/// Parts of **libpng** messages about exceeded chunk limits, reported as warnings or errors.
+const LIMIT_MESSAGES: [&str; 4] = [
+    "no space in chunk cache",
+    "chunk data is too large",
+    "exceeds memory limits",
+    "exceeds application limits",
+];
+
+/// Resource limits for untrusted input, exceeding them fails decoding with [Error::LimitExceeded]
+/// before large allocations. Defaults are those of **libpng**, with unlimited decoded size:
+/// ```
+/// use libpng::Limits;
+///
+/// let limits = Limits {
+///     max_width: 8192,
+///     max_height: 8192,
+///     max_decoded_bytes: 256 << 20,
+///     ..Limits::default()
+/// };
+/// assert_eq!(limits.max_chunk_count, 1000);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Limits {
+    /// From 1 to 2^31 - 1.
+    pub max_width: u32,
+    /// From 1 to 2^31 - 1.
+    pub max_height: u32,
+    /// Size of all decoded rows after transforms, or of the output buffer of [Decoder](crate::Decoder).
+    pub max_decoded_bytes: usize,
+    /// Number of stored ancillary chunks: text, sPLT and unknown ones.
+    pub max_chunk_count: u32,
+    /// Data size of any chunk except IDAT, at least 1. **libpng** drops text and ICC profiles
+    /// that decompress to a larger size.
+    pub max_chunk_size: usize,
+    /// Size of text of a text chunk, after decompression.
+    pub max_text_size: usize,
+    /// Size of a decompressed ICC profile.
+    pub max_icc_profile_size: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_width: DEFAULT_MAX_DIMENSION,
+            max_height: DEFAULT_MAX_DIMENSION,
+            max_decoded_bytes: usize::MAX,
+            max_chunk_count: DEFAULT_MAX_CHUNK_COUNT,
+            max_chunk_size: DEFAULT_MAX_CHUNK_SIZE,
+            max_text_size: DEFAULT_MAX_CHUNK_SIZE,
+            max_icc_profile_size: DEFAULT_MAX_CHUNK_SIZE,
+        }
+    }
+}
+
+impl Limits {
+    /// Checks ranges of limits, which is done by decoders as well.
+    pub fn validate(&self) -> Result<()> {
+        if !(1..=MAX_DIMENSION).contains(&self.max_width)
+            || !(1..=MAX_DIMENSION).contains(&self.max_height)
+        {
+            return Err(Error::InvalidInput(format!(
+                "Maximum dimensions must be from 1 to {MAX_DIMENSION}, got {}x{}",
+                self.max_width, self.max_height
+            )));
+        }
+
+        // libpng treats 0 as no limit
+        if self.max_chunk_size == 0 {
+            return Err(Error::InvalidInput(String::from(
+                "Maximum chunk size must be at least 1",
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Sets chunk limits of **libpng**, must be run via `png_rs_try` after [validate](Limits::validate).
+    /// Dimensions are checked by [check_header](Limits::check_header), to report them as exceeded limits.
+    pub(crate) unsafe fn set(&self, png_ptr: png_structp) {
+        png_set_user_limits(png_ptr, MAX_DIMENSION, MAX_DIMENSION);
+        // libpng drops the chunk that brings the counter down to 1, 0 is no limit
+        png_set_chunk_cache_max(png_ptr, self.max_chunk_count.saturating_add(2));
+        png_set_chunk_malloc_max(png_ptr, self.max_chunk_size);
+    }
+
+    /// Checks dimensions and chunks read before image data, before rows are allocated.
+    pub(crate) unsafe fn check_header(
+        &self,
+        png_ptr: png_structp,
+        info_ptr: png_infop,
+    ) -> Result<()> {
+        self.check_dimensions(
+            png_get_image_width(png_ptr, info_ptr),
+            png_get_image_height(png_ptr, info_ptr),
+        )?;
+
+        self.check_metadata(png_ptr, info_ptr)
+    }
+
+    pub(crate) fn check_dimensions(&self, width: u32, height: u32) -> Result<()> {
+        if width > self.max_width || height > self.max_height {
+            return Err(Error::LimitExceeded(format!(
+                "Image of {width}x{height} exceeds {}x{}",
+                self.max_width, self.max_height
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Checks the size of all decoded rows, before they are allocated.
+    pub(crate) fn check_decoded_bytes(&self, row_bytes: usize, height: u32) -> Result<()> {
+        let decoded_bytes = row_bytes.saturating_mul(height as usize);
+        if decoded_bytes > self.max_decoded_bytes {
+            return Err(Error::LimitExceeded(format!(
+                "Decoded image of {decoded_bytes} bytes exceeds {}",
+                self.max_decoded_bytes
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Checks text and iCCP chunks read into `info_ptr`, their allocations are bounded by the chunk size.
+    pub(crate) unsafe fn check_metadata(
+        &self,
+        png_ptr: png_structp,
+        info_ptr: png_infop,
+    ) -> Result<()> {
+        let mut texts: png_textp = null_mut();
+        let mut count: c_int = 0;
+        png_get_text(png_ptr, info_ptr, &mut texts, &mut count);
+
+        if !texts.is_null() && count > 0 {
+            for text in std::slice::from_raw_parts(texts, count as usize) {
+                let size = text.text_length.max(text.itxt_length);
+                if size > self.max_text_size {
+                    return Err(Error::LimitExceeded(format!(
+                        "Text of {size} bytes exceeds {}",
+                        self.max_text_size
+                    )));
+                }
+            }
+        }
+
+        let mut name: png_charp = null_mut();
+        let mut compression_type: c_int = 0;
+        let mut profile: png_bytep = null_mut();
+        let mut length: png_uint_32 = 0;
+        let has_profile = png_get_iCCP(
+            png_ptr,
+            info_ptr,
+            &mut name,
+            &mut compression_type,
+            &mut profile,
+            &mut length,
+        ) & PNG_INFO_iCCP
+            != 0;
+
+        if has_profile && length as usize > self.max_icc_profile_size {
+            return Err(Error::LimitExceeded(format!(
+                "ICC profile of {length} bytes exceeds {}",
+                self.max_icc_profile_size
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Checks sizes and the number of ancillary chunks in PNG data, for the simplified API.
+    pub(crate) fn check_chunks(&self, png: &[u8]) -> Result<()> {
+        let mut ancillary_count = 0u32;
+
+        for (name, length, _) in raw_chunks(png) {
+            self.check_chunk_size(name, length)?;
+
+            if ChunkName::new(name).is_ok_and(|name| !name.is_critical()) {
+                ancillary_count += 1;
+                if ancillary_count > self.max_chunk_count {
+                    return Err(Error::LimitExceeded(format!(
+                        "More than {} ancillary chunks",
+                        self.max_chunk_count
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_chunk_size(&self, name: [u8; 4], length: usize) -> Result<()> {
+        if &name != b"IDAT" && length > self.max_chunk_size {
+            return Err(Error::LimitExceeded(format!(
+                "{} chunk of {length} bytes exceeds {}",
+                String::from_utf8_lossy(&name),
+                self.max_chunk_size
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Follows chunks of PNG data fed in pieces, checking their sizes.
+/// The push reader of **libpng** 1.6 skips discarded chunks without checking them.
+pub(crate) struct ChunkScanner {
+    limits: Limits,
+    // Bytes until the next chunk header, the signature at first
+    skip: usize,
+    header: Vec<u8>,
+}
+
+impl ChunkScanner {
+    pub(crate) fn new(limits: Limits) -> Self {
+        Self {
+            limits,
+            skip: 8,
+            header: Vec::with_capacity(8),
+        }
+    }
+
+    /// Scans data consumed by **libpng**, after skipped chunks are consumed.
+    pub(crate) fn scan(&mut self, mut data: &[u8]) -> Result<()> {
+        while !data.is_empty() {
+            if self.skip > 0 {
+                let skipped = self.skip.min(data.len());
+                self.skip -= skipped;
+                data = &data[skipped..];
+                continue;
+            }
+
+            let taken = (8 - self.header.len()).min(data.len());
+            self.header.extend_from_slice(&data[..taken]);
+            data = &data[taken..];
+
+            if let Ok(header) = <[u8; 8]>::try_from(self.header.as_slice()) {
+                let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+                let name = [header[4], header[5], header[6], header[7]];
+                self.limits.check_chunk_size(name, length as usize)?;
+
+                // Data and CRC
+                self.skip = length as usize + 4;
+                self.header.clear();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether a **libpng** warning or error message reports an exceeded chunk limit.
+pub(crate) fn is_limit_message(message: &str) -> bool {
+    let message = message.to_ascii_lowercase();
+
+    LIMIT_MESSAGES
+        .iter()
+        .any(|limit_message| message.contains(limit_message))
+}
@@ -0,0 +1,50 @@
+//! Forwarding of **libpng** warnings and errors to `log` and `tracing`, enabled by the features with the same names.
+//! Messages are logged with the `libpng` target, chunk names and row numbers are structured fields.
+use crate::Warning;
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+const TARGET: &str = "libpng";
+
+/// Logs a warning of **libpng** at the warn level.
+#[cfg_attr(
+    not(any(feature = "log", feature = "tracing")),
+    allow(unused_variables)
+)]
+pub(crate) fn log_warning(warning: &Warning) {
+    #[cfg(feature = "log")]
+    {
+        let chunk = warning.chunk.map(|chunk| chunk.to_string());
+        log::warn!(
+            target: TARGET,
+            chunk = chunk.as_deref(),
+            row = warning.row;
+            "{}",
+            warning.message
+        );
+    }
+
+    #[cfg(feature = "tracing")]
+    {
+        let chunk = warning.chunk.map(|chunk| chunk.to_string());
+        tracing::warn!(
+            target: TARGET,
+            chunk = chunk.as_deref(),
+            row = warning.row,
+            "{}",
+            warning.message
+        );
+    }
+}
+
+/// Logs an error of **libpng** at the debug level, since it is returned to the caller as well.
+#[cfg_attr(
+    not(any(feature = "log", feature = "tracing")),
+    allow(unused_variables)
+)]
+pub(crate) fn log_error(message: &str) {
+    #[cfg(feature = "log")]
+    log::debug!(target: TARGET, "{message}");
+
+    #[cfg(feature = "tracing")]
+    tracing::debug!(target: TARGET, "{message}");
+}
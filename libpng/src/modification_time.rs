@@ -0,0 +1,295 @@
+use std::{
+    ptr::null_mut,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use libpng_vendored_sys::{
+    png_get_tIME, png_infop, png_set_tIME, png_structp, png_time, png_timep, PNG_INFO_tIME,
+};
+
+use crate::{Error, Result};
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+const MONTH_NAMES: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Time of the last image modification of tIME chunk, in UTC.
+///
+/// Converted from and to [SystemTime] without `time_t` of libc, as well as `chrono` and `time` types
+/// with the features of the same names:
+/// ```
+/// use std::time::{Duration, SystemTime, UNIX_EPOCH};
+///
+/// use libpng::ModificationTime;
+///
+/// let time = ModificationTime::try_from(UNIX_EPOCH + Duration::from_secs(1_700_000_000))?;
+/// assert_eq!(time, ModificationTime::new(2023, 11, 14, 22, 13, 20));
+/// assert_eq!(time.to_rfc1123()?, "14 Nov 2023 22:13:20 +0000");
+/// # Ok::<(), libpng::Error>(())
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ModificationTime {
+    /// Full year, like 2024.
+    pub year: u16,
+    /// From 1 to 12.
+    pub month: u8,
+    /// From 1 to the number of days in the month.
+    pub day: u8,
+    /// From 0 to 23.
+    pub hour: u8,
+    /// From 0 to 59.
+    pub minute: u8,
+    /// From 0 to 60, the latter for leap seconds.
+    pub second: u8,
+}
+
+impl ModificationTime {
+    pub const fn new(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> Self {
+        Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        }
+    }
+
+    /// Current system time.
+    pub fn now() -> Result<Self> {
+        Self::try_from(SystemTime::now())
+    }
+
+    /// Time of seconds since 1970-01-01 00:00:00 UTC, fails for years out of the `u16` range.
+    pub fn from_unix_timestamp(timestamp: i64) -> Result<Self> {
+        let (days, seconds) = (
+            timestamp.div_euclid(SECONDS_PER_DAY),
+            timestamp.rem_euclid(SECONDS_PER_DAY),
+        );
+        let (year, month, day) = civil_from_days(days);
+
+        let year = u16::try_from(year).map_err(|_| {
+            Error::InvalidInput(format!(
+                "Year {year} of timestamp {timestamp} is out of range"
+            ))
+        })?;
+
+        Ok(Self::new(
+            year,
+            month,
+            day,
+            (seconds / 3600) as u8,
+            (seconds / 60 % 60) as u8,
+            (seconds % 60) as u8,
+        ))
+    }
+
+    /// Seconds since 1970-01-01 00:00:00 UTC, a leap second is counted as the first one of the next minute.
+    pub fn unix_timestamp(&self) -> Result<i64> {
+        self.validate()?;
+
+        let days = days_from_civil(i64::from(self.year), self.month, self.day);
+        let seconds =
+            i64::from(self.hour) * 3600 + i64::from(self.minute) * 60 + i64::from(self.second);
+
+        Ok(days * SECONDS_PER_DAY + seconds)
+    }
+
+    /// Checks ranges of fields, which is done by encoders as well.
+    pub fn validate(&self) -> Result<()> {
+        let days = days_in_month(self.year, self.month);
+
+        if !(1..=12).contains(&self.month)
+            || !(1..=days).contains(&self.day)
+            || self.hour > 23
+            || self.minute > 59
+            || self.second > 60
+        {
+            return Err(Error::InvalidInput(format!(
+                "Invalid modification time {:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+                self.year, self.month, self.day, self.hour, self.minute, self.second
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Formats like `png_convert_to_rfc1123_buffer`, e.g. `1 Jan 2024 09:05:00 +0000`.
+    pub fn to_rfc1123(&self) -> Result<String> {
+        self.validate()?;
+
+        Ok(format!(
+            "{} {} {} {:02}:{:02}:{:02} +0000",
+            self.day,
+            MONTH_NAMES[usize::from(self.month) - 1],
+            self.year,
+            self.hour,
+            self.minute,
+            self.second
+        ))
+    }
+
+    /// Reads tIME chunk from `info_ptr`.
+    pub(crate) unsafe fn from_png(png_ptr: png_structp, info_ptr: png_infop) -> Option<Self> {
+        let mut time: png_timep = null_mut();
+
+        if png_get_tIME(png_ptr, info_ptr, &mut time) & PNG_INFO_tIME == 0 || time.is_null() {
+            return None;
+        }
+
+        let png_time {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        } = *time;
+
+        Some(Self::new(year, month, day, hour, minute, second))
+    }
+
+    /// Sets tIME chunk in `info_ptr`, must be run via `png_rs_try` after [validate](ModificationTime::validate).
+    pub(crate) unsafe fn set(&self, png_ptr: png_structp, info_ptr: png_infop) {
+        let time = png_time {
+            year: self.year,
+            month: self.month,
+            day: self.day,
+            hour: self.hour,
+            minute: self.minute,
+            second: self.second,
+        };
+
+        png_set_tIME(png_ptr, info_ptr, &time);
+    }
+}
+
+impl TryFrom<SystemTime> for ModificationTime {
+    type Error = Error;
+
+    /// Truncates to whole seconds.
+    fn try_from(time: SystemTime) -> Result<Self> {
+        let timestamp = match time.duration_since(UNIX_EPOCH) {
+            Ok(duration) => i64::try_from(duration.as_secs()).ok(),
+            Err(error) => {
+                let duration = error.duration();
+                i64::try_from(duration.as_secs())
+                    .ok()
+                    .map(|seconds| -seconds - i64::from(duration.subsec_nanos() > 0))
+            }
+        };
+
+        timestamp
+            .ok_or_else(|| Error::InvalidInput(format!("System time {time:?} is out of range")))
+            .and_then(Self::from_unix_timestamp)
+    }
+}
+
+impl TryFrom<ModificationTime> for SystemTime {
+    type Error = Error;
+
+    fn try_from(time: ModificationTime) -> Result<Self> {
+        let timestamp = time.unix_timestamp()?;
+        let duration = Duration::from_secs(timestamp.unsigned_abs());
+
+        match timestamp >= 0 {
+            true => UNIX_EPOCH.checked_add(duration),
+            false => UNIX_EPOCH.checked_sub(duration),
+        }
+        .ok_or_else(|| Error::InvalidInput(format!("{time:?} is out of system time range")))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<chrono::DateTime<chrono::Utc>> for ModificationTime {
+    type Error = Error;
+
+    /// Truncates to whole seconds, a leap second becomes the last regular one.
+    fn try_from(time: chrono::DateTime<chrono::Utc>) -> Result<Self> {
+        Self::from_unix_timestamp(time.timestamp())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<ModificationTime> for chrono::DateTime<chrono::Utc> {
+    type Error = Error;
+
+    fn try_from(time: ModificationTime) -> Result<Self> {
+        let timestamp = time.unix_timestamp()?;
+
+        chrono::DateTime::from_timestamp(timestamp, 0)
+            .ok_or_else(|| Error::InvalidInput(format!("{time:?} is out of chrono range")))
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<time::OffsetDateTime> for ModificationTime {
+    type Error = Error;
+
+    /// Converts to UTC and truncates to whole seconds.
+    fn try_from(time: time::OffsetDateTime) -> Result<Self> {
+        Self::from_unix_timestamp(time.unix_timestamp())
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<ModificationTime> for time::OffsetDateTime {
+    type Error = Error;
+
+    fn try_from(time: ModificationTime) -> Result<Self> {
+        let timestamp = time.unix_timestamp()?;
+
+        time::OffsetDateTime::from_unix_timestamp(timestamp)
+            .map_err(|error| Error::InvalidInput(format!("{time:?} is out of range: {error}")))
+    }
+}
+
+fn is_leap_year(year: u16) -> bool {
+    year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400))
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date, by Howard Hinnant's algorithm.
+fn days_from_civil(year: i64, month: u8, day: u8) -> i64 {
+    let (month, day) = (i64::from(month), i64::from(day));
+    let year = if month <= 2 { year - 1 } else { year };
+
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Inverse of [days_from_civil].
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let days = days + 719_468;
+
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u8;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u8;
+    let year = era * 400 + year_of_era + i64::from(month <= 2);
+
+    (year, month, day)
+}
@@ -0,0 +1,452 @@
+use std::{
+    ffi::{CStr, CString},
+    ptr::null_mut,
+    slice,
+};
+
+use libc::c_int;
+use libpng_vendored_sys::{
+    png_charp, png_charpp, png_get_oFFs, png_get_pCAL, png_get_pHYs, png_get_sCAL, png_infop,
+    png_int_32, png_set_oFFs, png_set_pCAL, png_set_pHYs, png_set_sCAL, png_structp, png_uint_32,
+    PNG_INFO_oFFs, PNG_INFO_pCAL, PNG_INFO_pHYs, PNG_INFO_sCAL, PNG_EQUATION_ARBITRARY,
+    PNG_EQUATION_BASE_E, PNG_EQUATION_HYPERBOLIC, PNG_EQUATION_LINEAR, PNG_OFFSET_MICROMETER,
+    PNG_OFFSET_PIXEL, PNG_RESOLUTION_METER, PNG_RESOLUTION_UNKNOWN, PNG_SCALE_METER,
+    PNG_SCALE_RADIAN,
+};
+
+use crate::{
+    text::{latin1_to_string, validate_keyword},
+    Error, Result,
+};
+
+/// Physical dimension chunks of a PNG image: pHYs, oFFs, sCAL and pCAL.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PhysicalInfo {
+    /// Pixel density or aspect ratio of pHYs chunk.
+    pub pixels_per_unit: Option<PixelsPerUnit>,
+    /// Image position on a page of oFFs chunk.
+    pub offset: Option<Offset>,
+    /// Physical size of a pixel of sCAL chunk.
+    pub scale: Option<PhysicalScale>,
+    /// Mapping of samples to physical values of pCAL chunk.
+    pub calibration: Option<Calibration>,
+}
+
+impl PhysicalInfo {
+    /// Whether no chunk is present.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Checks values accepted by **libpng**, which is done by encoders as well.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(scale) = &self.scale {
+            let valid = |value: f64| value.is_finite() && value > 0.0;
+            if !valid(scale.width) || !valid(scale.height) {
+                return Err(Error::InvalidInput(format!(
+                    "Pixel width and height must be positive, got {} and {}",
+                    scale.width, scale.height
+                )));
+            }
+        }
+
+        if let Some(calibration) = &self.calibration {
+            calibration.validate()?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads physical chunks from `info_ptr`.
+    pub(crate) unsafe fn from_png(png_ptr: png_structp, info_ptr: png_infop) -> Self {
+        let mut info = Self::default();
+
+        let (mut x, mut y, mut unit): (png_uint_32, png_uint_32, c_int) = (0, 0, 0);
+        if png_get_pHYs(png_ptr, info_ptr, &mut x, &mut y, &mut unit) & PNG_INFO_pHYs != 0 {
+            info.pixels_per_unit = Some(PixelsPerUnit {
+                x,
+                y,
+                unit: if unit as u32 == PNG_RESOLUTION_METER {
+                    ResolutionUnit::Meter
+                } else {
+                    ResolutionUnit::Unknown
+                },
+            });
+        }
+
+        let (mut x, mut y, mut unit): (png_int_32, png_int_32, c_int) = (0, 0, 0);
+        if png_get_oFFs(png_ptr, info_ptr, &mut x, &mut y, &mut unit) & PNG_INFO_oFFs != 0 {
+            info.offset = Some(Offset {
+                x,
+                y,
+                unit: if unit as u32 == PNG_OFFSET_MICROMETER {
+                    OffsetUnit::Micrometer
+                } else {
+                    OffsetUnit::Pixel
+                },
+            });
+        }
+
+        let (mut unit, mut width, mut height) = (0, 0.0, 0.0);
+        if png_get_sCAL(png_ptr, info_ptr, &mut unit, &mut width, &mut height) & PNG_INFO_sCAL != 0
+        {
+            info.scale = Some(PhysicalScale {
+                unit: if unit as u32 == PNG_SCALE_RADIAN {
+                    ScaleUnit::Radian
+                } else {
+                    ScaleUnit::Meter
+                },
+                width,
+                height,
+            });
+        }
+
+        info.calibration = Calibration::from_png(png_ptr, info_ptr);
+
+        info
+    }
+
+    /// Sets physical chunks in `info_ptr`, must be run via `png_rs_try` after [validate](PhysicalInfo::validate).
+    pub(crate) unsafe fn set(
+        &self,
+        png_ptr: png_structp,
+        info_ptr: png_infop,
+        calibration: Option<&PngCalibration>,
+    ) {
+        if let Some(PixelsPerUnit { x, y, unit }) = self.pixels_per_unit {
+            let unit = match unit {
+                ResolutionUnit::Unknown => PNG_RESOLUTION_UNKNOWN,
+                ResolutionUnit::Meter => PNG_RESOLUTION_METER,
+            };
+
+            png_set_pHYs(png_ptr, info_ptr, x, y, unit as c_int);
+        }
+
+        if let Some(Offset { x, y, unit }) = self.offset {
+            let unit = match unit {
+                OffsetUnit::Pixel => PNG_OFFSET_PIXEL,
+                OffsetUnit::Micrometer => PNG_OFFSET_MICROMETER,
+            };
+
+            png_set_oFFs(png_ptr, info_ptr, x, y, unit as c_int);
+        }
+
+        if let Some(PhysicalScale {
+            unit,
+            width,
+            height,
+        }) = self.scale
+        {
+            let unit = match unit {
+                ScaleUnit::Meter => PNG_SCALE_METER,
+                ScaleUnit::Radian => PNG_SCALE_RADIAN,
+            };
+
+            png_set_sCAL(png_ptr, info_ptr, unit as c_int, width, height);
+        }
+
+        if let (Some(calibration), Some(strings)) = (&self.calibration, calibration) {
+            png_set_pCAL(
+                png_ptr,
+                info_ptr,
+                strings.purpose.as_ptr(),
+                calibration.x0,
+                calibration.x1,
+                calibration.equation.to_png(),
+                strings.pointers.len() as c_int,
+                strings.units.as_ptr(),
+                strings.pointers.as_ptr() as png_charpp,
+            );
+        }
+    }
+}
+
+/// Unit of [PixelsPerUnit], `PNG_RESOLUTION_*`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ResolutionUnit {
+    /// Only the aspect ratio is known.
+    Unknown,
+    Meter,
+}
+
+/// Pixels per unit of pHYs chunk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PixelsPerUnit {
+    pub x: u32,
+    pub y: u32,
+    pub unit: ResolutionUnit,
+}
+
+impl PixelsPerUnit {
+    /// Pixels per meter matching `dpi` dots per inch in both directions.
+    pub fn from_dpi(dpi: u32) -> Self {
+        let ppm = (u64::from(dpi) * 5000 + 63) / 127;
+        let ppm = u32::try_from(ppm).unwrap_or(u32::MAX);
+
+        Self {
+            x: ppm,
+            y: ppm,
+            unit: ResolutionUnit::Meter,
+        }
+    }
+
+    /// Horizontal and vertical dots per inch, rounded like `png_get_pHYs_dpi`.
+    /// `None` if the unit is unknown.
+    pub fn dpi(&self) -> Option<(u32, u32)> {
+        let dpi = |ppm: u32| ((u64::from(ppm) * 127 + 2500) / 5000) as u32;
+
+        match self.unit {
+            ResolutionUnit::Meter => Some((dpi(self.x), dpi(self.y))),
+            ResolutionUnit::Unknown => None,
+        }
+    }
+}
+
+/// Unit of [Offset], `PNG_OFFSET_*`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OffsetUnit {
+    Pixel,
+    Micrometer,
+}
+
+/// Position of the image on a page of oFFs chunk, from the left and top edges.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Offset {
+    pub x: i32,
+    pub y: i32,
+    pub unit: OffsetUnit,
+}
+
+/// Unit of [PhysicalScale], `PNG_SCALE_*`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ScaleUnit {
+    Meter,
+    Radian,
+}
+
+/// Physical width and height of a pixel of sCAL chunk, e.g. for maps or sky images.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PhysicalScale {
+    pub unit: ScaleUnit,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Equation of [Calibration], `PNG_EQUATION_*`.
+///
+/// `X` is the original sample, `p0`, `p1`... are the parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Equation {
+    /// `p0 + p1 * X / (x1 - x0)`
+    Linear,
+    /// `p0 + p1 * exp(p2 * X / (x1 - x0))`
+    BaseE,
+    /// `p0 + p1 * pow(p2, X / (x1 - x0))`
+    Arbitrary,
+    /// `p0 + p1 * sinh(p2 * (X - p3) / (x1 - x0))`
+    Hyperbolic,
+}
+
+impl Equation {
+    /// Number of parameters required by the equation.
+    pub const fn parameters(self) -> usize {
+        match self {
+            Self::Linear => 2,
+            Self::BaseE | Self::Arbitrary => 3,
+            Self::Hyperbolic => 4,
+        }
+    }
+
+    fn from_png(equation: c_int) -> Option<Self> {
+        [Self::Linear, Self::BaseE, Self::Arbitrary, Self::Hyperbolic]
+            .into_iter()
+            .find(|known| known.to_png() == equation)
+    }
+
+    const fn to_png(self) -> c_int {
+        let equation = match self {
+            Self::Linear => PNG_EQUATION_LINEAR,
+            Self::BaseE => PNG_EQUATION_BASE_E,
+            Self::Arbitrary => PNG_EQUATION_ARBITRARY,
+            Self::Hyperbolic => PNG_EQUATION_HYPERBOLIC,
+        };
+
+        equation as c_int
+    }
+}
+
+/// Calibration of pCAL chunk, mapping stored samples to physical values.
+///
+/// Stored samples from 0 to the maximum of the bit depth are mapped linearly to original samples
+/// from `x0` to `x1`, which are mapped to physical values by the equation.
+/// ```
+/// use libpng::{Calibration, Equation};
+///
+/// // Temperatures from -40 to 60 degrees in 8-bit samples
+/// let calibration = Calibration {
+///     purpose: String::from("Temperature"),
+///     x0: 0,
+///     x1: 1000,
+///     equation: Equation::Linear,
+///     units: String::from("Celsius"),
+///     parameters: vec![-40.0, 100.0],
+/// };
+///
+/// assert_eq!(calibration.evaluate(0, 8), -40.0);
+/// assert_eq!(calibration.evaluate(255, 8), 60.0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Calibration {
+    /// Latin-1 name with the rules of text keywords.
+    pub purpose: String,
+    pub x0: i32,
+    pub x1: i32,
+    pub equation: Equation,
+    /// Latin-1 name of physical values, may be empty.
+    pub units: String,
+    /// As many parameters as [Equation::parameters].
+    pub parameters: Vec<f64>,
+}
+
+impl Calibration {
+    /// Physical value of a stored sample of `bit_depth` bits, as defined by the PNG specification.
+    pub fn evaluate(&self, sample: u32, bit_depth: u8) -> f64 {
+        let max = (1i64 << bit_depth.clamp(1, 16)) - 1;
+        let (x0, x1) = (i64::from(self.x0), i64::from(self.x1));
+
+        // Integer rounding of the specification
+        let original = (i64::from(sample) * (x1 - x0) + max / 2) / max + x0;
+        let x = original as f64 / (x1 - x0) as f64;
+        let p = |index: usize| self.parameters.get(index).copied().unwrap_or(f64::NAN);
+
+        match self.equation {
+            Equation::Linear => p(0) + p(1) * x,
+            Equation::BaseE => p(0) + p(1) * (p(2) * x).exp(),
+            Equation::Arbitrary => p(0) + p(1) * p(2).powf(x),
+            Equation::Hyperbolic => {
+                let x = (original as f64 - p(3)) / (x1 - x0) as f64;
+                p(0) + p(1) * (p(2) * x).sinh()
+            }
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        validate_keyword(&self.purpose)?;
+
+        if self.x0 == self.x1 {
+            return Err(Error::InvalidInput(String::from(
+                "Calibration x0 and x1 must differ",
+            )));
+        }
+
+        if self.units.chars().any(|c| c == '\0' || u32::from(c) > 0xff) {
+            return Err(Error::InvalidInput(format!(
+                "Calibration units must be Latin-1 without NUL characters, got {:?}",
+                self.units
+            )));
+        }
+
+        if self.parameters.len() != self.equation.parameters() {
+            return Err(Error::InvalidInput(format!(
+                "{:?} equation requires {} parameters, got {}",
+                self.equation,
+                self.equation.parameters(),
+                self.parameters.len()
+            )));
+        }
+
+        if self
+            .parameters
+            .iter()
+            .any(|parameter| !parameter.is_finite())
+        {
+            return Err(Error::InvalidInput(String::from(
+                "Calibration parameters must be finite",
+            )));
+        }
+
+        Ok(())
+    }
+
+    unsafe fn from_png(png_ptr: png_structp, info_ptr: png_infop) -> Option<Self> {
+        let mut purpose: png_charp = null_mut();
+        let (mut x0, mut x1, mut equation, mut count) = (0, 0, 0, 0);
+        let mut units: png_charp = null_mut();
+        let mut parameters: png_charpp = null_mut();
+
+        let valid = png_get_pCAL(
+            png_ptr,
+            info_ptr,
+            &mut purpose,
+            &mut x0,
+            &mut x1,
+            &mut equation,
+            &mut count,
+            &mut units,
+            &mut parameters,
+        );
+        if valid & PNG_INFO_pCAL == 0 || purpose.is_null() || units.is_null() {
+            return None;
+        }
+
+        let parameters = if parameters.is_null() {
+            Vec::new()
+        } else {
+            slice::from_raw_parts(parameters, count.max(0) as usize)
+                .iter()
+                .map(|parameter| {
+                    // Checked by libpng as a PNG floating point string
+                    CStr::from_ptr(*parameter)
+                        .to_str()
+                        .ok()
+                        .and_then(|parameter| parameter.parse().ok())
+                        .unwrap_or(f64::NAN)
+                })
+                .collect()
+        };
+
+        Some(Self {
+            purpose: latin1_to_string(purpose),
+            x0,
+            x1,
+            equation: Equation::from_png(equation)?,
+            units: latin1_to_string(units),
+            parameters,
+        })
+    }
+
+    /// Strings for [PhysicalInfo::set], after [validate](Calibration::validate).
+    pub(crate) fn to_png(&self) -> PngCalibration {
+        let c_string = |value: &str| {
+            CString::new(
+                value
+                    .chars()
+                    .map(|c| u32::from(c) as u8)
+                    .collect::<Vec<_>>(),
+            )
+            .unwrap_or_default()
+        };
+
+        let parameters: Vec<CString> = self
+            .parameters
+            .iter()
+            .map(|parameter| c_string(&parameter.to_string()))
+            .collect();
+
+        PngCalibration {
+            purpose: c_string(&self.purpose),
+            units: c_string(&self.units),
+            pointers: parameters.iter().map(|p| p.as_ptr() as png_charp).collect(),
+            _parameters: parameters,
+        }
+    }
+}
+
+/// C strings of [Calibration] for `png_set_pCAL`, which copies them.
+pub(crate) struct PngCalibration {
+    purpose: CString,
+    units: CString,
+    // Points to parameters
+    pointers: Vec<png_charp>,
+    _parameters: Vec<CString>,
+}
@@ -0,0 +1,611 @@
+use std::{collections::HashSet, ffi::CString, ptr::null_mut, slice};
+
+use libc::c_int;
+use libpng_vendored_sys::{
+    png_byte, png_bytep, png_charp, png_color, png_color_16, png_color_16p, png_color_8,
+    png_color_8p, png_colorp, png_get_PLTE, png_get_bKGD, png_get_color_type, png_get_hIST,
+    png_get_sBIT, png_get_sPLT, png_get_tRNS, png_infop, png_sPLT_entry, png_sPLT_t, png_sPLT_tp,
+    png_set_PLTE, png_set_bKGD, png_set_hIST, png_set_sBIT, png_set_sPLT, png_set_tRNS,
+    png_structp, png_uint_16p, PNG_INFO_bKGD, PNG_INFO_hIST, PNG_INFO_sBIT, PNG_INFO_tRNS,
+    PNG_INFO_PLTE,
+};
+
+use crate::{
+    text::{latin1_to_string, validate_keyword},
+    Color, ColorType, Error, Result,
+};
+
+/// Palette and pixel metadata chunks of a PNG image: PLTE, tRNS, bKGD, sBIT, hIST and sPLT.
+///
+/// Values are in the encoded sample depth. With [Transforms](crate::Transforms) read chunks are interpreted
+/// by the decoded color type and **libpng** drops tRNS when it is expanded to alpha.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PixelInfo {
+    /// Colors of PLTE chunk with alpha of tRNS chunk. Required for [ColorType::Palette],
+    /// suggested quantization for [ColorType::Rgb] and [ColorType::Rgba].
+    pub palette: Option<Palette>,
+    /// Fully transparent color of tRNS chunk for [ColorType::Gray] and [ColorType::Rgb].
+    pub transparent_color: Option<TransparentColor>,
+    /// Default background color of bKGD chunk.
+    pub background: Option<Background>,
+    /// Significant bits of samples of sBIT chunk.
+    pub significant_bits: Option<SignificantBits>,
+    /// Usage frequencies of palette entries of hIST chunk.
+    pub histogram: Option<Histogram>,
+    /// Suggested palettes of sPLT chunks.
+    pub suggested_palettes: Vec<SuggestedPalette>,
+}
+
+/// Palette of PLTE chunk with alpha values of tRNS chunk.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Palette {
+    /// From 1 to 256 entries, at most `2^bit_depth` for [ColorType::Palette].
+    pub colors: Vec<Color>,
+    /// Alpha of the first entries, missing ones are opaque. Only for [ColorType::Palette].
+    pub alpha: Vec<u8>,
+}
+
+impl Palette {
+    /// Opaque palette.
+    pub fn new(colors: Vec<Color>) -> Self {
+        Self {
+            colors,
+            alpha: Vec::new(),
+        }
+    }
+
+    pub fn with_alpha(mut self, alpha: Vec<u8>) -> Self {
+        self.alpha = alpha;
+        self
+    }
+}
+
+/// Color of tRNS chunk, pixels of which are fully transparent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TransparentColor {
+    /// For [ColorType::Gray].
+    Gray(u16),
+    /// For [ColorType::Rgb].
+    Rgb { red: u16, green: u16, blue: u16 },
+}
+
+/// Background color of bKGD chunk, the variant depends on color type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Background {
+    /// For [ColorType::Gray] and [ColorType::GrayAlpha].
+    Gray(u16),
+    /// For [ColorType::Rgb] and [ColorType::Rgba].
+    Rgb { red: u16, green: u16, blue: u16 },
+    /// Palette entry for [ColorType::Palette].
+    Index(u8),
+}
+
+/// Numbers of significant bits of sBIT chunk, from 1 to the sample depth, which is 8 for [ColorType::Palette].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SignificantBits {
+    /// For [ColorType::Gray] and [ColorType::GrayAlpha], with alpha for the latter.
+    Gray { gray: u8, alpha: Option<u8> },
+    /// For [ColorType::Rgb], [ColorType::Rgba] and [ColorType::Palette], with alpha for [ColorType::Rgba].
+    Rgb {
+        red: u8,
+        green: u8,
+        blue: u8,
+        alpha: Option<u8>,
+    },
+}
+
+/// Approximate usage frequencies of palette entries of hIST chunk.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Histogram {
+    /// One per palette entry.
+    pub frequencies: Vec<u16>,
+}
+
+/// Suggested palette of sPLT chunk, for displays with a limited number of colors.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SuggestedPalette {
+    /// Latin-1 name, unique among suggested palettes.
+    pub name: String,
+    /// Sample depth of entries, 8 or 16.
+    pub depth: u8,
+    /// At least one entry.
+    pub entries: Vec<SuggestedPaletteEntry>,
+}
+
+/// Entry of [SuggestedPalette], samples are below `2^depth`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SuggestedPaletteEntry {
+    pub red: u16,
+    pub green: u16,
+    pub blue: u16,
+    pub alpha: u16,
+    /// Relative usage frequency.
+    pub frequency: u16,
+}
+
+impl PixelInfo {
+    /// Whether no chunk is present.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Checks chunks against the color type and bit depth of the image, which is done by encoders as well.
+    pub fn validate(&self, color_type: ColorType, bit_depth: u8) -> Result<()> {
+        let max_sample = (1u32 << bit_depth.min(16)) - 1;
+        let check_sample = |name: &str, value: u16| {
+            if u32::from(value) > max_sample {
+                return Err(Error::InvalidInput(format!(
+                    "{name} value {value} exceeds {max_sample} for bit depth {bit_depth}"
+                )));
+            }
+            Ok(())
+        };
+
+        let palette_len = self.palette.as_ref().map_or(0, |p| p.colors.len());
+        match (&self.palette, color_type) {
+            (None, ColorType::Palette) => {
+                return Err(Error::InvalidInput(String::from(
+                    "Palette is required for palette color type",
+                )))
+            }
+            (Some(_), ColorType::Gray | ColorType::GrayAlpha) => {
+                return Err(Error::InvalidInput(format!(
+                    "Palette is not allowed for color type {color_type:?}"
+                )))
+            }
+            (Some(palette), _) => {
+                let max_len = match color_type {
+                    ColorType::Palette => 1 << bit_depth.min(8),
+                    _ => 256,
+                };
+                if palette.colors.is_empty() || palette.colors.len() > max_len {
+                    return Err(Error::InvalidInput(format!(
+                        "Palette must have from 1 to {max_len} entries, got {}",
+                        palette.colors.len()
+                    )));
+                }
+
+                if !palette.alpha.is_empty() && color_type != ColorType::Palette {
+                    return Err(Error::InvalidInput(format!(
+                        "Palette alpha is not allowed for color type {color_type:?}"
+                    )));
+                }
+                if palette.alpha.len() > palette.colors.len() {
+                    return Err(Error::InvalidInput(format!(
+                        "Palette has {} alpha values for {} entries",
+                        palette.alpha.len(),
+                        palette.colors.len()
+                    )));
+                }
+            }
+            (None, _) => {}
+        }
+
+        match (self.transparent_color, color_type) {
+            (None, _) => {}
+            (Some(TransparentColor::Gray(gray)), ColorType::Gray) => {
+                check_sample("Transparent", gray)?
+            }
+            (Some(TransparentColor::Rgb { red, green, blue }), ColorType::Rgb) => {
+                for value in [red, green, blue] {
+                    check_sample("Transparent", value)?;
+                }
+            }
+            (Some(color), _) => {
+                return Err(Error::InvalidInput(format!(
+                    "Transparent color {color:?} is not allowed for color type {color_type:?}"
+                )))
+            }
+        }
+
+        match (self.background, color_type) {
+            (None, _) => {}
+            (Some(Background::Gray(gray)), ColorType::Gray | ColorType::GrayAlpha) => {
+                check_sample("Background", gray)?;
+            }
+            (Some(Background::Rgb { red, green, blue }), ColorType::Rgb | ColorType::Rgba) => {
+                for value in [red, green, blue] {
+                    check_sample("Background", value)?;
+                }
+            }
+            (Some(Background::Index(index)), ColorType::Palette) => {
+                if usize::from(index) >= palette_len {
+                    return Err(Error::InvalidInput(format!(
+                        "Background index {index} exceeds {palette_len} palette entries"
+                    )));
+                }
+            }
+            (Some(background), _) => {
+                return Err(Error::InvalidInput(format!(
+                    "Background {background:?} is not allowed for color type {color_type:?}"
+                )))
+            }
+        }
+
+        if let Some(significant_bits) = self.significant_bits {
+            let sample_depth = match color_type {
+                ColorType::Palette => 8,
+                _ => bit_depth,
+            };
+            let (samples, alpha) = match (significant_bits, color_type) {
+                (SignificantBits::Gray { gray, alpha }, ColorType::Gray | ColorType::GrayAlpha) => {
+                    (vec![gray], alpha)
+                }
+                (
+                    SignificantBits::Rgb {
+                        red,
+                        green,
+                        blue,
+                        alpha,
+                    },
+                    ColorType::Rgb | ColorType::Rgba | ColorType::Palette,
+                ) => (vec![red, green, blue], alpha),
+                _ => {
+                    return Err(Error::InvalidInput(format!(
+                        "Significant bits {significant_bits:?} are not allowed for color type {color_type:?}"
+                    )))
+                }
+            };
+
+            if alpha.is_some() != color_type.has_alpha() {
+                return Err(Error::InvalidInput(format!(
+                    "Significant bits of alpha must be set only for color types with alpha, got {significant_bits:?}"
+                )));
+            }
+
+            if let Some(bits) = samples
+                .into_iter()
+                .chain(alpha)
+                .find(|bits| !(1..=sample_depth).contains(bits))
+            {
+                return Err(Error::InvalidInput(format!(
+                    "Significant bits must be from 1 to {sample_depth}, got {bits}"
+                )));
+            }
+        }
+
+        if let Some(histogram) = &self.histogram {
+            if histogram.frequencies.len() != palette_len || palette_len == 0 {
+                return Err(Error::InvalidInput(format!(
+                    "Histogram must have one frequency per palette entry, got {} for {palette_len} entries",
+                    histogram.frequencies.len()
+                )));
+            }
+        }
+
+        let mut names = HashSet::new();
+        for palette in &self.suggested_palettes {
+            palette.validate()?;
+
+            if !names.insert(&palette.name) {
+                return Err(Error::InvalidInput(format!(
+                    "Suggested palette name {:?} is not unique",
+                    palette.name
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads pixel chunks from `info_ptr`, interpreted by its color type.
+    pub(crate) unsafe fn from_png(png_ptr: png_structp, info_ptr: png_infop) -> Self {
+        let mut info = Self::default();
+        let color_type = ColorType::from_png(png_get_color_type(png_ptr, info_ptr));
+
+        let (mut colors, mut num_colors): (png_colorp, c_int) = (null_mut(), 0);
+        if png_get_PLTE(png_ptr, info_ptr, &mut colors, &mut num_colors) & PNG_INFO_PLTE != 0
+            && !colors.is_null()
+        {
+            info.palette = Some(Palette::new(
+                slice_from_png(colors, num_colors)
+                    .iter()
+                    .map(|c| Color::new(c.red, c.green, c.blue))
+                    .collect(),
+            ));
+        }
+
+        let (mut alpha, mut num_alpha, mut color): (png_bytep, c_int, png_color_16p) =
+            (null_mut(), 0, null_mut());
+        if png_get_tRNS(png_ptr, info_ptr, &mut alpha, &mut num_alpha, &mut color) & PNG_INFO_tRNS
+            != 0
+        {
+            match (color_type, &mut info.palette) {
+                (Some(ColorType::Palette), Some(palette)) => {
+                    palette.alpha = slice_from_png(alpha, num_alpha).to_vec();
+                }
+                (Some(ColorType::Gray), _) if !color.is_null() => {
+                    info.transparent_color = Some(TransparentColor::Gray((*color).gray));
+                }
+                (Some(ColorType::Rgb), _) if !color.is_null() => {
+                    let png_color_16 {
+                        red, green, blue, ..
+                    } = *color;
+                    info.transparent_color = Some(TransparentColor::Rgb { red, green, blue });
+                }
+                _ => {}
+            }
+        }
+
+        let mut background: png_color_16p = null_mut();
+        if png_get_bKGD(png_ptr, info_ptr, &mut background) & PNG_INFO_bKGD != 0
+            && !background.is_null()
+        {
+            let png_color_16 {
+                index,
+                red,
+                green,
+                blue,
+                gray,
+            } = *background;
+
+            info.background = match color_type {
+                Some(ColorType::Palette) => Some(Background::Index(index)),
+                Some(ColorType::Gray | ColorType::GrayAlpha) => Some(Background::Gray(gray)),
+                Some(ColorType::Rgb | ColorType::Rgba) => {
+                    Some(Background::Rgb { red, green, blue })
+                }
+                None => None,
+            };
+        }
+
+        let mut bits: png_color_8p = null_mut();
+        if png_get_sBIT(png_ptr, info_ptr, &mut bits) & PNG_INFO_sBIT != 0 && !bits.is_null() {
+            let png_color_8 {
+                red,
+                green,
+                blue,
+                gray,
+                alpha,
+            } = *bits;
+            let alpha =
+                (color_type.is_some_and(ColorType::has_alpha) && alpha != 0).then_some(alpha);
+
+            info.significant_bits = match color_type {
+                Some(ColorType::Gray | ColorType::GrayAlpha) => {
+                    Some(SignificantBits::Gray { gray, alpha })
+                }
+                Some(_) => Some(SignificantBits::Rgb {
+                    red,
+                    green,
+                    blue,
+                    alpha,
+                }),
+                None => None,
+            };
+        }
+
+        let mut frequencies: png_uint_16p = null_mut();
+        if png_get_hIST(png_ptr, info_ptr, &mut frequencies) & PNG_INFO_hIST != 0 {
+            info.histogram = Some(Histogram {
+                frequencies: slice_from_png(frequencies, num_colors).to_vec(),
+            });
+        }
+
+        let mut palettes: png_sPLT_tp = null_mut();
+        let num_palettes = png_get_sPLT(png_ptr, info_ptr, &mut palettes);
+        info.suggested_palettes = slice_from_png(palettes, num_palettes)
+            .iter()
+            .map(|palette| SuggestedPalette {
+                name: latin1_to_string(palette.name),
+                depth: palette.depth,
+                entries: slice_from_png(palette.entries, palette.nentries)
+                    .iter()
+                    .map(|entry| SuggestedPaletteEntry {
+                        red: entry.red,
+                        green: entry.green,
+                        blue: entry.blue,
+                        alpha: entry.alpha,
+                        frequency: entry.frequency,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        info
+    }
+
+    /// Converts chunks for [PngPixelInfo::set], after [validate](PixelInfo::validate).
+    pub(crate) fn to_png(&self) -> PngPixelInfo {
+        let color_16 = |index, red, green, blue, gray| png_color_16 {
+            index,
+            red,
+            green,
+            blue,
+            gray,
+        };
+
+        let palette = self.palette.as_ref();
+
+        let names: Vec<CString> = self
+            .suggested_palettes
+            .iter()
+            .map(|palette| {
+                let name = palette.name.chars().map(|c| u32::from(c) as u8);
+                CString::new(name.collect::<Vec<_>>()).unwrap_or_default()
+            })
+            .collect();
+        let entries: Vec<Vec<png_sPLT_entry>> = self
+            .suggested_palettes
+            .iter()
+            .map(|palette| {
+                palette
+                    .entries
+                    .iter()
+                    .map(|entry| png_sPLT_entry {
+                        red: entry.red,
+                        green: entry.green,
+                        blue: entry.blue,
+                        alpha: entry.alpha,
+                        frequency: entry.frequency,
+                    })
+                    .collect()
+            })
+            .collect();
+        let suggested_palettes = self
+            .suggested_palettes
+            .iter()
+            .zip(&names)
+            .zip(&entries)
+            .map(|((palette, name), entries)| png_sPLT_t {
+                name: name.as_ptr() as png_charp,
+                depth: palette.depth,
+                entries: entries.as_ptr() as *mut _,
+                nentries: entries.len() as c_int,
+            })
+            .collect();
+
+        PngPixelInfo {
+            colors: palette.map_or_else(Vec::new, |palette| {
+                palette.colors.iter().map(|c| c.to_png_color()).collect()
+            }),
+            alpha: palette.map_or_else(Vec::new, |palette| palette.alpha.clone()),
+            transparent_color: self.transparent_color.map(|color| match color {
+                TransparentColor::Gray(gray) => color_16(0, 0, 0, 0, gray),
+                TransparentColor::Rgb { red, green, blue } => color_16(0, red, green, blue, 0),
+            }),
+            background: self.background.map(|background| match background {
+                Background::Gray(gray) => color_16(0, 0, 0, 0, gray),
+                Background::Rgb { red, green, blue } => color_16(0, red, green, blue, 0),
+                Background::Index(index) => color_16(index, 0, 0, 0, 0),
+            }),
+            significant_bits: self.significant_bits.map(|bits| match bits {
+                SignificantBits::Gray { gray, alpha } => png_color_8 {
+                    red: 0,
+                    green: 0,
+                    blue: 0,
+                    gray,
+                    alpha: alpha.unwrap_or_default(),
+                },
+                SignificantBits::Rgb {
+                    red,
+                    green,
+                    blue,
+                    alpha,
+                } => png_color_8 {
+                    red,
+                    green,
+                    blue,
+                    gray: 0,
+                    alpha: alpha.unwrap_or_default(),
+                },
+            }),
+            histogram: self
+                .histogram
+                .as_ref()
+                .map(|histogram| histogram.frequencies.clone()),
+            suggested_palettes,
+            _names: names,
+            _entries: entries,
+        }
+    }
+}
+
+impl SuggestedPalette {
+    /// Checks name, depth and sample range.
+    pub fn validate(&self) -> Result<()> {
+        validate_keyword(&self.name)?;
+
+        let max_sample = match self.depth {
+            8 => u16::from(u8::MAX),
+            16 => u16::MAX,
+            depth => {
+                return Err(Error::InvalidInput(format!(
+                    "Suggested palette depth must be 8 or 16, got {depth}"
+                )))
+            }
+        };
+
+        if let Some(entry) = self.entries.iter().find(|entry| {
+            [entry.red, entry.green, entry.blue, entry.alpha]
+                .into_iter()
+                .any(|sample| sample > max_sample)
+        }) {
+            return Err(Error::InvalidInput(format!(
+                "Suggested palette samples must not exceed {max_sample}, got {entry:?}"
+            )));
+        }
+
+        // libpng fails to allocate empty palettes
+        if self.entries.is_empty() || c_int::try_from(self.entries.len()).is_err() {
+            return Err(Error::InvalidInput(format!(
+                "Suggested palette must have from 1 to {} entries, got {}",
+                c_int::MAX,
+                self.entries.len()
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// **libpng** structures of [PixelInfo] for the `png_set_*` functions, which copy them.
+pub(crate) struct PngPixelInfo {
+    colors: Vec<png_color>,
+    alpha: Vec<png_byte>,
+    transparent_color: Option<png_color_16>,
+    background: Option<png_color_16>,
+    significant_bits: Option<png_color_8>,
+    histogram: Option<Vec<u16>>,
+    // Points to names and entries
+    suggested_palettes: Vec<png_sPLT_t>,
+    _names: Vec<CString>,
+    _entries: Vec<Vec<png_sPLT_entry>>,
+}
+
+impl PngPixelInfo {
+    /// Sets pixel chunks in `info_ptr`, must be run via `png_rs_try` after setting the header.
+    pub(crate) unsafe fn set(&self, png_ptr: png_structp, info_ptr: png_infop) {
+        // PLTE is set first, tRNS, bKGD and hIST depend on the number of entries
+        if !self.colors.is_empty() {
+            png_set_PLTE(
+                png_ptr,
+                info_ptr,
+                self.colors.as_ptr(),
+                self.colors.len() as c_int,
+            );
+        }
+
+        if !self.alpha.is_empty() || self.transparent_color.is_some() {
+            png_set_tRNS(
+                png_ptr,
+                info_ptr,
+                self.alpha.as_ptr(),
+                self.alpha.len() as c_int,
+                self.transparent_color
+                    .as_ref()
+                    .map_or(null_mut(), |color| color as *const _ as *mut _),
+            );
+        }
+
+        if let Some(background) = &self.background {
+            png_set_bKGD(png_ptr, info_ptr, background);
+        }
+
+        if let Some(significant_bits) = &self.significant_bits {
+            png_set_sBIT(png_ptr, info_ptr, significant_bits);
+        }
+
+        if let Some(histogram) = &self.histogram {
+            png_set_hIST(png_ptr, info_ptr, histogram.as_ptr());
+        }
+
+        if !self.suggested_palettes.is_empty() {
+            png_set_sPLT(
+                png_ptr,
+                info_ptr,
+                self.suggested_palettes.as_ptr(),
+                self.suggested_palettes.len() as c_int,
+            );
+        }
+    }
+}
+
+/// Slice of `len` items at `ptr` of **libpng**, empty for null pointers.
+unsafe fn slice_from_png<'a, T>(ptr: *const T, len: c_int) -> &'a [T] {
+    if ptr.is_null() || len <= 0 {
+        return &[];
+    }
+
+    slice::from_raw_parts(ptr, len as usize)
+}
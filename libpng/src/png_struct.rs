@@ -0,0 +1,377 @@
+//! Owned **libpng** structs of the full API, with longjmp-free error handling via the C shim.
+//! Warnings and errors are forwarded to `log` and `tracing`, if enabled.
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    ffi::CStr,
+    io::{self, Read, Write},
+    panic::{self, AssertUnwindSafe},
+    ptr::null_mut,
+    slice,
+};
+
+use libc::{c_int, c_void};
+use libpng_vendored_sys::{
+    png_bytep, png_const_bytep, png_const_charp, png_create_info_struct, png_destroy_read_struct,
+    png_destroy_write_struct, png_get_current_row_number, png_get_io_chunk_type, png_infop,
+    png_rs_create_read_struct, png_rs_create_write_struct, png_rs_error_context, png_rs_io,
+    png_rs_set_read_fn, png_rs_set_write_fn, png_rs_try_fn, png_set_read_user_chunk_fn,
+    png_structp, PNG_RS_ERROR, PNG_RS_OK,
+};
+
+use crate::{
+    chunks::{user_chunk_callback, UserChunkCallback, UserChunkState},
+    limits::is_limit_message,
+    logging::{log_error, log_warning},
+    transforms::QuantizePalette,
+    ChunkName, Error, Limits, Result, Warning,
+};
+
+/// Read struct with its info struct, destroyed on drop.
+pub(crate) struct ReadStruct {
+    pub(crate) png_ptr: png_structp,
+    pub(crate) info_ptr: png_infop,
+    // Boxed, since libpng keeps pointers to them
+    context: Box<png_rs_error_context>,
+    warnings: Box<Warnings>,
+    io: Option<Box<png_rs_io>>,
+    // Owned, accessed by libpng via the user chunk pointer
+    user_chunk: *mut UserChunkState,
+    poisoned: Cell<bool>,
+    /// Limits set by [ReadOptions](crate::ReadOptions), checked by decoders as well.
+    pub(crate) limits: Option<Limits>,
+    /// Palette of [Transforms::quantize](crate::Transforms::quantize), libpng keeps a pointer to it.
+    pub(crate) quantize_palette: Option<QuantizePalette>,
+}
+
+impl ReadStruct {
+    pub(crate) fn new() -> Result<Self> {
+        let warnings = Box::new(Warnings::new(true));
+        let mut context = Box::new(png_rs_error_context::new(
+            Some(warning_callback),
+            &*warnings as *const Warnings as *mut c_void,
+        ));
+
+        let png_ptr = unsafe { png_rs_create_read_struct(&mut *context) };
+        if png_ptr.is_null() {
+            return Err(Error::Png(String::from("Cannot create read struct")));
+        }
+        warnings.png_ptr.set(png_ptr);
+
+        let mut read_struct = Self {
+            png_ptr,
+            info_ptr: null_mut(),
+            context,
+            warnings,
+            io: None,
+            user_chunk: null_mut(),
+            poisoned: Cell::new(false),
+            limits: None,
+            quantize_palette: None,
+        };
+
+        read_struct.info_ptr = unsafe { png_create_info_struct(png_ptr) };
+        if read_struct.info_ptr.is_null() {
+            return Err(Error::Png(String::from("Cannot create info struct")));
+        }
+
+        Ok(read_struct)
+    }
+
+    /// Makes **libpng** read from `source`, which must outlive the struct.
+    pub(crate) fn set_read_source<R: Read>(&mut self, source: &mut IoState<R>) {
+        let mut io = Box::new(png_rs_io {
+            read: Some(read_callback::<R>),
+            write: None,
+            flush: None,
+            user_data: source as *mut IoState<R> as *mut c_void,
+        });
+
+        unsafe { png_rs_set_read_fn(self.png_ptr, &mut *io) };
+
+        self.io = Some(io);
+    }
+
+    /// Makes **libpng** pass unknown chunks to `callback` before applying keep policies.
+    pub(crate) fn set_user_chunk_callback(&mut self, callback: UserChunkCallback) {
+        let state = Box::into_raw(Box::new(UserChunkState::new(callback)));
+
+        unsafe {
+            png_set_read_user_chunk_fn(self.png_ptr, state.cast(), Some(user_chunk_callback));
+            self.drop_user_chunk();
+        }
+
+        self.user_chunk = state;
+    }
+
+    /// Runs **libpng** calls, converting `png_error` into [Error::Png].
+    /// Errors of the user chunk callback are returned instead, its panics are propagated
+    /// and make further calls fail. With [limits](ReadStruct::limits), exceeded chunk limits
+    /// are returned as [Error::LimitExceeded], even if **libpng** only warned and dropped the chunk.
+    ///
+    /// `f` must only call **libpng** functions, its frame is skipped on error.
+    pub(crate) fn call<F: FnOnce() + Copy>(&self, f: F) -> Result<()> {
+        if self.poisoned.get() {
+            return Err(Error::Png(String::from(
+                "User chunk callback panicked earlier",
+            )));
+        }
+
+        let result = try_call(self.png_ptr, &self.context, f).map_err(|error| {
+            if self.user_chunk.is_null() {
+                return error;
+            }
+
+            // libpng does not access the state after returning
+            let state = unsafe { &mut *self.user_chunk };
+            if let Some(panic) = state.panic.take() {
+                self.poisoned.set(true);
+                panic::resume_unwind(panic);
+            }
+
+            state.error.take().unwrap_or(error)
+        });
+
+        let limit_exceeded = self.warnings.limit_exceeded.take();
+        if self.limits.is_none() {
+            return result;
+        }
+
+        match (result, limit_exceeded) {
+            (Err(Error::Png(message)), _) if is_limit_message(&message) => {
+                Err(Error::LimitExceeded(message))
+            }
+            (Ok(()) | Err(Error::Png(_)), Some(message)) => Err(Error::LimitExceeded(message)),
+            (result, _) => result,
+        }
+    }
+
+    /// Warnings of **libpng** so far.
+    pub(crate) fn warnings(&self) -> Vec<Warning> {
+        self.warnings.all.borrow().clone()
+    }
+
+    unsafe fn drop_user_chunk(&mut self) {
+        if !self.user_chunk.is_null() {
+            drop(Box::from_raw(self.user_chunk));
+            self.user_chunk = null_mut();
+        }
+    }
+}
+
+impl Drop for ReadStruct {
+    fn drop(&mut self) {
+        unsafe {
+            png_destroy_read_struct(&mut self.png_ptr, &mut self.info_ptr, null_mut());
+            self.drop_user_chunk();
+        }
+    }
+}
+
+/// Write struct with its info struct, destroyed on drop.
+pub(crate) struct WriteStruct {
+    pub(crate) png_ptr: png_structp,
+    pub(crate) info_ptr: png_infop,
+    // Boxed, since libpng keeps pointers to them
+    context: Box<png_rs_error_context>,
+    _warnings: Box<Warnings>,
+    io: Option<Box<png_rs_io>>,
+}
+
+impl WriteStruct {
+    pub(crate) fn new() -> Result<Self> {
+        // Only logged
+        let warnings = Box::new(Warnings::new(false));
+        let mut context = Box::new(png_rs_error_context::new(
+            Some(warning_callback),
+            &*warnings as *const Warnings as *mut c_void,
+        ));
+
+        let png_ptr = unsafe { png_rs_create_write_struct(&mut *context) };
+        if png_ptr.is_null() {
+            return Err(Error::Png(String::from("Cannot create write struct")));
+        }
+        warnings.png_ptr.set(png_ptr);
+
+        let mut write_struct = Self {
+            png_ptr,
+            info_ptr: null_mut(),
+            context,
+            _warnings: warnings,
+            io: None,
+        };
+
+        write_struct.info_ptr = unsafe { png_create_info_struct(png_ptr) };
+        if write_struct.info_ptr.is_null() {
+            return Err(Error::Png(String::from("Cannot create info struct")));
+        }
+
+        Ok(write_struct)
+    }
+
+    /// Makes **libpng** write to `sink`, which must outlive the struct.
+    pub(crate) fn set_write_sink<W: Write>(&mut self, sink: &mut IoState<W>) {
+        let mut io = Box::new(png_rs_io {
+            read: None,
+            write: Some(write_callback::<W>),
+            flush: Some(flush_callback::<W>),
+            user_data: sink as *mut IoState<W> as *mut c_void,
+        });
+
+        unsafe { png_rs_set_write_fn(self.png_ptr, &mut *io) };
+
+        self.io = Some(io);
+    }
+
+    /// Runs **libpng** calls, converting `png_error` into [Error::Png].
+    ///
+    /// `f` must only call **libpng** functions, its frame is skipped on error.
+    pub(crate) fn call<F: FnOnce() + Copy>(&self, f: F) -> Result<()> {
+        try_call(self.png_ptr, &self.context, f)
+    }
+}
+
+impl Drop for WriteStruct {
+    fn drop(&mut self) {
+        unsafe { png_destroy_write_struct(&mut self.png_ptr, &mut self.info_ptr) };
+    }
+}
+
+/// Warnings of **libpng**, passed to the warning callback as user data.
+struct Warnings {
+    png_ptr: Cell<png_structp>,
+    collect: bool,
+    all: RefCell<Vec<Warning>>,
+    limit_exceeded: Cell<Option<String>>,
+}
+
+impl Warnings {
+    fn new(collect: bool) -> Self {
+        Self {
+            png_ptr: Cell::new(null_mut()),
+            collect,
+            all: RefCell::default(),
+            limit_exceeded: Cell::default(),
+        }
+    }
+}
+
+unsafe extern "C" fn warning_callback(user_data: *mut c_void, message: png_const_charp) {
+    let warnings = &*(user_data as *const Warnings);
+    let message = CStr::from_ptr(message).to_string_lossy().into_owned();
+
+    if is_limit_message(&message) {
+        warnings.limit_exceeded.set(Some(message.clone()));
+    }
+
+    let png_ptr = warnings.png_ptr.get();
+    let chunk = ChunkName::new(png_get_io_chunk_type(png_ptr).to_be_bytes()).ok();
+    // Rows are only read in IDAT chunks
+    let row = chunk
+        .filter(|chunk| chunk.bytes() == *b"IDAT")
+        .map(|_| png_get_current_row_number(png_ptr));
+
+    let warning = Warning {
+        message,
+        chunk,
+        row,
+    };
+    // libpng cannot unwind, panics of loggers and subscribers are dropped
+    let _ = panic::catch_unwind(|| log_warning(&warning));
+
+    // Not borrowed elsewhere while libpng runs
+    if warnings.collect {
+        if let Ok(mut all) = warnings.all.try_borrow_mut() {
+            all.push(warning);
+        }
+    }
+}
+
+// libpng structs are not tied to a thread, their pointers are owned or point to data owned by their users
+unsafe impl Send for ReadStruct {}
+unsafe impl Send for WriteStruct {}
+
+fn try_call<F: FnOnce() + Copy>(
+    png_ptr: png_structp,
+    context: &png_rs_error_context,
+    f: F,
+) -> Result<()> {
+    match unsafe { png_rs_try_fn(png_ptr, f) } {
+        PNG_RS_OK => Ok(()),
+        _ => {
+            let message = context.message().to_string_lossy().into_owned();
+            log_error(&message);
+            Err(Error::Png(message))
+        }
+    }
+}
+
+/// Reader or writer for **libpng** callbacks, keeps the I/O error to report it instead of a **libpng** message.
+/// Panics of the reader or writer are kept as well, since they cannot unwind through **libpng**.
+pub(crate) struct IoState<T> {
+    pub(crate) inner: T,
+    pub(crate) error: Option<io::Error>,
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+impl<T> IoState<T> {
+    pub(crate) fn new(inner: T) -> Self {
+        Self {
+            inner,
+            error: None,
+            panic: None,
+        }
+    }
+
+    /// Replaces a generic **libpng** I/O error with the original one, propagates panics of the reader or writer.
+    pub(crate) fn map_error(&mut self, error: Error) -> Error {
+        if let Some(panic) = self.panic.take() {
+            panic::resume_unwind(panic);
+        }
+
+        self.error.take().map_or(error, Error::Io)
+    }
+
+    /// Runs an I/O operation of the reader or writer, catching its panic.
+    fn run(&mut self, f: impl FnOnce(&mut T) -> io::Result<()>) -> c_int {
+        match panic::catch_unwind(AssertUnwindSafe(|| f(&mut self.inner))) {
+            Ok(Ok(())) => PNG_RS_OK,
+            Ok(Err(error)) => {
+                self.error = Some(error);
+                PNG_RS_ERROR
+            }
+            Err(panic) => {
+                self.panic = Some(panic);
+                PNG_RS_ERROR
+            }
+        }
+    }
+}
+
+unsafe extern "C" fn read_callback<R: Read>(
+    user_data: *mut c_void,
+    data: png_bytep,
+    length: usize,
+) -> c_int {
+    let source = &mut *(user_data as *mut IoState<R>);
+    let buffer = slice::from_raw_parts_mut(data, length);
+
+    source.run(|reader| reader.read_exact(buffer))
+}
+
+unsafe extern "C" fn write_callback<W: Write>(
+    user_data: *mut c_void,
+    data: png_const_bytep,
+    length: usize,
+) -> c_int {
+    let sink = &mut *(user_data as *mut IoState<W>);
+    let buffer = slice::from_raw_parts(data, length);
+
+    sink.run(|writer| writer.write_all(buffer))
+}
+
+unsafe extern "C" fn flush_callback<W: Write>(user_data: *mut c_void) -> c_int {
+    let sink = &mut *(user_data as *mut IoState<W>);
+
+    sink.run(|writer| writer.flush())
+}
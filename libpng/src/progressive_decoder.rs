@@ -0,0 +1,471 @@
+use std::{
+    any::Any,
+    ops::ControlFlow,
+    panic::{self, AssertUnwindSafe},
+    ptr::null_mut,
+    slice,
+};
+
+use libc::{c_int, c_void};
+use libpng_vendored_sys::{
+    png_bytep, png_infop, png_process_data, png_read_update_info, png_rs_progressive,
+    png_rs_set_progressive_read_fn, png_set_interlace_handling, png_structp, png_uint_32,
+    PNG_RS_ERROR, PNG_RS_OK, PNG_RS_PAUSE,
+};
+
+use crate::{
+    chunks::get_unknown_chunks,
+    exif::{get_exif, keep_exif_chunk},
+    limits::ChunkScanner,
+    png_struct::ReadStruct,
+    stream_decoder::State,
+    text::get_text,
+    ColorInfo, Error, ModificationTime, PhysicalInfo, PixelInfo, ReadOptions, Result, RowPosition,
+    StreamInfo, TextChunk, Transforms, UnknownChunk, Warning,
+};
+
+/// Event reported by [ProgressiveDecoder], variants are listed in the order they occur.
+#[derive(Debug)]
+pub enum ProgressiveEvent<'a> {
+    /// The header and chunks before image data are read.
+    Header(&'a StreamInfo),
+    /// A row is updated by a pass, `data` is its current content.
+    Row {
+        position: RowPosition,
+        data: &'a [u8],
+    },
+    /// All rows of all passes are decoded.
+    ImageComplete,
+    /// The end of the image is reached, chunks after image data are read as well.
+    End,
+}
+
+/// Input is passed to **libpng** in pieces of this size, to stop soon after a row handler requests a pause.
+const PIECE_SIZE: usize = 1024;
+
+/// Push-based decoder for PNG data arriving in chunks, e.g. over a network protocol.
+///
+/// Data is passed to [feed](ProgressiveDecoder::feed) as soon as it arrives, events are reported to the handler
+/// as soon as libpng has enough data for them. Rows are in the encoded format, like of [StreamDecoder](crate::StreamDecoder),
+/// unless [Transforms] are set via [with_transforms](ProgressiveDecoder::with_transforms).
+///
+/// Interlaced images are rendered progressively: the whole image is kept and each pass is combined into it.
+/// **libpng** replicates pixels of early passes horizontally within their rows, but rows are not copied vertically,
+/// so rows not reached by any pass yet stay zeroed.
+/// Non-interlaced images keep a single row.
+/// ```no_run
+/// use std::{io::Read, net::TcpStream, ops::ControlFlow};
+///
+/// use libpng::{ProgressiveDecoder, ProgressiveEvent};
+///
+/// let mut stream = TcpStream::connect("example.com:8000")?;
+/// let mut decoder = ProgressiveDecoder::new()?;
+/// let mut buffer = [0; 4096];
+///
+/// while !decoder.is_finished() {
+///     let length = stream.read(&mut buffer)?;
+///     if length == 0 {
+///         break;
+///     }
+///
+///     decoder.feed(&buffer[..length], |event| {
+///         if let ProgressiveEvent::Row { position, data } = event {
+///             println!("Row {} of pass {}: {} bytes", position.y, position.pass, data.len());
+///         }
+///
+///         ControlFlow::Continue(())
+///     })?;
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct ProgressiveDecoder {
+    png: ReadStruct,
+    // Boxed, since libpng keeps a pointer to it
+    progressive: Box<png_rs_progressive>,
+    progress: Progress,
+    transforms: Transforms,
+    chunk_scanner: Option<ChunkScanner>,
+    state: State,
+}
+
+// Progressive pointers point to owned rows, user data is set only during `feed`
+unsafe impl Send for ProgressiveDecoder {}
+
+#[derive(Default)]
+struct Progress {
+    info: Option<StreamInfo>,
+    rows: Vec<u8>,
+    image_complete: bool,
+    ended: bool,
+}
+
+impl ProgressiveDecoder {
+    pub fn new() -> Result<Self> {
+        Self::with_options(ReadOptions::new())
+    }
+
+    /// Decoder handling chunks according to `options`.
+    pub fn with_options(options: ReadOptions) -> Result<Self> {
+        let mut png = ReadStruct::new()?;
+        options.apply(&mut png)?;
+
+        let mut progressive = Box::new(png_rs_progressive {
+            info: None,
+            row: None,
+            end: None,
+            rows: null_mut(),
+            row_stride: 0,
+            remaining: 0,
+            user_data: null_mut(),
+        });
+
+        let png_ptr = png.png_ptr;
+        png.call(|| unsafe { keep_exif_chunk(png_ptr) })?;
+        unsafe { png_rs_set_progressive_read_fn(png_ptr, &mut *progressive) };
+
+        let chunk_scanner = png.limits.map(ChunkScanner::new);
+
+        Ok(Self {
+            png,
+            progressive,
+            progress: Progress::default(),
+            transforms: Transforms::new(),
+            chunk_scanner,
+            state: State::Decoding,
+        })
+    }
+
+    /// Applies `transforms` when the header is read, [info](ProgressiveDecoder::info) describes transformed rows.
+    /// Has no effect after the [Header](ProgressiveEvent::Header) event.
+    pub fn with_transforms(mut self, transforms: Transforms) -> Self {
+        // libpng keeps a pointer to the palette once transforms are applied
+        if self.progress.info.is_none() && self.state == State::Decoding {
+            self.png.quantize_palette = transforms.quantize_palette();
+        }
+        self.transforms = transforms;
+        self
+    }
+
+    /// Row info, available after the [Header](ProgressiveEvent::Header) event.
+    pub fn info(&self) -> Option<&StreamInfo> {
+        self.progress.info.as_ref()
+    }
+
+    /// Color chunks, available after the [Header](ProgressiveEvent::Header) event.
+    pub fn color_info(&self) -> ColorInfo {
+        unsafe { ColorInfo::from_png(self.png.png_ptr, self.png.info_ptr) }
+    }
+
+    /// Physical chunks, available after the [Header](ProgressiveEvent::Header) event.
+    pub fn physical_info(&self) -> PhysicalInfo {
+        unsafe { PhysicalInfo::from_png(self.png.png_ptr, self.png.info_ptr) }
+    }
+
+    /// Palette and pixel chunks, available after the [Header](ProgressiveEvent::Header) event.
+    pub fn pixel_info(&self) -> PixelInfo {
+        unsafe { PixelInfo::from_png(self.png.png_ptr, self.png.info_ptr) }
+    }
+
+    /// Time of tIME chunk, read before image data or after it before the [End](ProgressiveEvent::End) event.
+    pub fn modification_time(&self) -> Option<ModificationTime> {
+        unsafe { ModificationTime::from_png(self.png.png_ptr, self.png.info_ptr) }
+    }
+
+    /// Raw EXIF data of eXIf chunk, available after the [Header](ProgressiveEvent::Header) event. Parsed by [ExifInfo::parse](crate::ExifInfo::parse).
+    pub fn exif(&self) -> Option<Vec<u8>> {
+        unsafe { get_exif(self.png.png_ptr, self.png.info_ptr) }
+    }
+
+    /// Warnings of **libpng** so far, see [Strictness](crate::Strictness).
+    pub fn warnings(&self) -> Vec<Warning> {
+        self.png.warnings()
+    }
+
+    /// Unknown chunks kept by [ReadOptions] so far, those after image data are added
+    /// before the [End](ProgressiveEvent::End) event.
+    pub fn unknown_chunks(&self) -> Vec<UnknownChunk> {
+        let chunks = unsafe { get_unknown_chunks(self.png.png_ptr, self.png.info_ptr) };
+
+        // Kept by `keep_exif_chunk` for `exif`
+        chunks
+            .into_iter()
+            .filter(|chunk| chunk.name.bytes() != *b"eXIf")
+            .collect()
+    }
+
+    /// Text chunks read so far, those after image data are added before the [End](ProgressiveEvent::End) event.
+    pub fn text(&self) -> Vec<TextChunk> {
+        unsafe { get_text(self.png.png_ptr, self.png.info_ptr) }
+    }
+
+    /// Whether the [End](ProgressiveEvent::End) event is reported, further data is ignored.
+    pub fn is_finished(&self) -> bool {
+        self.state == State::Finished
+    }
+
+    /// Decodes `data`, reporting events to `handler`. Returns the number of consumed bytes,
+    /// which is less than `data.len()` only if the handler paused decoding, the rest must be fed again.
+    ///
+    /// [ControlFlow::Break] from the [Header](ProgressiveEvent::Header) event pauses right after the header
+    /// via `png_process_data_pause`. Pausing inside image data makes **libpng** 1.6 process it twice,
+    /// so other events pause after the current piece of input, a few more events may follow.
+    ///
+    /// Panics of the handler are propagated after **libpng** returns, the decoder cannot be used after that.
+    pub fn feed<F>(&mut self, data: &[u8], mut handler: F) -> Result<usize>
+    where
+        F: FnMut(ProgressiveEvent<'_>) -> ControlFlow<()>,
+    {
+        match self.state {
+            State::Decoding => {}
+            State::Finished => return Ok(data.len()),
+            State::Failed => {
+                return Err(Error::Png(String::from(
+                    "Decoding failed earlier and cannot be continued",
+                )))
+            }
+        }
+
+        let png_ptr = self.png.png_ptr;
+        let info_ptr = self.png.info_ptr;
+
+        let mut feed = Feed {
+            png: &self.png,
+            png_ptr,
+            info_ptr,
+            transforms: &self.transforms,
+            progressive: &mut *self.progressive,
+            progress: &mut self.progress,
+            handler: &mut handler,
+            paused: false,
+            error: None,
+            panic: None,
+        };
+
+        self.progressive.info = Some(info_callback::<F>);
+        self.progressive.row = Some(row_callback::<F>);
+        self.progressive.end = Some(end_callback::<F>);
+        // Accessed only via the pointer while libpng may use it
+        let feed_ptr: *mut Feed<F> = &mut feed;
+        self.progressive.user_data = feed_ptr as *mut c_void;
+
+        let mut consumed = 0;
+        let mut result = Ok(());
+
+        for piece in data.chunks(PIECE_SIZE) {
+            let piece_ptr = piece.as_ptr() as png_bytep;
+            let length = piece.len();
+
+            self.progressive.remaining = 0;
+            result = self
+                .png
+                .call(|| unsafe { png_process_data(png_ptr, info_ptr, piece_ptr, length) });
+
+            let piece_consumed = length - self.progressive.remaining;
+            consumed += piece_consumed;
+
+            if let (Ok(()), Some(scanner)) = (&result, &mut self.chunk_scanner) {
+                result = scanner.scan(&piece[..piece_consumed]);
+            }
+
+            let (paused, ended) = unsafe { ((*feed_ptr).paused, (*feed_ptr).progress.ended) };
+            if result.is_err() || paused || ended {
+                break;
+            }
+        }
+
+        self.progressive.info = None;
+        self.progressive.row = None;
+        self.progressive.end = None;
+        self.progressive.user_data = null_mut();
+
+        let Feed { error, panic, .. } = feed;
+
+        if let Some(panic) = panic {
+            self.state = State::Failed;
+            panic::resume_unwind(panic);
+        }
+
+        if let Err(png_error) = result {
+            self.state = State::Failed;
+            return Err(error.unwrap_or(png_error));
+        }
+
+        if self.progress.ended {
+            self.state = State::Finished;
+            consumed = data.len();
+        }
+
+        Ok(consumed)
+    }
+}
+
+/// State of a [ProgressiveDecoder::feed] call, passed to callbacks as user data.
+struct Feed<'a, F> {
+    png: &'a ReadStruct,
+    png_ptr: png_structp,
+    info_ptr: png_infop,
+    transforms: &'a Transforms,
+    // Raw, since libpng holds a pointer to it as well
+    progressive: *mut png_rs_progressive,
+    progress: &'a mut Progress,
+    handler: &'a mut F,
+    paused: bool,
+    error: Option<Error>,
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+impl<F: FnMut(ProgressiveEvent<'_>) -> ControlFlow<()>> Feed<'_, F> {
+    /// Runs a callback body, converting its result into a status for the C side.
+    /// [ControlFlow::Break] is returned as [PNG_RS_PAUSE], only the info callback passes it to the C side.
+    fn run(&mut self, body: impl FnOnce(&mut Self) -> Result<ControlFlow<()>>) -> c_int {
+        match panic::catch_unwind(AssertUnwindSafe(|| body(self))) {
+            Ok(Ok(ControlFlow::Continue(()))) => PNG_RS_OK,
+            Ok(Ok(ControlFlow::Break(()))) => {
+                self.paused = true;
+                PNG_RS_PAUSE
+            }
+            Ok(Err(error)) => {
+                self.error = Some(error);
+                PNG_RS_ERROR
+            }
+            Err(panic) => {
+                self.panic = Some(panic);
+                PNG_RS_ERROR
+            }
+        }
+    }
+
+    fn image_complete(&mut self) -> ControlFlow<()> {
+        self.progress.image_complete = true;
+        (self.handler)(ProgressiveEvent::ImageComplete)
+    }
+}
+
+unsafe extern "C" fn info_callback<F: FnMut(ProgressiveEvent<'_>) -> ControlFlow<()>>(
+    user_data: *mut c_void,
+) -> c_int {
+    let feed = &mut *(user_data as *mut Feed<F>);
+
+    feed.run(|feed| {
+        let png_ptr = feed.png_ptr;
+        let info_ptr = feed.info_ptr;
+        let transforms = feed.transforms;
+
+        if let Some(limits) = &feed.png.limits {
+            unsafe { limits.check_header(png_ptr, info_ptr)? };
+        }
+
+        unsafe { transforms.validate_for(png_ptr, info_ptr)? };
+
+        // Nested, the info callback is run by libpng
+        let quantize_palette = feed.png.quantize_palette.as_ref();
+        feed.png.call(|| unsafe {
+            transforms.apply(png_ptr, quantize_palette);
+            png_set_interlace_handling(png_ptr);
+            png_read_update_info(png_ptr, info_ptr);
+        })?;
+
+        let info = unsafe { StreamInfo::from_png(feed.png)? };
+
+        if let Some(limits) = &feed.png.limits {
+            limits.check_decoded_bytes(info.row_bytes, info.height)?;
+        }
+
+        // Interlaced rows are combined with their content from previous passes
+        let (size, row_stride) = if info.interlaced {
+            let size = info
+                .row_bytes
+                .checked_mul(info.height as usize)
+                .ok_or_else(|| Error::Png(String::from("Image is too large")))?;
+
+            (size, info.row_bytes)
+        } else {
+            (info.row_bytes, 0)
+        };
+
+        let rows = &mut feed.progress.rows;
+        rows.try_reserve_exact(size)
+            .map_err(|_| Error::Png(String::from("Cannot allocate rows")))?;
+        rows.resize(size, 0);
+
+        unsafe {
+            (*feed.progressive).rows = rows.as_mut_ptr();
+            (*feed.progressive).row_stride = row_stride;
+        }
+
+        let info = feed.progress.info.insert(info);
+
+        Ok((feed.handler)(ProgressiveEvent::Header(info)))
+    })
+}
+
+unsafe extern "C" fn row_callback<F: FnMut(ProgressiveEvent<'_>) -> ControlFlow<()>>(
+    user_data: *mut c_void,
+    row: png_bytep,
+    row_num: png_uint_32,
+    pass: c_int,
+) -> c_int {
+    let feed = &mut *(user_data as *mut Feed<F>);
+
+    let status = feed.run(|feed| {
+        let Some(info) = &feed.progress.info else {
+            return Err(Error::Png(String::from("Row is decoded before the header")));
+        };
+
+        let position = RowPosition {
+            pass: pass as u32,
+            y: row_num,
+        };
+        let last = position.y + 1 == info.height && position.pass + 1 == info.passes;
+
+        // Null rows have no pixels of the pass, they are reported to detect the last one only
+        let mut flow = ControlFlow::Continue(());
+        if !row.is_null() {
+            let data = unsafe { slice::from_raw_parts(row, info.row_bytes) };
+            flow = (feed.handler)(ProgressiveEvent::Row { position, data });
+        }
+
+        if last && !feed.progress.image_complete && feed.image_complete().is_break() {
+            flow = ControlFlow::Break(());
+        }
+
+        Ok(flow)
+    });
+
+    without_pause(status)
+}
+
+unsafe extern "C" fn end_callback<F: FnMut(ProgressiveEvent<'_>) -> ControlFlow<()>>(
+    user_data: *mut c_void,
+) -> c_int {
+    let feed = &mut *(user_data as *mut Feed<F>);
+
+    let status = feed.run(|feed| {
+        if let Some(limits) = &feed.png.limits {
+            unsafe { limits.check_metadata(feed.png_ptr, feed.info_ptr)? };
+        }
+
+        let mut flow = ControlFlow::Continue(());
+        if !feed.progress.image_complete {
+            flow = feed.image_complete();
+        }
+
+        feed.progress.ended = true;
+
+        if (feed.handler)(ProgressiveEvent::End).is_break() {
+            flow = ControlFlow::Break(());
+        }
+
+        Ok(flow)
+    });
+
+    without_pause(status)
+}
+
+/// The pause is handled between pieces of input instead.
+fn without_pause(status: c_int) -> c_int {
+    match status {
+        PNG_RS_PAUSE => PNG_RS_OK,
+        status => status,
+    }
+}
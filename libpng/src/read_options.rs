@@ -0,0 +1,145 @@
+use std::fmt;
+
+use crate::{
+    chunks::{chunk_list, set_keep, UserChunkCallback},
+    png_struct::ReadStruct,
+    ChunkName, Error, KeepPolicy, Limits, Result, Strictness, UnknownChunk,
+};
+
+/// Reading options of [StreamDecoder](crate::StreamDecoder), [ProgressiveDecoder](crate::ProgressiveDecoder)
+/// and async decoders, applied before any data is read: handling of unknown chunks, [Limits] and [Strictness].
+///
+/// Unknown chunks are discarded by default. Kept ones are returned by `unknown_chunks` of decoders:
+/// ```
+/// use libpng::{KeepPolicy, ReadOptions};
+///
+/// let options = ReadOptions::new()
+///     .with_unknown_chunks(KeepPolicy::IfSafe)
+///     .with_chunk_policy("prVt".parse()?, KeepPolicy::Always)
+///     .with_user_chunk_callback(|chunk| {
+///         println!("{} of {} bytes", chunk.name, chunk.data.len());
+///         Ok(false)
+///     });
+/// # Ok::<(), libpng::Error>(())
+/// ```
+#[derive(Default)]
+pub struct ReadOptions {
+    unknown_chunks: KeepPolicy,
+    chunk_policies: Vec<(ChunkName, KeepPolicy)>,
+    user_chunk_callback: Option<UserChunkCallback>,
+    limits: Option<Limits>,
+    strictness: Strictness,
+}
+
+impl ReadOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Policy for unknown chunks without a policy of their own, [KeepPolicy::Default] discards them.
+    pub fn with_unknown_chunks(mut self, keep: KeepPolicy) -> Self {
+        self.unknown_chunks = keep;
+        self
+    }
+
+    /// Policy for chunks named `name`, the last one wins. Known ancillary chunks, like tEXt,
+    /// are handled as unknown unless `keep` is [KeepPolicy::Default]. IHDR, PLTE, IDAT and IEND are rejected.
+    pub fn with_chunk_policy(mut self, name: ChunkName, keep: KeepPolicy) -> Self {
+        self.chunk_policies.push((name, keep));
+        self
+    }
+
+    /// Passes unknown chunks to `callback` before keep policies are applied.
+    ///
+    /// `Ok(true)` marks the chunk as handled, so it's not kept, and accepts unknown critical chunks.
+    /// `Ok(false)` applies policies, **libpng** keeps ancillary chunks even if discarded by them.
+    /// Errors fail decoding and are returned by the decoder.
+    pub fn with_user_chunk_callback<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&UnknownChunk) -> Result<bool> + Send + 'static,
+    {
+        self.user_chunk_callback = Some(Box::new(callback));
+        self
+    }
+
+    /// Fails decoding of images exceeding `limits` with [Error::LimitExceeded].
+    /// Without limits only the defaults of **libpng** apply, which drops excess chunks.
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    /// Handling of CRC errors and other damage, [Strictness::Lenient] by default.
+    /// Warnings are returned by `warnings` of decoders.
+    pub fn with_strictness(mut self, strictness: Strictness) -> Self {
+        self.strictness = strictness;
+        self
+    }
+
+    /// Checks chunk policies and limits, which is done by decoders as well.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(limits) = &self.limits {
+            limits.validate()?;
+        }
+
+        if let Some((name, _)) = self
+            .chunk_policies
+            .iter()
+            .find(|(name, _)| name.is_structural())
+        {
+            return Err(Error::InvalidInput(format!(
+                "Chunk {name} is always handled by libpng"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Sets policies, limits, strictness and the callback on `png`, before any data is read.
+    pub(crate) fn apply(self, png: &mut ReadStruct) -> Result<()> {
+        self.validate()?;
+
+        let lists: Vec<_> = self
+            .chunk_policies
+            .iter()
+            .map(|(name, keep)| (*keep, chunk_list([name])))
+            .collect();
+
+        let png_ptr = png.png_ptr;
+        let unknown_chunks = self.unknown_chunks;
+        let lists = &lists;
+        let limits = self.limits;
+        let strictness = self.strictness;
+        png.call(|| unsafe {
+            strictness.set(png_ptr);
+            set_keep(png_ptr, unknown_chunks, &[], 0);
+
+            for (keep, (list, count)) in lists {
+                set_keep(png_ptr, *keep, list, *count);
+            }
+
+            if let Some(limits) = &limits {
+                limits.set(png_ptr);
+            }
+        })?;
+        png.limits = limits;
+
+        if let Some(callback) = self.user_chunk_callback {
+            png.set_user_chunk_callback(callback);
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ReadOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadOptions")
+            .field("unknown_chunks", &self.unknown_chunks)
+            .field("chunk_policies", &self.chunk_policies)
+            .field("user_chunk_callback", &self.user_chunk_callback.is_some())
+            .field("limits", &self.limits)
+            .field("strictness", &self.strictness)
+            .finish()
+    }
+}
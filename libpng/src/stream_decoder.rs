@@ -0,0 +1,312 @@
+use std::{io::Read, ptr::null_mut};
+
+use libpng_vendored_sys::{
+    png_get_bit_depth, png_get_channels, png_get_color_type, png_get_current_pass_number,
+    png_get_image_height, png_get_image_width, png_get_interlace_type, png_get_rowbytes,
+    png_read_end, png_read_info, png_read_row, png_read_update_info, png_set_interlace_handling,
+    PNG_INTERLACE_NONE, PNG_PASS_COLS, PNG_ROW_IN_INTERLACE_PASS,
+};
+
+use crate::{
+    chunks::get_unknown_chunks,
+    exif::get_exif,
+    png_struct::{IoState, ReadStruct},
+    text::get_text,
+    Color, ColorInfo, ColorType, Error, ModificationTime, PhysicalInfo, PixelInfo, ReadOptions,
+    Result, TextChunk, Transforms, UnknownChunk, Warning,
+};
+
+/// Header of a streamed image, describes decoded rows.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StreamInfo {
+    pub width: u32,
+    pub height: u32,
+    /// Bits per sample, 16-bit samples are big-endian unless swapped by [Transforms::swap].
+    pub bit_depth: u8,
+    pub color_type: ColorType,
+    pub channels: u8,
+    /// Size of a decoded row in bytes.
+    pub row_bytes: usize,
+    pub interlaced: bool,
+    /// Number of passes rows are decoded in, 7 for Adam7 interlaced images, 1 otherwise.
+    pub passes: u32,
+    /// Colors of indices in rows quantized by [Transforms::quantize].
+    pub palette: Option<Vec<Color>>,
+}
+
+impl StreamInfo {
+    /// Info of decoded rows, after `png_read_update_info` with transforms and interlace handling.
+    pub(crate) unsafe fn from_png(png: &ReadStruct) -> Result<Self> {
+        let png_ptr = png.png_ptr;
+        let info_ptr = png.info_ptr;
+        let color_type = png_get_color_type(png_ptr, info_ptr);
+        let interlaced = png_get_interlace_type(png_ptr, info_ptr) as u32 != PNG_INTERLACE_NONE;
+
+        Ok(Self {
+            width: png_get_image_width(png_ptr, info_ptr),
+            height: png_get_image_height(png_ptr, info_ptr),
+            bit_depth: png_get_bit_depth(png_ptr, info_ptr),
+            color_type: ColorType::from_png(color_type)
+                .ok_or_else(|| Error::Png(format!("Unknown color type: {color_type}")))?,
+            channels: png_get_channels(png_ptr, info_ptr),
+            row_bytes: png_get_rowbytes(png_ptr, info_ptr),
+            interlaced,
+            passes: if interlaced { 7 } else { 1 },
+            palette: png
+                .quantize_palette
+                .as_ref()
+                .map(|palette| palette.reduced()),
+        })
+    }
+}
+
+/// Position of a decoded row in the image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RowPosition {
+    /// Adam7 pass, always 0 for non-interlaced images.
+    pub pass: u32,
+    /// Row in the image.
+    pub y: u32,
+}
+
+/// Decodes a PNG image row by row from any [Read] implementation, without holding the whole image in memory.
+///
+/// Interlaced images are decoded in 7 passes, each pass yields only rows containing its pixels.
+/// Pixels of a pass are written at their final positions in the row buffer, other pixels are kept intact,
+/// so the buffer of the row from the previous passes must be passed to get a complete image,
+/// see [next_position](StreamDecoder::next_position).
+/// ```no_run
+/// use std::fs::File;
+///
+/// use libpng::StreamDecoder;
+///
+/// let mut decoder = StreamDecoder::new(File::open("image.png")?)?;
+/// let mut row = vec![0; decoder.info().row_bytes];
+///
+/// while let Some(position) = decoder.next_row(&mut row)? {
+///     println!("Row {} of pass {}", position.y, position.pass);
+/// }
+/// # Ok::<(), libpng::Error>(())
+/// ```
+pub struct StreamDecoder<R: Read> {
+    // Dropped before the source it reads from
+    png: ReadStruct,
+    source: Box<IoState<R>>,
+    info: StreamInfo,
+    rows_read: u64,
+    state: State,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum State {
+    Decoding,
+    Finished,
+    Failed,
+}
+
+impl<R: Read> StreamDecoder<R> {
+    /// Reads the header, the first rows are read on [next_row](StreamDecoder::next_row).
+    pub fn new(reader: R) -> Result<Self> {
+        Self::with_transforms(reader, Transforms::new())
+    }
+
+    /// Reads the header and applies `transforms`, [info](StreamDecoder::info) describes transformed rows.
+    pub fn with_transforms(reader: R, transforms: Transforms) -> Result<Self> {
+        Self::with_options(reader, transforms, ReadOptions::new())
+    }
+
+    /// Reads the header with `options` and applies `transforms`.
+    pub fn with_options(reader: R, transforms: Transforms, options: ReadOptions) -> Result<Self> {
+        let mut source = Box::new(IoState::new(reader));
+
+        let mut png = ReadStruct::new()?;
+        png.set_read_source(&mut source);
+        options.apply(&mut png)?;
+
+        let png_ptr = png.png_ptr;
+        let info_ptr = png.info_ptr;
+
+        png.call(|| unsafe { png_read_info(png_ptr, info_ptr) })
+            .map_err(|error| source.map_error(error))?;
+
+        if let Some(limits) = &png.limits {
+            unsafe { limits.check_header(png_ptr, info_ptr)? };
+        }
+
+        unsafe { transforms.validate_for(png_ptr, info_ptr)? };
+        png.quantize_palette = transforms.quantize_palette();
+
+        let transforms = &transforms;
+        let quantize_palette = png.quantize_palette.as_ref();
+        png.call(|| unsafe {
+            transforms.apply(png_ptr, quantize_palette);
+            png_set_interlace_handling(png_ptr);
+            png_read_update_info(png_ptr, info_ptr);
+        })
+        .map_err(|error| source.map_error(error))?;
+
+        let info = unsafe { StreamInfo::from_png(&png)? };
+
+        if let Some(limits) = &png.limits {
+            limits.check_decoded_bytes(info.row_bytes, info.height)?;
+        }
+
+        Ok(Self {
+            png,
+            source,
+            info,
+            rows_read: 0,
+            state: State::Decoding,
+        })
+    }
+
+    pub fn info(&self) -> &StreamInfo {
+        &self.info
+    }
+
+    /// Color chunks, read before image data.
+    pub fn color_info(&self) -> ColorInfo {
+        unsafe { ColorInfo::from_png(self.png.png_ptr, self.png.info_ptr) }
+    }
+
+    /// Physical chunks, read before image data.
+    pub fn physical_info(&self) -> PhysicalInfo {
+        unsafe { PhysicalInfo::from_png(self.png.png_ptr, self.png.info_ptr) }
+    }
+
+    /// Palette and pixel chunks, read before image data.
+    pub fn pixel_info(&self) -> PixelInfo {
+        unsafe { PixelInfo::from_png(self.png.png_ptr, self.png.info_ptr) }
+    }
+
+    /// Time of tIME chunk, read before image data or after it when [next_row](StreamDecoder::next_row)
+    /// returns `None`.
+    pub fn modification_time(&self) -> Option<ModificationTime> {
+        unsafe { ModificationTime::from_png(self.png.png_ptr, self.png.info_ptr) }
+    }
+
+    /// Raw EXIF data of eXIf chunk, read before image data. Parsed by [ExifInfo::parse](crate::ExifInfo::parse).
+    pub fn exif(&self) -> Option<Vec<u8>> {
+        unsafe { get_exif(self.png.png_ptr, self.png.info_ptr) }
+    }
+
+    /// Warnings of **libpng** so far, see [Strictness](crate::Strictness).
+    pub fn warnings(&self) -> Vec<Warning> {
+        self.png.warnings()
+    }
+
+    /// Unknown chunks kept by [ReadOptions] so far, those after image data are added
+    /// when [next_row](StreamDecoder::next_row) returns `None`.
+    pub fn unknown_chunks(&self) -> Vec<UnknownChunk> {
+        unsafe { get_unknown_chunks(self.png.png_ptr, self.png.info_ptr) }
+    }
+
+    /// Text chunks read so far, those after image data are added when [next_row](StreamDecoder::next_row)
+    /// returns `None`.
+    pub fn text(&self) -> Vec<TextChunk> {
+        unsafe { get_text(self.png.png_ptr, self.png.info_ptr) }
+    }
+
+    /// Position of the row decoded by the next [next_row](StreamDecoder::next_row) call,
+    /// `None` if all rows are decoded. Used to pass the matching row buffer for interlaced images.
+    pub fn next_position(&self) -> Option<RowPosition> {
+        if self.state != State::Decoding {
+            return None;
+        }
+
+        (self.rows_read..self.total_rows())
+            .map(|index| self.position(index))
+            .find(|position| self.in_pass(*position))
+    }
+
+    /// Decodes the next row into `row`, which must be at least [row_bytes](StreamInfo::row_bytes) long.
+    /// Returns `None` after the last row, when trailing chunks are read as well.
+    pub fn next_row(&mut self, row: &mut [u8]) -> Result<Option<RowPosition>> {
+        if row.len() < self.info.row_bytes {
+            return Err(Error::InvalidInput(format!(
+                "Row buffer is too short, expected at least {} bytes, got {}",
+                self.info.row_bytes,
+                row.len()
+            )));
+        }
+
+        let png_ptr = self.png.png_ptr;
+        let info_ptr = self.png.info_ptr;
+
+        loop {
+            match self.state {
+                State::Decoding => {}
+                State::Finished => return Ok(None),
+                State::Failed => {
+                    return Err(Error::Png(String::from(
+                        "Decoding failed earlier and cannot be continued",
+                    )))
+                }
+            }
+
+            if self.rows_read == self.total_rows() {
+                self.call(|| unsafe { png_read_end(png_ptr, info_ptr) })?;
+
+                if let Some(limits) = &self.png.limits {
+                    if let Err(error) = unsafe { limits.check_metadata(png_ptr, info_ptr) } {
+                        self.state = State::Failed;
+                        return Err(error);
+                    }
+                }
+                self.state = State::Finished;
+
+                return Ok(None);
+            }
+
+            let position = RowPosition {
+                pass: u32::from(unsafe { png_get_current_pass_number(png_ptr) }),
+                ..self.position(self.rows_read)
+            };
+            let row_ptr = row.as_mut_ptr();
+
+            // With interlace handling libpng expects a call for every image row in every pass,
+            // rows without pixels of the current pass are left intact
+            self.call(|| unsafe { png_read_row(png_ptr, row_ptr, null_mut()) })?;
+            self.rows_read += 1;
+
+            if self.in_pass(position) {
+                return Ok(Some(position));
+            }
+        }
+    }
+
+    /// Returns the underlying reader, positioned after the last read chunk.
+    pub fn into_inner(self) -> R {
+        let Self { png, source, .. } = self;
+        drop(png);
+
+        source.inner
+    }
+
+    fn total_rows(&self) -> u64 {
+        u64::from(self.info.passes) * u64::from(self.info.height)
+    }
+
+    /// Position of `png_read_row` call with the given index, libpng passes every row of the image for every pass.
+    fn position(&self, index: u64) -> RowPosition {
+        let height = u64::from(self.info.height);
+
+        RowPosition {
+            pass: (index / height) as u32,
+            y: (index % height) as u32,
+        }
+    }
+
+    fn in_pass(&self, position: RowPosition) -> bool {
+        !self.info.interlaced
+            || (PNG_ROW_IN_INTERLACE_PASS(position.y, position.pass)
+                && PNG_PASS_COLS(self.info.width, position.pass) > 0)
+    }
+
+    fn call<F: FnOnce() + Copy>(&mut self, f: F) -> Result<()> {
+        self.png.call(f).map_err(|error| {
+            self.state = State::Failed;
+            self.source.map_error(error)
+        })
+    }
+}
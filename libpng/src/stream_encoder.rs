@@ -0,0 +1,341 @@
+use std::io::Write;
+
+use libc::c_int;
+use libpng_vendored_sys::{
+    png_get_rowbytes, png_set_IHDR, png_set_eXIf_1, png_set_flush, png_set_text, png_write_end,
+    png_write_flush, png_write_info, png_write_row, PNG_COMPRESSION_TYPE_DEFAULT,
+    PNG_FILTER_TYPE_DEFAULT, PNG_INTERLACE_NONE,
+};
+
+use crate::{
+    chunks::PngUnknownChunks,
+    exif::validate_exif,
+    png_struct::{IoState, WriteStruct},
+    text::PngTexts,
+    ColorInfo, ColorType, Error, ModificationTime, PhysicalInfo, PixelInfo, Result, TextChunk,
+    UnknownChunk,
+};
+
+/// Header of a streamed image, written before the first row.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StreamHeader {
+    pub width: u32,
+    pub height: u32,
+    /// Bits per sample: 1, 2, 4, 8 or 16 depending on color type.
+    pub bit_depth: u8,
+    pub color_type: ColorType,
+    pub color_info: ColorInfo,
+    pub physical_info: PhysicalInfo,
+    /// Palette and pixel chunks, a palette is required for [ColorType::Palette].
+    pub pixel_info: PixelInfo,
+    /// Time of the last modification, written as tIME chunk.
+    pub modification_time: Option<ModificationTime>,
+    /// Raw EXIF data in TIFF format, starting with `II` or `MM`.
+    pub exif: Option<Vec<u8>>,
+    /// Text chunks written before image data.
+    pub text: Vec<TextChunk>,
+    /// Chunks written as is at their locations, even if unsafe to copy.
+    pub unknown_chunks: Vec<UnknownChunk>,
+}
+
+impl StreamHeader {
+    /// Header with 8-bit samples.
+    pub fn new(width: u32, height: u32, color_type: ColorType) -> Self {
+        Self {
+            width,
+            height,
+            bit_depth: 8,
+            color_type,
+            color_info: ColorInfo::default(),
+            physical_info: PhysicalInfo::default(),
+            pixel_info: PixelInfo::default(),
+            modification_time: None,
+            exif: None,
+            text: Vec::new(),
+            unknown_chunks: Vec::new(),
+        }
+    }
+
+    pub fn with_bit_depth(mut self, bit_depth: u8) -> Self {
+        self.bit_depth = bit_depth;
+        self
+    }
+
+    pub fn with_color_info(mut self, color_info: ColorInfo) -> Self {
+        self.color_info = color_info;
+        self
+    }
+
+    pub fn with_physical_info(mut self, physical_info: PhysicalInfo) -> Self {
+        self.physical_info = physical_info;
+        self
+    }
+
+    pub fn with_pixel_info(mut self, pixel_info: PixelInfo) -> Self {
+        self.pixel_info = pixel_info;
+        self
+    }
+
+    pub fn with_modification_time(mut self, modification_time: ModificationTime) -> Self {
+        self.modification_time = Some(modification_time);
+        self
+    }
+
+    pub fn with_exif(mut self, exif: Vec<u8>) -> Self {
+        self.exif = Some(exif);
+        self
+    }
+
+    pub fn with_text(mut self, chunk: TextChunk) -> Self {
+        self.text.push(chunk);
+        self
+    }
+
+    pub fn with_unknown_chunk(mut self, chunk: UnknownChunk) -> Self {
+        self.unknown_chunks.push(chunk);
+        self
+    }
+}
+
+/// Encodes a non-interlaced PNG image row by row to any [Write] implementation.
+///
+/// Rows are in the encoded format: samples of less than 8 bits are packed, 16-bit samples are big-endian.
+/// ```no_run
+/// use std::fs::File;
+///
+/// use libpng::{ColorType, StreamEncoder, StreamHeader};
+///
+/// let header = StreamHeader::new(1024, 768, ColorType::Rgb);
+/// let mut encoder = StreamEncoder::new(File::create("image.png")?, header)?;
+///
+/// let row = vec![0; encoder.row_bytes()];
+/// for _ in 0..768 {
+///     encoder.write_row(&row)?;
+/// }
+///
+/// encoder.finish()?;
+/// # Ok::<(), libpng::Error>(())
+/// ```
+pub struct StreamEncoder<W: Write> {
+    // Dropped before the sink it writes to
+    png: WriteStruct,
+    sink: Box<IoState<W>>,
+    header: StreamHeader,
+    row_bytes: usize,
+    rows_written: u32,
+    // Mirrors libpng counter, flushing twice without new rows is a zlib buffer error
+    flush_interval: u32,
+    rows_since_flush: u32,
+    failed: bool,
+}
+
+impl<W: Write> StreamEncoder<W> {
+    /// Validates and writes the header.
+    pub fn new(writer: W, header: StreamHeader) -> Result<Self> {
+        let mut sink = Box::new(IoState::new(writer));
+
+        let mut png = WriteStruct::new()?;
+        png.set_write_sink(&mut sink);
+
+        let png_ptr = png.png_ptr;
+        let info_ptr = png.info_ptr;
+        let StreamHeader {
+            width,
+            height,
+            bit_depth,
+            color_type,
+            ..
+        } = header;
+
+        let color_info = &header.color_info;
+        color_info.validate()?;
+        let profile_name = &*color_info.profile_name();
+
+        let physical_info = &header.physical_info;
+        physical_info.validate()?;
+        let calibration = physical_info.calibration.as_ref().map(|c| c.to_png());
+        let calibration = calibration.as_ref();
+
+        header.pixel_info.validate(color_type, bit_depth)?;
+        let pixel_info = header.pixel_info.to_png();
+
+        let modification_time = header.modification_time;
+        if let Some(modification_time) = &modification_time {
+            modification_time.validate()?;
+        }
+
+        if let Some(exif) = &header.exif {
+            validate_exif(exif)?;
+        }
+        let exif = header.exif.as_deref();
+
+        let texts = PngTexts::new(&header.text)?;
+        let (texts_ptr, texts_len) = (texts.as_ptr(), texts.len());
+
+        let unknown_chunks = &PngUnknownChunks::new(&header.unknown_chunks)?;
+
+        png.call(|| unsafe {
+            png_set_IHDR(
+                png_ptr,
+                info_ptr,
+                width,
+                height,
+                c_int::from(bit_depth),
+                c_int::from(color_type.to_png()),
+                PNG_INTERLACE_NONE as c_int,
+                PNG_COMPRESSION_TYPE_DEFAULT as c_int,
+                PNG_FILTER_TYPE_DEFAULT as c_int,
+            );
+            color_info.set(png_ptr, info_ptr, profile_name);
+            physical_info.set(png_ptr, info_ptr, calibration);
+            pixel_info.set(png_ptr, info_ptr);
+            if let Some(modification_time) = &modification_time {
+                modification_time.set(png_ptr, info_ptr);
+            }
+            if let Some(exif) = exif {
+                // Copied by libpng, the pointer is not written through
+                png_set_eXIf_1(
+                    png_ptr,
+                    info_ptr,
+                    exif.len() as u32,
+                    exif.as_ptr() as *mut u8,
+                );
+            }
+            png_set_text(png_ptr, info_ptr, texts_ptr, texts_len);
+            unknown_chunks.set(png_ptr, info_ptr);
+            png_write_info(png_ptr, info_ptr);
+        })
+        .map_err(|error| sink.map_error(error))?;
+
+        let row_bytes = unsafe { png_get_rowbytes(png_ptr, info_ptr) };
+
+        Ok(Self {
+            png,
+            sink,
+            header,
+            row_bytes,
+            rows_written: 0,
+            flush_interval: 0,
+            rows_since_flush: 0,
+            failed: false,
+        })
+    }
+
+    pub fn header(&self) -> &StreamHeader {
+        &self.header
+    }
+
+    /// Exact size of a row in bytes.
+    pub fn row_bytes(&self) -> usize {
+        self.row_bytes
+    }
+
+    /// Flushes compressed data to the writer every `rows` rows, 0 disables periodic flushing (the default).
+    /// Frequent flushing degrades compression.
+    pub fn set_flush_interval(&mut self, rows: u32) {
+        let png_ptr = self.png.png_ptr;
+        let rows = c_int::try_from(rows).unwrap_or(c_int::MAX);
+
+        unsafe { png_set_flush(png_ptr, rows) };
+
+        self.flush_interval = rows as u32;
+    }
+
+    /// Writes the next row, which must be exactly [row_bytes](StreamEncoder::row_bytes) long.
+    pub fn write_row(&mut self, row: &[u8]) -> Result<()> {
+        if row.len() != self.row_bytes {
+            return Err(Error::InvalidInput(format!(
+                "Row must be {} bytes long, got {}",
+                self.row_bytes,
+                row.len()
+            )));
+        }
+
+        if self.rows_written == self.header.height {
+            return Err(Error::InvalidInput(format!(
+                "All {} rows are already written",
+                self.header.height
+            )));
+        }
+
+        let png_ptr = self.png.png_ptr;
+        let row_ptr = row.as_ptr();
+
+        self.call(|| unsafe { png_write_row(png_ptr, row_ptr) })?;
+        self.rows_written += 1;
+        self.rows_since_flush += 1;
+
+        if self.flush_interval > 0 && self.rows_since_flush >= self.flush_interval {
+            self.rows_since_flush = 0;
+        }
+
+        Ok(())
+    }
+
+    /// Flushes compressed data of the written rows and the writer itself.
+    pub fn flush(&mut self) -> Result<()> {
+        if self.rows_since_flush == 0 {
+            return Ok(self.sink.inner.flush()?);
+        }
+
+        let png_ptr = self.png.png_ptr;
+
+        self.call(|| unsafe { png_write_flush(png_ptr) })?;
+        self.rows_since_flush = 0;
+
+        Ok(())
+    }
+
+    /// Adds a text chunk, written after image data by [finish](StreamEncoder::finish).
+    /// Text known in advance should be passed via [StreamHeader::with_text] instead.
+    pub fn add_text(&mut self, chunk: TextChunk) -> Result<()> {
+        let texts = PngTexts::new(&[chunk])?;
+        let (texts_ptr, texts_len) = (texts.as_ptr(), texts.len());
+        let png_ptr = self.png.png_ptr;
+        let info_ptr = self.png.info_ptr;
+
+        self.call(|| unsafe { png_set_text(png_ptr, info_ptr, texts_ptr, texts_len) })
+    }
+
+    /// Writer with the data written so far, [write_row](StreamEncoder::write_row) may keep some data compressed only.
+    #[cfg(any(feature = "tokio", feature = "futures-io"))]
+    pub(crate) fn writer_mut(&mut self) -> &mut W {
+        &mut self.sink.inner
+    }
+
+    /// Writes the end of the image after all rows and returns the flushed writer.
+    pub fn finish(mut self) -> Result<W> {
+        if self.rows_written != self.header.height {
+            return Err(Error::InvalidInput(format!(
+                "Only {} of {} rows are written",
+                self.rows_written, self.header.height
+            )));
+        }
+
+        let png_ptr = self.png.png_ptr;
+        let info_ptr = self.png.info_ptr;
+
+        self.call(|| unsafe { png_write_end(png_ptr, info_ptr) })?;
+
+        let Self { png, sink, .. } = self;
+        drop(png);
+
+        let mut writer = sink.inner;
+        writer.flush()?;
+
+        Ok(writer)
+    }
+
+    fn call<F: FnOnce() + Copy>(&mut self, f: F) -> Result<()> {
+        if self.failed {
+            return Err(Error::Png(String::from(
+                "Encoding failed earlier and cannot be continued",
+            )));
+        }
+
+        self.png.call(f).map_err(|error| {
+            self.failed = true;
+            self.sink.map_error(error)
+        })
+    }
+}
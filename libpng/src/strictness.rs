@@ -0,0 +1,79 @@
+use libc::c_int;
+use libpng_vendored_sys::{
+    png_set_benign_errors, png_set_crc_action, png_set_option, png_structp,
+    PNG_SKIP_sRGB_CHECK_PROFILE, PNG_CRC_DEFAULT, PNG_CRC_ERROR_QUIT, PNG_CRC_WARN_USE,
+    PNG_OPTION_ON,
+};
+
+use crate::ChunkName;
+
+/// `PNG_IGNORE_ADLER32` option of **libpng**, not exported by the bindings.
+const IGNORE_ADLER32: c_int = 8;
+
+/// Handling of damaged or non-conforming input by [ReadOptions](crate::ReadOptions).
+///
+/// Whatever **libpng** accepts is reported as a [Warning] by `warnings` of decoders.
+/// [Decoder](crate::Decoder) uses the defaults of **libpng**, like [Strictness::Lenient].
+/// ```
+/// use libpng::{ReadOptions, Strictness};
+///
+/// let options = ReadOptions::new().with_strictness(Strictness::Recover);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Strictness {
+    /// Fails on CRC errors of any chunk and on benign errors, like invalid ancillary chunks.
+    Strict,
+    /// Defaults of **libpng**: fails on CRC errors of critical chunks, discards ancillary chunks
+    /// with CRC errors and warns about benign errors.
+    #[default]
+    Lenient,
+    /// Uses critical chunks with CRC errors, ignores Adler-32 checksums of compressed data
+    /// and skips checks of ICC profiles against sRGB, for recovery of damaged images.
+    /// **libpng** 1.6 discards ancillary chunks with CRC errors anyway.
+    Recover,
+}
+
+impl Strictness {
+    /// Sets CRC actions and options of **libpng**, must be run via `png_rs_try` before any data is read.
+    pub(crate) unsafe fn set(self, png_ptr: png_structp) {
+        match self {
+            Self::Strict => {
+                png_set_crc_action(
+                    png_ptr,
+                    PNG_CRC_ERROR_QUIT as c_int,
+                    PNG_CRC_ERROR_QUIT as c_int,
+                );
+                png_set_benign_errors(png_ptr, 0);
+            }
+            Self::Lenient => {
+                png_set_crc_action(png_ptr, PNG_CRC_DEFAULT as c_int, PNG_CRC_DEFAULT as c_int);
+                png_set_benign_errors(png_ptr, 1);
+            }
+            Self::Recover => {
+                png_set_crc_action(
+                    png_ptr,
+                    PNG_CRC_WARN_USE as c_int,
+                    PNG_CRC_WARN_USE as c_int,
+                );
+                png_set_benign_errors(png_ptr, 1);
+                // Options unsupported by the build are left unset
+                png_set_option(png_ptr, IGNORE_ADLER32, PNG_OPTION_ON as c_int);
+                png_set_option(
+                    png_ptr,
+                    PNG_SKIP_sRGB_CHECK_PROFILE as c_int,
+                    PNG_OPTION_ON as c_int,
+                );
+            }
+        }
+    }
+}
+
+/// Warning of **libpng** while decoding. Messages about chunks start with the chunk name,
+/// `chunk` is the chunk being read, if any.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Warning {
+    pub message: String,
+    pub chunk: Option<ChunkName>,
+    /// Row being decoded in IDAT chunks, counted within the pass for interlaced images.
+    pub row: Option<u32>,
+}
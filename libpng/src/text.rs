@@ -0,0 +1,305 @@
+use std::{
+    ffi::{CStr, CString},
+    ptr::null_mut,
+};
+
+use libc::c_int;
+use libpng_vendored_sys::{
+    png_charp, png_get_text, png_infop, png_structp, png_text, png_textp,
+    PNG_ITXT_COMPRESSION_zTXt, PNG_TEXT_COMPRESSION_zTXt, PNG_ITXT_COMPRESSION_NONE,
+    PNG_KEYWORD_MAX_LENGTH, PNG_TEXT_COMPRESSION_NONE,
+};
+
+use crate::{Error, Result};
+
+/// Texts of at least this size in bytes are compressed by [TextChunk::new].
+pub const TEXT_COMPRESSION_THRESHOLD: usize = 1024;
+
+/// Text metadata of a PNG image, one of tEXt, zTXt and iTXt chunks.
+///
+/// Keywords are Latin-1, from 1 to 79 characters, without leading, trailing or consecutive spaces,
+/// e.g. `Title`, `Author`, `Software`. Texts cannot contain NUL characters.
+/// ```
+/// use libpng::TextChunk;
+///
+/// let chunk = TextChunk::new("Comment", "Café ☕");
+/// assert!(matches!(chunk, TextChunk::International { .. }));
+/// assert!(chunk.validate().is_ok());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TextChunk {
+    /// tEXt, uncompressed Latin-1 text.
+    Text { keyword: String, text: String },
+    /// zTXt, compressed Latin-1 text.
+    CompressedText { keyword: String, text: String },
+    /// iTXt, UTF-8 text with a language tag like `en-US` and the keyword translated to that language,
+    /// both may be empty.
+    International {
+        keyword: String,
+        language: String,
+        translated_keyword: String,
+        text: String,
+        compressed: bool,
+    },
+}
+
+impl TextChunk {
+    /// Chooses the chunk by the text: tEXt or zTXt for Latin-1, iTXt otherwise,
+    /// compressed from [TEXT_COMPRESSION_THRESHOLD] bytes.
+    pub fn new(keyword: impl Into<String>, text: impl Into<String>) -> Self {
+        let keyword = keyword.into();
+        let text = text.into();
+        let compressed = text.len() >= TEXT_COMPRESSION_THRESHOLD;
+
+        match (is_latin1(&text), compressed) {
+            (true, false) => Self::Text { keyword, text },
+            (true, true) => Self::CompressedText { keyword, text },
+            (false, compressed) => Self::International {
+                keyword,
+                language: String::new(),
+                translated_keyword: String::new(),
+                text,
+                compressed,
+            },
+        }
+    }
+
+    pub fn keyword(&self) -> &str {
+        match self {
+            Self::Text { keyword, .. }
+            | Self::CompressedText { keyword, .. }
+            | Self::International { keyword, .. } => keyword,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        match self {
+            Self::Text { text, .. }
+            | Self::CompressedText { text, .. }
+            | Self::International { text, .. } => text,
+        }
+    }
+
+    /// Checks the keyword, the text encoding and the language tag, which is done by encoders as well.
+    pub fn validate(&self) -> Result<()> {
+        validate_keyword(self.keyword())?;
+
+        match self {
+            Self::Text { text, .. } | Self::CompressedText { text, .. } => {
+                if !is_latin1(text) {
+                    return Err(Error::InvalidInput(String::from(
+                        "tEXt and zTXt text must be Latin-1, use iTXt for other characters",
+                    )));
+                }
+
+                validate_no_nul("Text", text)
+            }
+            Self::International {
+                language,
+                translated_keyword,
+                text,
+                ..
+            } => {
+                if !language
+                    .split('-')
+                    .all(|part| part.len() <= 8 && part.bytes().all(|b| b.is_ascii_alphanumeric()))
+                {
+                    return Err(Error::InvalidInput(format!(
+                        "Language tag must consist of alphanumeric parts separated by hyphens, got {language:?}"
+                    )));
+                }
+
+                validate_no_nul("Translated keyword", translated_keyword)?;
+                validate_no_nul("Text", text)
+            }
+        }
+    }
+
+    /// Converts a text read by **libpng**, tEXt and zTXt are decoded from Latin-1, iTXt from UTF-8.
+    unsafe fn from_png(text: &png_text) -> Self {
+        let keyword = latin1_to_string(text.key);
+        let value = |ptr: png_charp| {
+            if ptr.is_null() {
+                String::new()
+            } else {
+                CStr::from_ptr(ptr).to_string_lossy().into_owned()
+            }
+        };
+
+        match text.compression {
+            PNG_TEXT_COMPRESSION_NONE => Self::Text {
+                keyword,
+                text: latin1_to_string(text.text),
+            },
+            compression if compression == PNG_TEXT_COMPRESSION_zTXt as c_int => {
+                Self::CompressedText {
+                    keyword,
+                    text: latin1_to_string(text.text),
+                }
+            }
+            compression => Self::International {
+                keyword,
+                language: value(text.lang),
+                translated_keyword: value(text.lang_key),
+                text: value(text.text),
+                compressed: compression == PNG_ITXT_COMPRESSION_zTXt as c_int,
+            },
+        }
+    }
+}
+
+/// Validated text chunks as C strings for `png_set_text`, which copies them.
+pub(crate) struct PngTexts {
+    // Keeps the strings pointed to by texts
+    _strings: Vec<CString>,
+    texts: Vec<png_text>,
+}
+
+impl PngTexts {
+    pub(crate) fn new(chunks: &[TextChunk]) -> Result<Self> {
+        let mut strings = Vec::new();
+        let mut texts = Vec::with_capacity(chunks.len());
+
+        // NUL characters are rejected by validation
+        let mut c_string = |bytes: Vec<u8>| {
+            let string = CString::new(bytes).expect("validated text");
+            // Moving CString keeps its heap buffer in place
+            let ptr = string.as_ptr() as png_charp;
+            strings.push(string);
+            ptr
+        };
+
+        for chunk in chunks {
+            chunk.validate()?;
+
+            let key = c_string(string_to_latin1(chunk.keyword()));
+            let text = match chunk {
+                TextChunk::Text { text, .. } => png_text {
+                    compression: PNG_TEXT_COMPRESSION_NONE,
+                    key,
+                    text: c_string(string_to_latin1(text)),
+                    text_length: 0,
+                    itxt_length: 0,
+                    lang: null_mut(),
+                    lang_key: null_mut(),
+                },
+                TextChunk::CompressedText { text, .. } => png_text {
+                    compression: PNG_TEXT_COMPRESSION_zTXt as c_int,
+                    key,
+                    text: c_string(string_to_latin1(text)),
+                    text_length: 0,
+                    itxt_length: 0,
+                    lang: null_mut(),
+                    lang_key: null_mut(),
+                },
+                TextChunk::International {
+                    language,
+                    translated_keyword,
+                    text,
+                    compressed,
+                    ..
+                } => png_text {
+                    compression: if *compressed {
+                        PNG_ITXT_COMPRESSION_zTXt
+                    } else {
+                        PNG_ITXT_COMPRESSION_NONE
+                    } as c_int,
+                    key,
+                    text: c_string(text.clone().into_bytes()),
+                    text_length: 0,
+                    itxt_length: 0,
+                    lang: c_string(language.clone().into_bytes()),
+                    lang_key: c_string(translated_keyword.clone().into_bytes()),
+                },
+            };
+
+            texts.push(text);
+        }
+
+        Ok(Self {
+            _strings: strings,
+            texts,
+        })
+    }
+
+    pub(crate) fn as_ptr(&self) -> *const png_text {
+        self.texts.as_ptr()
+    }
+
+    pub(crate) fn len(&self) -> c_int {
+        self.texts.len() as c_int
+    }
+}
+
+/// Text chunks read into `info_ptr` so far.
+pub(crate) unsafe fn get_text(png_ptr: png_structp, info_ptr: png_infop) -> Vec<TextChunk> {
+    let mut texts: png_textp = null_mut();
+    let mut count: c_int = 0;
+
+    png_get_text(png_ptr, info_ptr, &mut texts, &mut count);
+
+    if texts.is_null() || count <= 0 {
+        return Vec::new();
+    }
+
+    std::slice::from_raw_parts(texts, count as usize)
+        .iter()
+        .map(|text| TextChunk::from_png(text))
+        .collect()
+}
+
+pub(crate) fn validate_keyword(keyword: &str) -> Result<()> {
+    let length = keyword.chars().count();
+    if length == 0 || length > PNG_KEYWORD_MAX_LENGTH as usize {
+        return Err(Error::InvalidInput(format!(
+            "Keyword must be from 1 to {PNG_KEYWORD_MAX_LENGTH} characters long, got {length}"
+        )));
+    }
+
+    if let Some(c) = keyword
+        .chars()
+        .find(|c| !matches!(u32::from(*c), 32..=126 | 161..=255))
+    {
+        return Err(Error::InvalidInput(format!(
+            "Keyword must consist of printable Latin-1 characters, got {c:?}"
+        )));
+    }
+
+    if keyword.starts_with(' ') || keyword.ends_with(' ') || keyword.contains("  ") {
+        return Err(Error::InvalidInput(format!(
+            "Keyword cannot have leading, trailing or consecutive spaces, got {keyword:?}"
+        )));
+    }
+
+    Ok(())
+}
+
+fn validate_no_nul(name: &str, value: &str) -> Result<()> {
+    if value.contains('\0') {
+        return Err(Error::InvalidInput(format!(
+            "{name} cannot contain NUL characters"
+        )));
+    }
+
+    Ok(())
+}
+
+fn is_latin1(value: &str) -> bool {
+    value.chars().all(|c| u32::from(c) <= 0xff)
+}
+
+fn string_to_latin1(value: &str) -> Vec<u8> {
+    value.chars().map(|c| u32::from(c) as u8).collect()
+}
+
+pub(crate) unsafe fn latin1_to_string(ptr: png_charp) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+
+    CStr::from_ptr(ptr)
+        .to_bytes()
+        .iter()
+        .map(|&b| char::from(b))
+        .collect()
+}
@@ -0,0 +1,626 @@
+use std::{ptr::null, slice};
+
+use libc::c_int;
+use libpng_vendored_sys::{
+    png_color, png_color_16, png_colorp, png_get_bit_depth, png_get_color_type, png_infop,
+    png_set_add_alpha, png_set_alpha_mode, png_set_background, png_set_bgr, png_set_expand,
+    png_set_expand_16, png_set_expand_gray_1_2_4_to_8, png_set_filler, png_set_gamma,
+    png_set_gray_to_rgb, png_set_invert_alpha, png_set_invert_mono, png_set_packing,
+    png_set_packswap, png_set_palette_to_rgb, png_set_quantize, png_set_rgb_to_gray,
+    png_set_scale_16, png_set_strip_16, png_set_strip_alpha, png_set_swap, png_set_swap_alpha,
+    png_set_tRNS_to_alpha, png_structp, PNG_DEFAULT_sRGB, PNG_ALPHA_BROKEN, PNG_ALPHA_OPTIMIZED,
+    PNG_ALPHA_PNG, PNG_ALPHA_STANDARD, PNG_BACKGROUND_GAMMA_FILE, PNG_BACKGROUND_GAMMA_SCREEN,
+    PNG_BACKGROUND_GAMMA_UNIQUE, PNG_ERROR_ACTION_NONE, PNG_FILLER_AFTER, PNG_FILLER_BEFORE,
+    PNG_GAMMA_MAC_18,
+};
+
+use crate::{Color, ColorType, Error, Result};
+
+/// Gamma accepted by **libpng** floating point API, from 0.01 to 100.
+const GAMMA_RANGE: std::ops::RangeInclusive<f64> = 0.01..=100.0;
+
+/// Screen or file gamma of [Transforms].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Gamma {
+    /// sRGB transfer function, about 2.2 for the screen or 1/2.2 for the file.
+    Srgb,
+    /// Gamma 1.8 of old Mac displays.
+    Mac18,
+    Linear,
+    /// Screen exponent like 2.2, or file gamma like 0.45455, from 0.01 to 100.
+    Value(f64),
+}
+
+impl Gamma {
+    fn to_png(self) -> f64 {
+        match self {
+            Gamma::Srgb => f64::from(PNG_DEFAULT_sRGB),
+            Gamma::Mac18 => f64::from(PNG_GAMMA_MAC_18),
+            Gamma::Linear => 1.0,
+            Gamma::Value(value) => value,
+        }
+    }
+
+    fn validate(self) -> Result<()> {
+        match self {
+            Gamma::Value(value) if !GAMMA_RANGE.contains(&value) => Err(Error::InvalidInput(
+                format!("Gamma must be from 0.01 to 100, got {value}"),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Alpha encoding of `png_set_alpha_mode`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlphaMode {
+    /// Not premultiplied, the default PNG encoding.
+    Png,
+    /// Premultiplied, components are linear.
+    Standard,
+    /// Premultiplied, opaque pixels are gamma encoded, others are linear.
+    Optimized,
+    /// Premultiplied, components are gamma encoded.
+    Broken,
+}
+
+impl AlphaMode {
+    fn to_png(self) -> c_int {
+        (match self {
+            AlphaMode::Png => PNG_ALPHA_PNG,
+            AlphaMode::Standard => PNG_ALPHA_STANDARD,
+            AlphaMode::Optimized => PNG_ALPHA_OPTIMIZED,
+            AlphaMode::Broken => PNG_ALPHA_BROKEN,
+        }) as c_int
+    }
+}
+
+/// Position of the channel added by [Transforms::filler] and [Transforms::add_alpha].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FillerPosition {
+    Before,
+    After,
+}
+
+/// Color composed under transparent pixels by [Transforms::background], in the output bit depth.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackgroundColor {
+    Gray(u16),
+    Rgb { red: u16, green: u16, blue: u16 },
+}
+
+/// Gamma of [BackgroundColor] values.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BackgroundGamma {
+    /// Values are encoded for the screen.
+    Screen,
+    /// Values are encoded like the file.
+    File,
+    /// Values are encoded with their own gamma, from 0.01 to 100.
+    Unique(f64),
+}
+
+/// Palette of [Transforms::quantize] owned by the read struct, since **libpng** keeps a pointer to it
+/// and reduces it in place to the maximum number of colors.
+pub(crate) struct QuantizePalette {
+    // Raw, since libpng modifies it
+    colors: *mut [png_color],
+    maximum_colors: usize,
+}
+
+impl QuantizePalette {
+    pub(crate) fn as_mut_ptr(&self) -> png_colorp {
+        self.colors.cast()
+    }
+
+    /// Colors indexed by quantized rows, after [Transforms::apply].
+    pub(crate) fn reduced(&self) -> Vec<Color> {
+        // libpng keeps the first entries when reducing
+        let colors = unsafe { slice::from_raw_parts(self.as_mut_ptr(), self.colors.len()) };
+
+        colors
+            .iter()
+            .take(self.maximum_colors)
+            .map(|color| Color::new(color.red, color.green, color.blue))
+            .collect()
+    }
+}
+
+impl Drop for QuantizePalette {
+    fn drop(&mut self) {
+        drop(unsafe { Box::from_raw(self.colors) });
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Filler {
+    value: u16,
+    position: FillerPosition,
+    alpha: bool,
+}
+
+/// Typed set of **libpng** read transforms for [StreamDecoder](crate::StreamDecoder)
+/// and [ProgressiveDecoder](crate::ProgressiveDecoder).
+///
+/// Transforms are validated and applied in the order recommended by **libpng**, whatever the order of calls.
+/// The resulting color type, bit depth, channels and row size are reported by [StreamInfo](crate::StreamInfo).
+/// ```
+/// use libpng::Transforms;
+///
+/// // 8-bit RGBA output for any input
+/// let transforms = Transforms::new()
+///     .expand()
+///     .scale_16()
+///     .gray_to_rgb()
+///     .add_alpha(0xff, libpng::FillerPosition::After);
+///
+/// assert!(transforms.validate().is_ok());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Transforms {
+    expand: bool,
+    palette_to_rgb: bool,
+    trns_to_alpha: bool,
+    expand_gray_1_2_4_to_8: bool,
+    expand_16: bool,
+    strip_16: bool,
+    scale_16: bool,
+    strip_alpha: bool,
+    gray_to_rgb: bool,
+    // Negative coefficients select the defaults
+    rgb_to_gray: Option<(f64, f64)>,
+    bgr: bool,
+    swap_alpha: bool,
+    invert_alpha: bool,
+    invert_mono: bool,
+    swap: bool,
+    packing: bool,
+    packswap: bool,
+    filler: Option<Filler>,
+    background: Option<(BackgroundColor, BackgroundGamma)>,
+    gamma: Option<(Gamma, Gamma)>,
+    alpha_mode: Option<(AlphaMode, Gamma)>,
+    quantize: Option<(Vec<Color>, u16)>,
+}
+
+impl Transforms {
+    /// No transforms, rows are decoded in the encoded format.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expands palette images to RGB, gray images of less than 8 bits to 8 bits and tRNS chunk to alpha.
+    pub fn expand(mut self) -> Self {
+        self.expand = true;
+        self
+    }
+
+    pub fn palette_to_rgb(mut self) -> Self {
+        self.palette_to_rgb = true;
+        self
+    }
+
+    pub fn trns_to_alpha(mut self) -> Self {
+        self.trns_to_alpha = true;
+        self
+    }
+
+    pub fn expand_gray_1_2_4_to_8(mut self) -> Self {
+        self.expand_gray_1_2_4_to_8 = true;
+        self
+    }
+
+    /// Like [expand](Transforms::expand), also expanding 8-bit samples to 16 bits.
+    pub fn expand_16(mut self) -> Self {
+        self.expand_16 = true;
+        self
+    }
+
+    /// Reduces 16-bit samples to 8 bits by dropping low bytes, less accurate than [scale_16](Transforms::scale_16).
+    pub fn strip_16(mut self) -> Self {
+        self.strip_16 = true;
+        self
+    }
+
+    /// Reduces 16-bit samples to 8 bits with rounding.
+    pub fn scale_16(mut self) -> Self {
+        self.scale_16 = true;
+        self
+    }
+
+    /// Drops alpha, leaving spurious colors in transparent pixels, see [background](Transforms::background).
+    pub fn strip_alpha(mut self) -> Self {
+        self.strip_alpha = true;
+        self
+    }
+
+    pub fn gray_to_rgb(mut self) -> Self {
+        self.gray_to_rgb = true;
+        self
+    }
+
+    /// Converts RGB to gray with the default sRGB coefficients.
+    pub fn rgb_to_gray(mut self) -> Self {
+        self.rgb_to_gray = Some((-1.0, -1.0));
+        self
+    }
+
+    /// Converts RGB to gray with the given red and green coefficients, blue one is the rest to 1.
+    pub fn rgb_to_gray_with_coefficients(mut self, red: f64, green: f64) -> Self {
+        self.rgb_to_gray = Some((red, green));
+        self
+    }
+
+    pub fn bgr(mut self) -> Self {
+        self.bgr = true;
+        self
+    }
+
+    /// Moves alpha before color components.
+    pub fn swap_alpha(mut self) -> Self {
+        self.swap_alpha = true;
+        self
+    }
+
+    /// Makes 0 opaque and the maximum value transparent.
+    pub fn invert_alpha(mut self) -> Self {
+        self.invert_alpha = true;
+        self
+    }
+
+    /// Makes 0 white for gray images.
+    pub fn invert_mono(mut self) -> Self {
+        self.invert_mono = true;
+        self
+    }
+
+    /// Makes 16-bit samples little-endian.
+    pub fn swap(mut self) -> Self {
+        self.swap = true;
+        self
+    }
+
+    /// Unpacks samples of less than 8 bits into separate bytes.
+    pub fn packing(mut self) -> Self {
+        self.packing = true;
+        self
+    }
+
+    /// Packs samples of less than 8 bits starting from the least significant bits.
+    pub fn packswap(mut self) -> Self {
+        self.packswap = true;
+        self
+    }
+
+    /// Adds a channel with `value` to images without alpha, the color type is kept.
+    pub fn filler(mut self, value: u16, position: FillerPosition) -> Self {
+        self.filler = Some(Filler {
+            value,
+            position,
+            alpha: false,
+        });
+        self
+    }
+
+    /// Adds an alpha channel with `value` to images without alpha.
+    pub fn add_alpha(mut self, value: u16, position: FillerPosition) -> Self {
+        self.filler = Some(Filler {
+            value,
+            position,
+            alpha: true,
+        });
+        self
+    }
+
+    /// Composes transparent pixels over `color` and removes alpha.
+    pub fn background(mut self, color: BackgroundColor, gamma: BackgroundGamma) -> Self {
+        self.background = Some((color, gamma));
+        self
+    }
+
+    /// Converts from `file` gamma, used if the image has no gamma information, to `screen` gamma.
+    pub fn gamma(mut self, screen: Gamma, file: Gamma) -> Self {
+        self.gamma = Some((screen, file));
+        self
+    }
+
+    /// Sets alpha encoding and `screen` gamma, which overrides [gamma](Transforms::gamma) for the screen.
+    pub fn alpha_mode(mut self, mode: AlphaMode, screen: Gamma) -> Self {
+        self.alpha_mode = Some((mode, screen));
+        self
+    }
+
+    /// Maps 8-bit RGB pixels to the closest entries of `palette`, rows become [ColorType::Palette] indices
+    /// and alpha is dropped. If `palette` has more than `maximum_colors` entries, **libpng** drops the closest ones,
+    /// [palette](crate::StreamInfo::palette) reports the remaining colors.
+    pub fn quantize(mut self, palette: Vec<Color>, maximum_colors: u16) -> Self {
+        self.quantize = Some((palette, maximum_colors));
+        self
+    }
+
+    /// Checks values and combinations of transforms, which is done by decoders as well.
+    pub fn validate(&self) -> Result<()> {
+        let conflicts = [
+            (self.strip_16 && self.scale_16, "strip_16", "scale_16"),
+            (self.expand_16 && self.strip_16, "expand_16", "strip_16"),
+            (self.expand_16 && self.scale_16, "expand_16", "scale_16"),
+            (
+                self.gray_to_rgb && self.rgb_to_gray.is_some(),
+                "gray_to_rgb",
+                "rgb_to_gray",
+            ),
+            (
+                self.strip_alpha && self.filler.is_some(),
+                "strip_alpha",
+                "filler or add_alpha",
+            ),
+            (
+                self.strip_alpha && self.background.is_some(),
+                "strip_alpha",
+                "background",
+            ),
+            (
+                self.background.is_some()
+                    && matches!(self.alpha_mode, Some((mode, _)) if mode != AlphaMode::Png),
+                "background",
+                "premultiplied alpha_mode",
+            ),
+            (
+                self.quantize.is_some() && self.rgb_to_gray.is_some(),
+                "quantize",
+                "rgb_to_gray",
+            ),
+            (
+                self.quantize.is_some() && self.filler.is_some(),
+                "quantize",
+                "filler or add_alpha",
+            ),
+        ];
+
+        if let Some((_, first, second)) = conflicts.iter().find(|(conflict, ..)| *conflict) {
+            return Err(Error::InvalidInput(format!(
+                "Transforms {first} and {second} cannot be combined"
+            )));
+        }
+
+        if let Some((red, green)) = self.rgb_to_gray {
+            let default = red < 0.0 && green < 0.0;
+            let valid = red >= 0.0 && green >= 0.0 && red + green <= 1.0;
+            if !(default || valid) {
+                return Err(Error::InvalidInput(format!(
+                    "RGB to gray coefficients must be non-negative with the sum up to 1, got {red} and {green}"
+                )));
+            }
+        }
+
+        if let Some((screen, file)) = self.gamma {
+            screen.validate()?;
+            file.validate()?;
+        }
+
+        if let Some((_, screen)) = self.alpha_mode {
+            screen.validate()?;
+        }
+
+        if let Some((_, BackgroundGamma::Unique(gamma))) = self.background {
+            Gamma::Value(gamma).validate()?;
+        }
+
+        if let Some((palette, maximum_colors)) = &self.quantize {
+            if !(1..=256).contains(&palette.len()) {
+                return Err(Error::InvalidInput(format!(
+                    "Quantize palette must have from 1 to 256 colors, got {}",
+                    palette.len()
+                )));
+            }
+            if !(1..=256).contains(maximum_colors) {
+                return Err(Error::InvalidInput(format!(
+                    "Maximum quantized colors must be from 1 to 256, got {maximum_colors}"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validates transforms against the header read by `png_read_info`.
+    pub(crate) unsafe fn validate_for(
+        &self,
+        png_ptr: png_structp,
+        info_ptr: png_infop,
+    ) -> Result<()> {
+        self.validate()?;
+
+        if self.background.is_none() && self.quantize.is_none() {
+            return Ok(());
+        }
+
+        let color_type = png_get_color_type(png_ptr, info_ptr);
+        let color_type = ColorType::from_png(color_type)
+            .ok_or_else(|| Error::Png(format!("Unknown color type: {color_type}")))?;
+        let bit_depth = png_get_bit_depth(png_ptr, info_ptr);
+
+        let expand = self.expand || self.expand_gray_1_2_4_to_8 || self.expand_16;
+        let color_input = !matches!(color_type, ColorType::Gray | ColorType::GrayAlpha);
+        let color_output = (color_input && self.rgb_to_gray.is_none()) || self.gray_to_rgb;
+        let output_16 = self.expand_16 || (bit_depth == 16 && !self.strip_16 && !self.scale_16);
+
+        if let Some((color, _)) = self.background {
+            if color_type == ColorType::Gray && bit_depth < 8 && !expand {
+                return Err(Error::InvalidInput(String::from(
+                    "Background of gray images with less than 8 bits requires expanding them",
+                )));
+            }
+
+            let (components, max) = match color {
+                BackgroundColor::Gray(gray) => (false, gray),
+                BackgroundColor::Rgb { red, green, blue } => (true, red.max(green).max(blue)),
+            };
+
+            if components != color_output {
+                return Err(Error::InvalidInput(format!(
+                    "Background color must be {} for this image",
+                    if color_output { "RGB" } else { "gray" }
+                )));
+            }
+
+            if !output_16 && max > 0xff {
+                return Err(Error::InvalidInput(format!(
+                    "Background color must be 8-bit for this image, got {max}"
+                )));
+            }
+        }
+
+        if self.quantize.is_some() {
+            if color_type == ColorType::Palette {
+                return Err(Error::InvalidInput(String::from(
+                    "Palette images cannot be quantized",
+                )));
+            }
+
+            if !color_output || output_16 || (bit_depth < 8 && !expand) {
+                return Err(Error::InvalidInput(String::from(
+                    "Quantizing requires 8-bit RGB rows, like gray_to_rgb and scale_16 give",
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Copy of the palette of [quantize](Transforms::quantize) for [apply](Transforms::apply).
+    pub(crate) fn quantize_palette(&self) -> Option<QuantizePalette> {
+        let (palette, maximum_colors) = self.quantize.as_ref()?;
+        let colors: Box<[png_color]> = palette.iter().map(|c| c.to_png_color()).collect();
+
+        Some(QuantizePalette {
+            colors: Box::into_raw(colors),
+            maximum_colors: usize::from(*maximum_colors),
+        })
+    }
+
+    /// Calls `png_set_*` functions in the order recommended by **libpng**, before `png_read_update_info`.
+    ///
+    /// Must be run via `png_rs_try` after [validate_for](Transforms::validate_for),
+    /// with `quantize_palette` from [quantize_palette](Transforms::quantize_palette) kept by the read struct.
+    pub(crate) unsafe fn apply(
+        &self,
+        png_ptr: png_structp,
+        quantize_palette: Option<&QuantizePalette>,
+    ) {
+        if self.scale_16 {
+            png_set_scale_16(png_ptr);
+        }
+        if self.strip_16 {
+            png_set_strip_16(png_ptr);
+        }
+        if self.strip_alpha {
+            png_set_strip_alpha(png_ptr);
+        }
+        if self.packing {
+            png_set_packing(png_ptr);
+        }
+        if self.packswap {
+            png_set_packswap(png_ptr);
+        }
+
+        if self.expand {
+            png_set_expand(png_ptr);
+        }
+        if self.palette_to_rgb {
+            png_set_palette_to_rgb(png_ptr);
+        }
+        if self.expand_gray_1_2_4_to_8 {
+            png_set_expand_gray_1_2_4_to_8(png_ptr);
+        }
+        if self.trns_to_alpha {
+            png_set_tRNS_to_alpha(png_ptr);
+        }
+        if self.expand_16 {
+            png_set_expand_16(png_ptr);
+        }
+
+        if self.gray_to_rgb {
+            png_set_gray_to_rgb(png_ptr);
+        }
+        if let Some((red, green)) = self.rgb_to_gray {
+            png_set_rgb_to_gray(png_ptr, PNG_ERROR_ACTION_NONE as c_int, red, green);
+        }
+
+        // The file gamma default is set before alpha mode, which would override it otherwise
+        if let Some((screen, file)) = self.gamma {
+            png_set_gamma(png_ptr, screen.to_png(), file.to_png());
+        }
+        if let Some((mode, screen)) = self.alpha_mode {
+            png_set_alpha_mode(png_ptr, mode.to_png(), screen.to_png());
+        }
+        if let Some((color, gamma)) = self.background {
+            let (red, green, blue, gray) = match color {
+                BackgroundColor::Gray(gray) => (0, 0, 0, gray),
+                BackgroundColor::Rgb { red, green, blue } => (red, green, blue, 0),
+            };
+            let color = png_color_16 {
+                index: 0,
+                red,
+                green,
+                blue,
+                gray,
+            };
+            let (code, value) = match gamma {
+                BackgroundGamma::Screen => (PNG_BACKGROUND_GAMMA_SCREEN, 1.0),
+                BackgroundGamma::File => (PNG_BACKGROUND_GAMMA_FILE, 1.0),
+                BackgroundGamma::Unique(value) => (PNG_BACKGROUND_GAMMA_UNIQUE, value),
+            };
+
+            png_set_background(png_ptr, &color, code as c_int, 0, value);
+        }
+
+        if let (Some((palette, maximum_colors)), Some(quantize_palette)) =
+            (&self.quantize, quantize_palette)
+        {
+            // Full quantization builds the lookup of RGB values
+            png_set_quantize(
+                png_ptr,
+                quantize_palette.as_mut_ptr(),
+                palette.len() as c_int,
+                c_int::from(*maximum_colors),
+                null(),
+                1,
+            );
+        }
+
+        if self.invert_mono {
+            png_set_invert_mono(png_ptr);
+        }
+        if self.invert_alpha {
+            png_set_invert_alpha(png_ptr);
+        }
+        if self.bgr {
+            png_set_bgr(png_ptr);
+        }
+        if self.swap_alpha {
+            png_set_swap_alpha(png_ptr);
+        }
+        if self.swap {
+            png_set_swap(png_ptr);
+        }
+
+        if let Some(Filler {
+            value,
+            position,
+            alpha,
+        }) = self.filler
+        {
+            let flags = match position {
+                FillerPosition::Before => PNG_FILLER_BEFORE,
+                FillerPosition::After => PNG_FILLER_AFTER,
+            } as c_int;
+
+            if alpha {
+                png_set_add_alpha(png_ptr, u32::from(value), flags);
+            } else {
+                png_set_filler(png_ptr, u32::from(value), flags);
+            }
+        }
+    }
+}
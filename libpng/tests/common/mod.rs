@@ -0,0 +1,65 @@
+//! Helpers shared by integration tests, each test crate uses only some of them.
+#![allow(dead_code)]
+
+use std::{io::Cursor, path::PathBuf};
+
+use libpng::{Error, ReadOptions, StreamDecoder, StreamEncoder, StreamHeader, Transforms};
+
+/// Path of an image in the 'tests' directory.
+pub fn test_image_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join(name)
+}
+
+/// Path of 'test.png', shared with the 'libpng-vendored-sys' tests.
+pub fn test_png_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../libpng-vendored-sys/tests")
+        .join("test.png")
+}
+
+/// Encodes `rows` via [StreamEncoder].
+pub fn encode(header: StreamHeader, rows: &[&[u8]]) -> Vec<u8> {
+    let mut encoder = StreamEncoder::new(Vec::new(), header).unwrap();
+
+    for row in rows {
+        encoder.write_row(row).unwrap();
+    }
+
+    encoder.finish().unwrap()
+}
+
+/// Encodes rows of zeros via [StreamEncoder].
+pub fn encode_blank(header: StreamHeader) -> Vec<u8> {
+    let height = header.height;
+    let mut encoder = StreamEncoder::new(Vec::new(), header).unwrap();
+    let row = vec![0; encoder.row_bytes()];
+
+    for _ in 0..height {
+        encoder.write_row(&row).unwrap();
+    }
+
+    encoder.finish().unwrap()
+}
+
+/// Decoder of an in-memory image.
+pub type SliceDecoder<'a> = StreamDecoder<Cursor<&'a [u8]>>;
+
+/// Decodes all rows via [StreamDecoder] with `options`, returns them with the finished decoder
+/// for chunks and warnings.
+pub fn decode_rows(
+    encoded: &[u8],
+    options: ReadOptions,
+) -> Result<(Vec<u8>, SliceDecoder<'_>), Error> {
+    let mut decoder =
+        StreamDecoder::with_options(Cursor::new(encoded), Transforms::new(), options)?;
+    let mut rows = vec![];
+    let mut row = vec![0; decoder.info().row_bytes];
+
+    while decoder.next_row(&mut row)?.is_some() {
+        rows.extend_from_slice(&row);
+    }
+
+    Ok((rows, decoder))
+}
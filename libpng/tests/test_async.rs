@@ -0,0 +1,241 @@
+#![cfg(all(feature = "tokio", feature = "futures-io"))]
+mod common;
+
+use std::{fs::read, ops::ControlFlow};
+
+use tokio::io::{duplex, AsyncWriteExt};
+use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
+
+use libpng::{
+    futures_io, tokio as png_tokio, ColorType, Decoder, Error, PixelFormat, ProgressiveEvent,
+    StreamHeader,
+};
+
+use common::{test_image_path, test_png_path};
+
+#[tokio::test]
+async fn test_tokio_round_trip() {
+    let (reader, writer) = duplex(256);
+
+    let encoding = tokio::spawn(async move {
+        let mut encoder = png_tokio::AsyncEncoder::new(writer, header()).unwrap();
+
+        for y in 0..HEIGHT {
+            encoder.write_row(&row(y)).await.unwrap();
+        }
+
+        encoder.finish().await.unwrap();
+    });
+
+    let mut decoder = png_tokio::AsyncDecoder::new(reader).unwrap();
+    let rows = decode_rows(&mut decoder).await;
+
+    encoding.await.unwrap();
+
+    assert_eq!(rows, (0..HEIGHT).map(row).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn test_futures_io_round_trip() {
+    let (reader, writer) = duplex(256);
+
+    let encoding = tokio::spawn(async move {
+        let mut encoder = futures_io::AsyncEncoder::new(writer.compat_write(), header()).unwrap();
+        encoder.set_flush_interval(8);
+
+        for y in 0..HEIGHT {
+            encoder.write_row(&row(y)).await.unwrap();
+        }
+
+        encoder.flush().await.unwrap();
+        encoder.finish().await.unwrap();
+    });
+
+    let mut decoder = futures_io::AsyncDecoder::new(reader.compat()).unwrap();
+    let mut rows = Vec::new();
+
+    decoder
+        .decode(|event| {
+            if let ProgressiveEvent::Row { data, .. } = event {
+                rows.push(data.to_vec());
+            }
+
+            ControlFlow::Continue(())
+        })
+        .await
+        .unwrap();
+
+    encoding.await.unwrap();
+
+    assert!(decoder.is_finished());
+    assert_eq!(rows, (0..HEIGHT).map(row).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn test_tokio_decode_file() {
+    let path = test_image_path("test_interlaced.png");
+    let data = read(&path).unwrap();
+    let (reader, mut writer) = duplex(64);
+
+    let writing = tokio::spawn(async move {
+        writer.write_all(&data).await.unwrap();
+    });
+
+    let mut decoder = png_tokio::AsyncDecoder::new(reader).unwrap();
+    let rows = decode_rows(&mut decoder).await;
+
+    writing.await.unwrap();
+
+    let expected = Decoder::from_path(&path)
+        .unwrap()
+        .with_format(PixelFormat::RGBA)
+        .decode()
+        .unwrap();
+
+    assert!(decoder.info().unwrap().interlaced);
+
+    for (y, row) in rows.iter().enumerate() {
+        assert_eq!(row, expected.row(y as u32));
+    }
+}
+
+#[tokio::test]
+async fn test_tokio_pause() {
+    let data = read(test_png_path()).unwrap();
+    let mut decoder = png_tokio::AsyncDecoder::new(data.as_slice()).unwrap();
+
+    decoder
+        .decode(|event| match event {
+            ProgressiveEvent::Header(_) => ControlFlow::Break(()),
+            _ => panic!("No events expected before resuming"),
+        })
+        .await
+        .unwrap();
+
+    assert!(!decoder.is_finished());
+    assert_eq!(decoder.info().unwrap().height, 480);
+
+    let mut rows = 0;
+
+    decoder
+        .decode(|event| {
+            if let ProgressiveEvent::Row { .. } = event {
+                rows += 1;
+            }
+
+            ControlFlow::Continue(())
+        })
+        .await
+        .unwrap();
+
+    assert!(decoder.is_finished());
+    assert_eq!(rows, 480);
+}
+
+#[tokio::test]
+async fn test_tokio_pause_on_row() {
+    let (reader, writer) = duplex(64);
+
+    let encoding = tokio::spawn(async move {
+        let mut encoder = png_tokio::AsyncEncoder::new(writer, header()).unwrap();
+        // Noise, so that rows are spread over many reads
+        let mut state = 1u32;
+
+        for _ in 0..HEIGHT {
+            let row: Vec<u8> = (0..WIDTH * 3)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 17;
+                    state ^= state << 5;
+                    state as u8
+                })
+                .collect();
+            encoder.write_row(&row).await.unwrap();
+        }
+
+        encoder.finish().await.unwrap();
+    });
+
+    let mut decoder = png_tokio::AsyncDecoder::new(reader).unwrap();
+    let mut rows = 0;
+
+    // Reads fit into a single piece of input, the pause takes effect after it
+    decoder
+        .decode(|event| match event {
+            ProgressiveEvent::Row { .. } => {
+                rows += 1;
+                ControlFlow::Break(())
+            }
+            _ => ControlFlow::Continue(()),
+        })
+        .await
+        .unwrap();
+
+    assert!(!decoder.is_finished());
+    assert!((1..HEIGHT).contains(&rows), "{rows}");
+
+    decoder
+        .decode(|event| {
+            if let ProgressiveEvent::Row { .. } = event {
+                rows += 1;
+            }
+
+            ControlFlow::Continue(())
+        })
+        .await
+        .unwrap();
+
+    encoding.await.unwrap();
+
+    assert!(decoder.is_finished());
+    assert_eq!(rows, HEIGHT);
+}
+
+#[tokio::test]
+async fn test_tokio_truncated() {
+    let data = read(test_png_path()).unwrap();
+    let mut decoder = png_tokio::AsyncDecoder::new(&data[..data.len() / 2]).unwrap();
+
+    let result = decoder.decode(|_| ControlFlow::Continue(())).await;
+
+    assert!(
+        matches!(result, Err(Error::Io(ref error)) if error.kind() == std::io::ErrorKind::UnexpectedEof)
+    );
+}
+
+const WIDTH: u32 = 33;
+const HEIGHT: u32 = 40;
+
+fn header() -> StreamHeader {
+    StreamHeader::new(WIDTH, HEIGHT, ColorType::Rgb)
+}
+
+fn row(y: u32) -> Vec<u8> {
+    (0..WIDTH * 3).map(|x| (x * 7 + y * 13) as u8).collect()
+}
+
+/// Final content of every row.
+async fn decode_rows<R: tokio::io::AsyncRead + Unpin>(
+    decoder: &mut png_tokio::AsyncDecoder<R>,
+) -> Vec<Vec<u8>> {
+    let mut rows = Vec::new();
+
+    decoder
+        .decode(|event| {
+            match event {
+                ProgressiveEvent::Header(info) => rows = vec![Vec::new(); info.height as usize],
+                ProgressiveEvent::Row { position, data } => {
+                    rows[position.y as usize] = data.to_vec();
+                }
+                _ => {}
+            }
+
+            ControlFlow::Continue(())
+        })
+        .await
+        .unwrap();
+
+    assert!(decoder.is_finished());
+
+    rows
+}
@@ -0,0 +1,184 @@
+mod common;
+
+use std::{fs::File, io::Cursor};
+
+use libpng::{
+    Chromaticities, ColorInfo, ColorType, Error, IccProfile, RenderingIntent, StreamDecoder,
+    StreamEncoder, StreamHeader, Xy, SRGB_CHROMATICITIES, SRGB_GAMMA,
+};
+
+use common::test_png_path;
+
+#[test]
+fn test_read_color_info() {
+    let decoder = StreamDecoder::new(File::open(test_png_path()).unwrap()).unwrap();
+
+    assert_eq!(
+        decoder.color_info(),
+        ColorInfo {
+            icc_profile: None,
+            rendering_intent: None,
+            chromaticities: Some(SRGB_CHROMATICITIES),
+            gamma: Some(SRGB_GAMMA),
+        }
+    );
+}
+
+#[test]
+fn test_srgb_round_trip() {
+    for intent in [
+        RenderingIntent::Perceptual,
+        RenderingIntent::RelativeColorimetric,
+        RenderingIntent::Saturation,
+        RenderingIntent::AbsoluteColorimetric,
+    ] {
+        let color_info = ColorInfo::srgb(intent);
+        assert_eq!(round_trip(ColorType::Rgb, &color_info), color_info);
+    }
+}
+
+#[test]
+fn test_gamma_chromaticities_round_trip() {
+    let color_info = ColorInfo {
+        chromaticities: Some(Chromaticities {
+            white: Xy::new(0.3127, 0.329),
+            red: Xy::new(0.64, 0.33),
+            green: Xy::new(0.21, 0.71),
+            blue: Xy::new(0.15, 0.06),
+        }),
+        gamma: Some(0.5),
+        ..ColorInfo::default()
+    };
+
+    assert_eq!(round_trip(ColorType::Rgb, &color_info), color_info);
+    assert!(round_trip(ColorType::Rgb, &ColorInfo::default()).is_empty());
+}
+
+#[test]
+fn test_icc_profile_round_trip() {
+    let color_info = ColorInfo {
+        icc_profile: Some(IccProfile {
+            // libpng 1.6 rejects iCCP chunks shorter than 92 bytes
+            name: String::from("Minimal RGB profile with a long name"),
+            data: icc_profile(b"RGB "),
+        }),
+        ..ColorInfo::default()
+    };
+
+    assert_eq!(round_trip(ColorType::Rgb, &color_info), color_info);
+}
+
+#[test]
+fn test_xyz() {
+    let colorants = SRGB_CHROMATICITIES.to_xyz().unwrap();
+
+    let expected = [
+        (colorants.red, [0.4124, 0.2126, 0.0193]),
+        (colorants.green, [0.3576, 0.7152, 0.1192]),
+        (colorants.blue, [0.1805, 0.0722, 0.9505]),
+    ];
+    for (xyz, [x, y, z]) in expected {
+        assert!((xyz.x - x).abs() < 1e-3, "{xyz:?}");
+        assert!((xyz.y - y).abs() < 1e-3, "{xyz:?}");
+        assert!((xyz.z - z).abs() < 1e-3, "{xyz:?}");
+    }
+
+    let chromaticities = Chromaticities::from_xyz(&colorants).unwrap();
+    for (actual, expected) in [
+        (chromaticities.white, SRGB_CHROMATICITIES.white),
+        (chromaticities.red, SRGB_CHROMATICITIES.red),
+        (chromaticities.green, SRGB_CHROMATICITIES.green),
+        (chromaticities.blue, SRGB_CHROMATICITIES.blue),
+    ] {
+        assert!((actual.x - expected.x).abs() < 1e-9);
+        assert!((actual.y - expected.y).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_invalid_color_info() {
+    let profile = IccProfile {
+        name: String::from("Profile"),
+        data: icc_profile(b"RGB "),
+    };
+    let chromaticities = |red| Chromaticities {
+        red,
+        ..SRGB_CHROMATICITIES
+    };
+
+    let invalid = [
+        ColorInfo {
+            icc_profile: Some(profile.clone()),
+            ..ColorInfo::srgb(RenderingIntent::Perceptual)
+        },
+        ColorInfo {
+            icc_profile: Some(IccProfile {
+                name: String::new(),
+                ..profile
+            }),
+            ..ColorInfo::default()
+        },
+        ColorInfo {
+            gamma: Some(0.0),
+            ..ColorInfo::default()
+        },
+        ColorInfo {
+            chromaticities: Some(chromaticities(Xy::new(0.7, 0.7))),
+            ..ColorInfo::default()
+        },
+        ColorInfo {
+            // On the line between green and blue
+            chromaticities: Some(chromaticities(Xy::new(0.225, 0.33))),
+            ..ColorInfo::default()
+        },
+    ];
+
+    for color_info in invalid {
+        assert!(matches!(color_info.validate(), Err(Error::InvalidInput(_))));
+
+        let header = StreamHeader::new(1, 1, ColorType::Rgb).with_color_info(color_info);
+        assert!(matches!(
+            StreamEncoder::new(Vec::new(), header),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+}
+
+fn round_trip(color_type: ColorType, color_info: &ColorInfo) -> ColorInfo {
+    let header = StreamHeader::new(1, 1, color_type).with_color_info(color_info.clone());
+    let mut encoder = StreamEncoder::new(Vec::new(), header).unwrap();
+
+    let row = vec![0; encoder.row_bytes()];
+    encoder.write_row(&row).unwrap();
+    let encoded = encoder.finish().unwrap();
+
+    StreamDecoder::new(Cursor::new(encoded))
+        .unwrap()
+        .color_info()
+}
+
+/// Minimal ICC profile with the media white point tag only.
+fn icc_profile(color_space: &[u8; 4]) -> Vec<u8> {
+    let d50 = [0xf6d6u32, 0x1_0000, 0xd32d].map(u32::to_be_bytes).concat();
+    let mut profile = vec![0; 164];
+
+    profile[0..4].copy_from_slice(&164u32.to_be_bytes());
+    profile[8..12].copy_from_slice(&[2, 0x10, 0, 0]);
+    profile[12..16].copy_from_slice(b"mntr");
+    profile[16..20].copy_from_slice(color_space);
+    profile[20..24].copy_from_slice(b"XYZ ");
+    profile[36..40].copy_from_slice(b"acsp");
+    profile[68..80].copy_from_slice(&d50);
+    // Profile ID, normally MD5 of the profile
+    profile[84..100].copy_from_slice(b"0123456789abcdef");
+
+    // Tag table: wtpt at offset 144 of 20 bytes
+    profile[128..132].copy_from_slice(&1u32.to_be_bytes());
+    profile[132..136].copy_from_slice(b"wtpt");
+    profile[136..140].copy_from_slice(&144u32.to_be_bytes());
+    profile[140..144].copy_from_slice(&20u32.to_be_bytes());
+    profile[144..148].copy_from_slice(b"XYZ ");
+    profile[152..164].copy_from_slice(&d50);
+
+    profile
+}
@@ -0,0 +1,223 @@
+mod common;
+
+use std::fs::{read, File};
+
+use libpng::{Color, Decoder, Error, PixelFormat};
+
+use common::test_png_path;
+
+const WIDTH: u32 = 640;
+const HEIGHT: u32 = 480;
+
+#[test]
+fn test_decode_from_path() {
+    let decoder = Decoder::from_path(test_png_path()).unwrap();
+
+    assert_eq!(decoder.width(), WIDTH);
+    assert_eq!(decoder.height(), HEIGHT);
+    assert_eq!(decoder.source_format().unwrap(), PixelFormat::RGBA);
+
+    let image = decoder.decode().unwrap();
+
+    assert_eq!(image.width, WIDTH);
+    assert_eq!(image.height, HEIGHT);
+    assert_eq!(image.format, PixelFormat::RGBA);
+    assert_eq!(image.row_stride, WIDTH as usize * 4);
+    assert_eq!(image.data.len(), (WIDTH * HEIGHT * 4) as usize);
+    assert!(image.colormap.is_empty());
+}
+
+#[test]
+fn test_decode_sources_match() {
+    let input = read(test_png_path()).unwrap();
+
+    let from_path = Decoder::from_path(test_png_path())
+        .unwrap()
+        .decode()
+        .unwrap();
+    let from_slice = Decoder::from_slice(&input).unwrap().decode().unwrap();
+    let from_file = Decoder::from_file(File::open(test_png_path()).unwrap())
+        .unwrap()
+        .decode()
+        .unwrap();
+
+    assert_eq!(from_path, from_slice);
+    assert_eq!(from_path, from_file);
+}
+
+#[test]
+fn test_decode_formats() {
+    let formats = [
+        PixelFormat::GRAY,
+        PixelFormat::GRAY_ALPHA,
+        PixelFormat::ALPHA_GRAY,
+        PixelFormat::RGB,
+        PixelFormat::BGR,
+        PixelFormat::ARGB,
+        PixelFormat::BGRA,
+        PixelFormat::ABGR,
+        PixelFormat::LINEAR_GRAY,
+        PixelFormat::LINEAR_GRAY_ALPHA,
+        PixelFormat::LINEAR_RGB,
+        PixelFormat::LINEAR_RGBA,
+    ];
+
+    for format in formats {
+        let image = Decoder::from_path(test_png_path())
+            .unwrap()
+            .with_format(format)
+            .decode()
+            .unwrap();
+
+        assert_eq!(image.format, format);
+        assert_eq!(image.row_stride, WIDTH as usize * format.pixel_size());
+        assert_eq!(
+            image.data.len(),
+            (WIDTH * HEIGHT) as usize * format.pixel_size(),
+            "{format:?}"
+        );
+    }
+}
+
+#[test]
+fn test_decode_colormap() {
+    let format = PixelFormat::RGB.colormap();
+
+    let image = Decoder::from_path(test_png_path())
+        .unwrap()
+        .with_format(format)
+        .with_background(Color::new(255, 255, 255))
+        .decode()
+        .unwrap();
+
+    assert_eq!(image.format, format);
+    assert_eq!(image.data.len(), (WIDTH * HEIGHT) as usize);
+    assert!(image.colormap_entries() > 0);
+    assert!(image.colormap_entries() <= 256);
+    assert_eq!(image.colormap.len(), image.colormap_entries() * 3);
+
+    let max_index = *image.data.iter().max().unwrap() as usize;
+    assert!(max_index < image.colormap_entries());
+}
+
+#[test]
+fn test_decode_linear_colormap() {
+    let format = PixelFormat::LINEAR_RGB.colormap();
+
+    let image = Decoder::from_path(test_png_path())
+        .unwrap()
+        .with_format(format)
+        .decode()
+        .unwrap();
+
+    // 1-byte indices, 16-bit colormap components
+    assert_eq!(image.row_stride, WIDTH as usize);
+    assert_eq!(image.data.len(), (WIDTH * HEIGHT) as usize);
+    assert_eq!(image.colormap.len(), image.colormap_entries() * 6);
+
+    let max_index = *image.data.iter().max().unwrap() as usize;
+    assert!(max_index < image.colormap_entries());
+}
+
+#[test]
+fn test_decode_colormap_requires_background() {
+    let result = Decoder::from_path(test_png_path())
+        .unwrap()
+        .with_format(PixelFormat::RGB.colormap())
+        .decode();
+
+    assert!(matches!(result, Err(Error::Png(_))));
+}
+
+#[test]
+fn test_decode_background() {
+    let white = Decoder::from_path(test_png_path())
+        .unwrap()
+        .with_format(PixelFormat::RGB)
+        .with_background(Color::new(255, 255, 255))
+        .decode()
+        .unwrap();
+
+    let rgba = Decoder::from_path(test_png_path())
+        .unwrap()
+        .decode()
+        .unwrap();
+
+    // Fully transparent pixels take the background color
+    let transparent = rgba
+        .data
+        .chunks(4)
+        .position(|pixel| pixel[3] == 0)
+        .expect("Test image has transparent pixels");
+
+    assert_eq!(
+        &white.data[transparent * 3..transparent * 3 + 3],
+        &[255, 255, 255]
+    );
+}
+
+#[test]
+fn test_decode_row_stride() {
+    let padded = Decoder::from_path(test_png_path())
+        .unwrap()
+        .with_row_stride(WIDTH as usize * 4 + 16)
+        .decode()
+        .unwrap();
+
+    let packed = Decoder::from_path(test_png_path())
+        .unwrap()
+        .decode()
+        .unwrap();
+
+    assert_eq!(
+        padded.data.len(),
+        (WIDTH as usize * 4 + 16) * HEIGHT as usize
+    );
+
+    for y in 0..HEIGHT {
+        assert_eq!(padded.row(y), packed.row(y));
+    }
+}
+
+#[test]
+fn test_decode_invalid_row_stride() {
+    for row_stride in [WIDTH as usize * 4 - 1, 0] {
+        let result = Decoder::from_path(test_png_path())
+            .unwrap()
+            .with_row_stride(row_stride)
+            .decode();
+
+        assert!(matches!(result, Err(Error::InvalidInput(_))));
+    }
+
+    let result = Decoder::from_path(test_png_path())
+        .unwrap()
+        .with_format(PixelFormat::LINEAR_RGBA)
+        .with_row_stride(WIDTH as usize * 8 + 1)
+        .decode();
+
+    assert!(matches!(result, Err(Error::InvalidInput(_))));
+}
+
+#[test]
+fn test_pixel_format_from_bits() {
+    let unknown_flag = 0x100;
+    let bits = PixelFormat::BGRA.bits() | unknown_flag;
+
+    assert_eq!(PixelFormat::from_bits(bits), None);
+    assert_eq!(PixelFormat::from_bits_truncate(bits), PixelFormat::BGRA);
+}
+
+#[test]
+fn test_decode_invalid_input() {
+    let result = Decoder::from_slice(b"not a PNG file");
+
+    assert!(matches!(result, Err(Error::Png(_))));
+}
+
+#[test]
+fn test_decode_missing_file() {
+    let result = Decoder::from_path(test_png_path().with_file_name("missing.png"));
+
+    assert!(matches!(result, Err(Error::Io(_))));
+}
@@ -0,0 +1,241 @@
+mod common;
+
+use std::{env::temp_dir, fs::remove_file, io::Cursor};
+
+use libpng::{Color, Decoder, Encoder, Error, Image, PixelFormat};
+
+use common::test_png_path;
+
+#[test]
+fn test_round_trip_to_vec() {
+    let image = decode(PixelFormat::RGBA);
+
+    let encoded = Encoder::from_image(&image).encode_to_vec().unwrap();
+    let decoded = Decoder::from_slice(&encoded).unwrap().decode().unwrap();
+
+    assert_eq!(decoded, image);
+}
+
+#[test]
+fn test_round_trip_formats() {
+    let formats = [
+        PixelFormat::GRAY,
+        PixelFormat::GRAY_ALPHA,
+        PixelFormat::RGB,
+        PixelFormat::BGRA,
+        PixelFormat::ARGB,
+        PixelFormat::LINEAR_GRAY,
+        PixelFormat::LINEAR_RGB,
+    ];
+
+    for format in formats {
+        let image = decode(format);
+
+        let encoded = Encoder::from_image(&image).encode_to_vec().unwrap();
+        let decoded = Decoder::from_slice(&encoded)
+            .unwrap()
+            .with_format(format)
+            .decode()
+            .unwrap();
+
+        assert_eq!(decoded, image, "{format:?}");
+    }
+}
+
+#[test]
+fn test_linear_unaligned_data() {
+    let image = decode(PixelFormat::LINEAR_RGB);
+
+    let mut shifted = vec![0_u8];
+    shifted.extend_from_slice(&image.data);
+
+    let encoded = Encoder::new(&shifted[1..], image.width, image.height, image.format)
+        .encode_to_vec()
+        .unwrap();
+
+    assert_eq!(
+        encoded,
+        Encoder::from_image(&image).encode_to_vec().unwrap()
+    );
+}
+
+#[test]
+fn test_linear_colormap_unaligned_indices() {
+    let colormap: Vec<u8> = [0_u16, 0, 0, 65535, 0, 0, 0, 0, 65535]
+        .iter()
+        .flat_map(|component| component.to_ne_bytes())
+        .collect();
+
+    // Odd number of 1-byte indices at an odd address
+    let shifted = [0_u8, 0, 1, 2];
+    let indices = &shifted[1..];
+
+    let encoder = |data| {
+        Encoder::new(data, 3, 1, PixelFormat::LINEAR_RGB.colormap())
+            .with_colormap(&colormap)
+            .encode_to_vec()
+            .unwrap()
+    };
+    let encoded = encoder(indices);
+    let aligned = indices.to_vec();
+
+    assert_eq!(encoded, encoder(&aligned));
+
+    let decoded = Decoder::from_slice(&encoded)
+        .unwrap()
+        .with_format(PixelFormat::RGB)
+        .decode()
+        .unwrap();
+
+    assert_eq!(decoded.data, [0, 0, 0, 255, 0, 0, 0, 0, 255]);
+}
+
+#[test]
+fn test_fast() {
+    let image = decode(PixelFormat::RGBA);
+
+    let encoded = Encoder::from_image(&image)
+        .with_fast(true)
+        .encode_to_vec()
+        .unwrap();
+    let decoded = Decoder::from_slice(&encoded).unwrap().decode().unwrap();
+
+    assert_eq!(decoded, image);
+}
+
+#[test]
+fn test_convert_to_8_bit() {
+    let linear = decode(PixelFormat::LINEAR_RGBA);
+
+    let linear_encoded = Encoder::from_image(&linear).encode_to_vec().unwrap();
+    let converted_encoded = Encoder::from_image(&linear)
+        .with_convert_to_8_bit(true)
+        .encode_to_vec()
+        .unwrap();
+
+    // Bit depth from IHDR
+    assert_eq!(linear_encoded[24], 16);
+    assert_eq!(converted_encoded[24], 8);
+
+    let decoded = Decoder::from_slice(&converted_encoded)
+        .unwrap()
+        .decode()
+        .unwrap();
+
+    assert_eq!(decoded.format, PixelFormat::RGBA);
+    assert_eq!(decoded.data.len(), linear.data.len() / 2);
+}
+
+#[test]
+fn test_colormap_round_trip() {
+    let image = Decoder::from_path(test_png_path())
+        .unwrap()
+        .with_format(PixelFormat::RGB.colormap())
+        .with_background(Color::new(255, 255, 255))
+        .decode()
+        .unwrap();
+
+    let encoded = Encoder::from_image(&image).encode_to_vec().unwrap();
+    let decoded = Decoder::from_slice(&encoded)
+        .unwrap()
+        .with_format(PixelFormat::RGB)
+        .decode()
+        .unwrap();
+
+    for (index, pixel) in image.data.iter().zip(decoded.data.chunks(3)).take(1000) {
+        let entry = *index as usize * 3;
+        assert_eq!(&image.colormap[entry..entry + 3], pixel);
+    }
+}
+
+#[test]
+fn test_row_stride() {
+    let image = decode(PixelFormat::RGB);
+    let row_size = image.width as usize * 3;
+    let padded_stride = row_size + 5;
+
+    let mut padded = vec![0_u8; padded_stride * image.height as usize];
+    for y in 0..image.height {
+        let start = y as usize * padded_stride;
+        padded[start..start + row_size].copy_from_slice(image.row(y));
+    }
+
+    let encoded = Encoder::new(&padded, image.width, image.height, PixelFormat::RGB)
+        .with_row_stride(padded_stride)
+        .encode_to_vec()
+        .unwrap();
+    let decoded = Decoder::from_slice(&encoded)
+        .unwrap()
+        .with_format(PixelFormat::RGB)
+        .decode()
+        .unwrap();
+
+    assert_eq!(decoded, image);
+}
+
+#[test]
+fn test_encode_to_path() {
+    let image = decode(PixelFormat::RGBA);
+    let path = temp_dir().join(format!("libpng-test-encoder-{}.png", std::process::id()));
+
+    Encoder::from_image(&image).encode_to_path(&path).unwrap();
+    let decoded = Decoder::from_path(&path).unwrap().decode();
+
+    remove_file(&path).unwrap();
+
+    assert_eq!(decoded.unwrap(), image);
+
+    let missing_dir = path.with_extension("missing").join("image.png");
+    let result = Encoder::from_image(&image).encode_to_path(missing_dir);
+
+    assert!(matches!(result, Err(Error::Png(_))));
+}
+
+#[test]
+fn test_encode_to_writer() {
+    let image = decode(PixelFormat::RGBA);
+    let mut writer = Cursor::new(vec![]);
+
+    Encoder::from_image(&image)
+        .encode_to_writer(&mut writer)
+        .unwrap();
+
+    assert_eq!(
+        writer.into_inner(),
+        Encoder::from_image(&image).encode_to_vec().unwrap()
+    );
+}
+
+#[test]
+fn test_invalid_input() {
+    let data = vec![0_u8; 16 * 16 * 3];
+    let colormap = vec![0_u8; 257 * 3];
+
+    let invalid = [
+        Encoder::new(&data, 0, 16, PixelFormat::RGB),
+        Encoder::new(&data, 16, 17, PixelFormat::RGB),
+        Encoder::new(&data, 16, 16, PixelFormat::RGBA),
+        Encoder::new(&data, 16, 16, PixelFormat::RGB).with_row_stride(47),
+        Encoder::new(&data, 16, 16, PixelFormat::LINEAR_GRAY).with_row_stride(33),
+        Encoder::new(&data, 16, 3, PixelFormat::LINEAR_GRAY).with_row_stride(usize::MAX / 2 - 1),
+        Encoder::new(&data, 16, 16, PixelFormat::RGB.colormap()),
+        Encoder::new(&data, 16, 16, PixelFormat::RGB).with_colormap(&colormap[..3]),
+        Encoder::new(&data, 16, 16, PixelFormat::RGB.colormap()).with_colormap(&colormap[..4]),
+        Encoder::new(&data, 16, 16, PixelFormat::RGB.colormap()).with_colormap(&colormap),
+    ];
+
+    for encoder in invalid {
+        assert!(
+            matches!(encoder.encode_to_vec(), Err(Error::InvalidInput(_))),
+            "{encoder:?}"
+        );
+    }
+}
+
+fn decode(format: PixelFormat) -> Image {
+    Decoder::from_path(test_png_path())
+        .unwrap()
+        .with_format(format)
+        .decode()
+        .unwrap()
+}
@@ -0,0 +1,218 @@
+mod common;
+
+use std::{io::Cursor, ops::ControlFlow};
+
+use libpng::{
+    ColorType, Decoder, Error, ExifInfo, Image, Orientation, PixelFormat, ProgressiveDecoder,
+    StreamDecoder, StreamEncoder, StreamHeader,
+};
+
+use common::encode;
+
+#[test]
+fn test_parse_exif() {
+    for big_endian in [false, true] {
+        let info = ExifInfo::parse(&exif(big_endian, 6)).unwrap();
+
+        assert_eq!(
+            info,
+            ExifInfo {
+                orientation: Some(Orientation::Rotate90),
+                date_time_original: Some(String::from("2024:05:17 14:03:59")),
+                date_time: Some(String::from("2024:05:18 09:00:00")),
+                make: Some(String::from("Maker")),
+                model: Some(String::from("Camera Model X")),
+                lens_model: None,
+            }
+        );
+    }
+
+    let mut prefixed = b"Exif\0\0".to_vec();
+    prefixed.extend(exif(true, 3));
+    let info = ExifInfo::parse(&prefixed).unwrap();
+    assert_eq!(info.orientation, Some(Orientation::Rotate180));
+
+    let info = ExifInfo::parse(&exif(false, 9)).unwrap();
+    assert_eq!(info.orientation, None);
+}
+
+#[test]
+fn test_invalid_exif() {
+    let mut truncated = exif(false, 1);
+    truncated.truncate(20);
+
+    let mut bad_ifd_offset = exif(true, 1);
+    bad_ifd_offset[4..8].copy_from_slice(&1000u32.to_be_bytes());
+
+    for data in [
+        vec![],
+        b"II".to_vec(),
+        b"XX*\0\0\0\0\0".to_vec(),
+        truncated,
+        bad_ifd_offset,
+    ] {
+        assert!(matches!(
+            ExifInfo::parse(&data),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    let header = StreamHeader::new(1, 1, ColorType::Gray).with_exif(b"Exif".to_vec());
+    assert!(matches!(
+        StreamEncoder::new(Vec::new(), header),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+#[test]
+fn test_exif_round_trip() {
+    let data = exif(true, 8);
+    let encoded = encode_with_exif(&data);
+
+    let decoder = StreamDecoder::new(Cursor::new(&encoded)).unwrap();
+    assert_eq!(decoder.exif(), Some(data.clone()));
+
+    let mut decoder = ProgressiveDecoder::new().unwrap();
+    decoder
+        .feed(&encoded, |_| ControlFlow::Continue(()))
+        .unwrap();
+    assert_eq!(decoder.exif(), Some(data.clone()));
+
+    assert_eq!(Decoder::from_slice(&encoded).unwrap().exif(), Some(&*data));
+
+    let encoded = encode_without_exif();
+    let decoder = StreamDecoder::new(Cursor::new(&encoded)).unwrap();
+    assert_eq!(decoder.exif(), None);
+    assert_eq!(Decoder::from_slice(&encoded).unwrap().exif(), None);
+}
+
+#[test]
+fn test_decode_with_exif_orientation() {
+    // 3x2 pixels numbered row by row:
+    // 1 2 3
+    // 4 5 6
+    let expected: [(u16, u32, &[u8]); 8] = [
+        (1, 3, &[1, 2, 3, 4, 5, 6]),
+        (2, 3, &[3, 2, 1, 6, 5, 4]),
+        (3, 3, &[6, 5, 4, 3, 2, 1]),
+        (4, 3, &[4, 5, 6, 1, 2, 3]),
+        (5, 2, &[1, 4, 2, 5, 3, 6]),
+        (6, 2, &[4, 1, 5, 2, 6, 3]),
+        (7, 2, &[6, 3, 5, 2, 4, 1]),
+        (8, 2, &[3, 6, 2, 5, 1, 4]),
+    ];
+
+    for (orientation, width, pixels) in expected {
+        let encoded = encode_with_exif(&exif(false, orientation));
+
+        let image = Decoder::from_slice(&encoded)
+            .unwrap()
+            .with_format(PixelFormat::GRAY)
+            .with_exif_orientation(true)
+            .decode()
+            .unwrap();
+
+        assert_eq!(image.width, width, "Orientation {orientation}");
+        assert_eq!(image.height, 6 / width);
+        assert_eq!(image.row_stride, width as usize);
+        assert_eq!(image.data, pixels, "Orientation {orientation}");
+
+        let image = Decoder::from_slice(&encoded)
+            .unwrap()
+            .with_format(PixelFormat::GRAY)
+            .decode()
+            .unwrap();
+        assert_eq!(image.data, [1, 2, 3, 4, 5, 6]);
+    }
+}
+
+#[test]
+fn test_apply_orientation_with_padding() {
+    let image = Image {
+        width: 2,
+        height: 2,
+        format: PixelFormat::GRAY_ALPHA,
+        data: vec![1, 10, 2, 20, 0, 3, 30, 4, 40, 0],
+        row_stride: 5,
+        colormap: vec![],
+    };
+
+    let rotated = image.apply_orientation(Orientation::Rotate90);
+    assert_eq!(rotated.row_stride, 4);
+    assert_eq!(rotated.data, [3, 30, 1, 10, 4, 40, 2, 20]);
+}
+
+const ROWS: [&[u8]; 2] = [&[1, 2, 3], &[4, 5, 6]];
+
+fn encode_with_exif(exif: &[u8]) -> Vec<u8> {
+    let header = StreamHeader::new(3, 2, ColorType::Gray).with_exif(exif.to_vec());
+    encode(header, &ROWS)
+}
+
+fn encode_without_exif() -> Vec<u8> {
+    encode(StreamHeader::new(3, 2, ColorType::Gray), &ROWS)
+}
+
+/// TIFF data with orientation, make, model and DateTime in the first IFD and DateTimeOriginal in the EXIF IFD.
+fn exif(big_endian: bool, orientation: u16) -> Vec<u8> {
+    let u16_bytes = |value: u16| match big_endian {
+        true => value.to_be_bytes(),
+        false => value.to_le_bytes(),
+    };
+    let u32_bytes = |value: u32| match big_endian {
+        true => value.to_be_bytes(),
+        false => value.to_le_bytes(),
+    };
+
+    let strings: [&[u8]; 4] = [
+        b"Maker\0",
+        b"Camera Model X\0",
+        b"2024:05:18 09:00:00\0",
+        b"2024:05:17 14:03:59\0",
+    ];
+    // Header, first IFD of 5 entries, EXIF IFD of 1 entry, then strings
+    let ifd_offset = 8;
+    let exif_ifd_offset = ifd_offset + 2 + 5 * 12 + 4;
+    let mut string_offset = exif_ifd_offset + 2 + 12 + 4;
+
+    let mut data = match big_endian {
+        true => b"MM\0*".to_vec(),
+        false => b"II*\0".to_vec(),
+    };
+    data.extend(u32_bytes(ifd_offset));
+
+    let entry = |data: &mut Vec<u8>, tag: u16, kind: u16, count: u32, value: [u8; 4]| {
+        data.extend(u16_bytes(tag));
+        data.extend(u16_bytes(kind));
+        data.extend(u32_bytes(count));
+        data.extend(value);
+    };
+    let mut string_value = |index: usize| {
+        let offset = string_offset;
+        string_offset += strings[index].len() as u32;
+        (strings[index].len() as u32, u32_bytes(offset))
+    };
+
+    data.extend(u16_bytes(5));
+    let (count, value) = string_value(0);
+    entry(&mut data, 0x010f, 2, count, value);
+    let (count, value) = string_value(1);
+    entry(&mut data, 0x0110, 2, count, value);
+    let [a, b] = u16_bytes(orientation);
+    entry(&mut data, 0x0112, 3, 1, [a, b, 0, 0]);
+    let (count, value) = string_value(2);
+    entry(&mut data, 0x0132, 2, count, value);
+    entry(&mut data, 0x8769, 4, 1, u32_bytes(exif_ifd_offset));
+    data.extend(u32_bytes(0));
+
+    data.extend(u16_bytes(1));
+    let (count, value) = string_value(3);
+    entry(&mut data, 0x9003, 2, count, value);
+    data.extend(u32_bytes(0));
+
+    for string in strings {
+        data.extend(string);
+    }
+
+    data
+}
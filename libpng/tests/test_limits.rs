@@ -0,0 +1,282 @@
+mod common;
+
+use std::{io::Cursor, ops::ControlFlow};
+
+use libpng::{
+    ChunkLocation, ColorInfo, ColorType, Decoder, Error, IccProfile, Limits, PixelFormat,
+    ProgressiveDecoder, ReadOptions, StreamDecoder, StreamHeader, TextChunk, Transforms,
+    UnknownChunk,
+};
+
+use common::{decode_rows, encode_blank};
+
+const WIDTH: u32 = 16;
+const HEIGHT: u32 = 8;
+
+fn header() -> StreamHeader {
+    StreamHeader::new(WIDTH, HEIGHT, ColorType::Gray)
+}
+
+/// Decodes via the full API, both streaming and progressive.
+fn decode(encoded: &[u8], limits: Limits) -> Result<(), Error> {
+    decode_rows(encoded, ReadOptions::new().with_limits(limits))?;
+
+    let mut decoder = ProgressiveDecoder::with_options(ReadOptions::new().with_limits(limits))?;
+    decoder.feed(encoded, |_| ControlFlow::Continue(()))?;
+    assert!(decoder.is_finished());
+
+    Ok(())
+}
+
+fn assert_exceeded(encoded: &[u8], limits: Limits) {
+    assert!(
+        matches!(decode(encoded, limits), Err(Error::LimitExceeded(_))),
+        "{limits:?}"
+    );
+
+    let mut decoder =
+        ProgressiveDecoder::with_options(ReadOptions::new().with_limits(limits)).unwrap();
+    assert!(
+        matches!(
+            decoder.feed(encoded, |_| ControlFlow::Continue(())),
+            Err(Error::LimitExceeded(_))
+        ),
+        "{limits:?}"
+    );
+}
+
+#[test]
+fn test_dimensions() {
+    let encoded = encode_blank(header());
+
+    let limits = Limits {
+        max_width: WIDTH,
+        max_height: HEIGHT,
+        ..Limits::default()
+    };
+    decode(&encoded, limits).unwrap();
+    Decoder::from_slice(&encoded)
+        .unwrap()
+        .with_limits(limits)
+        .decode()
+        .unwrap();
+
+    for limits in [
+        Limits {
+            max_width: WIDTH - 1,
+            ..limits
+        },
+        Limits {
+            max_height: HEIGHT - 1,
+            ..limits
+        },
+    ] {
+        assert_exceeded(&encoded, limits);
+        assert!(matches!(
+            Decoder::from_slice(&encoded)
+                .unwrap()
+                .with_limits(limits)
+                .decode(),
+            Err(Error::LimitExceeded(_))
+        ));
+    }
+}
+
+#[test]
+fn test_decoded_bytes() {
+    let encoded = encode_blank(header());
+    let decoded_bytes = (WIDTH * HEIGHT) as usize;
+
+    let limits = Limits {
+        max_decoded_bytes: decoded_bytes,
+        ..Limits::default()
+    };
+    decode(&encoded, limits).unwrap();
+
+    let options = ReadOptions::new().with_limits(limits);
+    let transforms = Transforms::new().gray_to_rgb();
+    assert!(matches!(
+        StreamDecoder::with_options(Cursor::new(&encoded), transforms, options),
+        Err(Error::LimitExceeded(_))
+    ));
+
+    let limits = Limits {
+        max_decoded_bytes: decoded_bytes - 1,
+        ..Limits::default()
+    };
+    assert_exceeded(&encoded, limits);
+
+    let decoder = Decoder::from_slice(&encoded).unwrap();
+    assert!(matches!(
+        decoder
+            .with_format(PixelFormat::RGBA)
+            .with_limits(Limits {
+                max_decoded_bytes: decoded_bytes * 4 - 1,
+                ..Limits::default()
+            })
+            .decode(),
+        Err(Error::LimitExceeded(_))
+    ));
+}
+
+#[test]
+fn test_chunk_count() {
+    let mut header = header();
+    for index in 0..3 {
+        header = header.with_text(TextChunk::new("Comment", format!("Text {index}")));
+    }
+    let encoded = encode_blank(header);
+
+    let limits = Limits {
+        max_chunk_count: 3,
+        ..Limits::default()
+    };
+    decode(&encoded, limits).unwrap();
+
+    let options = ReadOptions::new().with_limits(limits);
+    let decoder =
+        StreamDecoder::with_options(Cursor::new(&encoded), Transforms::new(), options).unwrap();
+    assert_eq!(decoder.text().len(), 3);
+
+    let limits = Limits {
+        max_chunk_count: 2,
+        ..Limits::default()
+    };
+    assert_exceeded(&encoded, limits);
+    assert!(matches!(
+        Decoder::from_slice(&encoded)
+            .unwrap()
+            .with_limits(limits)
+            .decode(),
+        Err(Error::LimitExceeded(_))
+    ));
+
+    // Without limits libpng keeps all chunks
+    let decoder = StreamDecoder::new(Cursor::new(&encoded)).unwrap();
+    assert_eq!(decoder.text().len(), 3);
+}
+
+#[test]
+fn test_chunk_size() {
+    let chunk = UnknownChunk::new(
+        "prVt".parse().unwrap(),
+        vec![0; 100],
+        ChunkLocation::AfterIdat,
+    );
+    let encoded = encode_blank(header().with_unknown_chunk(chunk));
+
+    let limits = Limits {
+        max_chunk_size: 100,
+        ..Limits::default()
+    };
+    decode(&encoded, limits).unwrap();
+
+    let limits = Limits {
+        max_chunk_size: 99,
+        ..Limits::default()
+    };
+    assert_exceeded(&encoded, limits);
+    assert!(matches!(
+        Decoder::from_slice(&encoded)
+            .unwrap()
+            .with_limits(limits)
+            .decode(),
+        Err(Error::LimitExceeded(_))
+    ));
+}
+
+#[test]
+fn test_text_size() {
+    // Compressed, the chunk is smaller than the text
+    let text = "a".repeat(2000);
+    let encoded = encode_blank(header().with_text(TextChunk::new("Comment", &text)));
+
+    let limits = Limits {
+        max_text_size: 2000,
+        ..Limits::default()
+    };
+    decode(&encoded, limits).unwrap();
+
+    let limits = Limits {
+        max_text_size: 1999,
+        ..Limits::default()
+    };
+    assert_exceeded(&encoded, limits);
+}
+
+#[test]
+fn test_icc_profile_size() {
+    let profile = icc_profile();
+    let profile_size = profile.len();
+    let color_info = ColorInfo {
+        icc_profile: Some(IccProfile {
+            name: String::from("Minimal gray profile"),
+            data: profile,
+        }),
+        ..ColorInfo::default()
+    };
+    let encoded = encode_blank(header().with_color_info(color_info));
+
+    let limits = Limits {
+        max_icc_profile_size: profile_size,
+        ..Limits::default()
+    };
+    decode(&encoded, limits).unwrap();
+
+    let limits = Limits {
+        max_icc_profile_size: profile_size - 1,
+        ..Limits::default()
+    };
+    assert_exceeded(&encoded, limits);
+}
+
+#[test]
+fn test_invalid_limits() {
+    let invalid = [
+        Limits {
+            max_width: 0,
+            ..Limits::default()
+        },
+        Limits {
+            max_height: 0x8000_0000,
+            ..Limits::default()
+        },
+        Limits {
+            max_chunk_size: 0,
+            ..Limits::default()
+        },
+    ];
+
+    for limits in invalid {
+        assert!(matches!(limits.validate(), Err(Error::InvalidInput(_))));
+        assert!(matches!(
+            ProgressiveDecoder::with_options(ReadOptions::new().with_limits(limits)),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+}
+
+/// Minimal ICC profile with the media white point tag only.
+fn icc_profile() -> Vec<u8> {
+    let d50 = [0xf6d6u32, 0x1_0000, 0xd32d].map(u32::to_be_bytes).concat();
+    let mut profile = vec![0; 164];
+
+    profile[0..4].copy_from_slice(&164u32.to_be_bytes());
+    profile[8..12].copy_from_slice(&[2, 0x10, 0, 0]);
+    profile[12..16].copy_from_slice(b"mntr");
+    profile[16..20].copy_from_slice(b"GRAY");
+    profile[20..24].copy_from_slice(b"XYZ ");
+    profile[36..40].copy_from_slice(b"acsp");
+    profile[68..80].copy_from_slice(&d50);
+    profile[84..100].copy_from_slice(b"0123456789abcdef");
+
+    // Tag table: wtpt at offset 144 of 20 bytes
+    profile[128..132].copy_from_slice(&1u32.to_be_bytes());
+    profile[132..136].copy_from_slice(b"wtpt");
+    profile[136..140].copy_from_slice(&144u32.to_be_bytes());
+    profile[140..144].copy_from_slice(&20u32.to_be_bytes());
+    profile[144..148].copy_from_slice(b"XYZ ");
+    profile[152..164].copy_from_slice(&d50);
+
+    profile
+}
@@ -0,0 +1,184 @@
+#![cfg(any(feature = "log", feature = "tracing"))]
+use std::io::Cursor;
+
+use libpng::{ColorType, StreamDecoder, StreamEncoder, StreamHeader, TextChunk};
+
+/// Image with a CRC error in its tEXt chunk, discarded with a warning.
+fn damaged() -> Vec<u8> {
+    let header =
+        StreamHeader::new(1, 1, ColorType::Gray).with_text(TextChunk::new("Comment", "Damaged"));
+    let mut encoder = StreamEncoder::new(Vec::new(), header).unwrap();
+    encoder.write_row(&[0]).unwrap();
+    let mut encoded = encoder.finish().unwrap();
+
+    // IHDR is followed by tEXt of "Comment\0Damaged"
+    let text_crc = 8 + 25 + 8 + 15;
+    encoded[text_crc] ^= 1;
+
+    encoded
+}
+
+fn decode(encoded: &[u8]) {
+    let mut decoder = StreamDecoder::new(Cursor::new(encoded)).unwrap();
+    let mut row = [0];
+    while decoder.next_row(&mut row).unwrap().is_some() {}
+    assert_eq!(decoder.warnings().len(), 1);
+}
+
+#[cfg(feature = "log")]
+mod log_records {
+    use std::sync::Mutex;
+
+    use log::{Level, Log, Metadata, Record};
+
+    use super::*;
+
+    /// Level, target, chunk and message of a record.
+    type Entry = (Level, String, Option<String>, String);
+
+    static RECORDS: Mutex<Vec<Entry>> = Mutex::new(Vec::new());
+
+    struct Logger;
+
+    impl Log for Logger {
+        fn enabled(&self, _: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record) {
+            let chunk = record
+                .key_values()
+                .get("chunk".into())
+                .map(|chunk| chunk.to_string());
+
+            RECORDS.lock().unwrap().push((
+                record.level(),
+                record.target().to_owned(),
+                chunk,
+                record.args().to_string(),
+            ));
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn test_log_warning() {
+        log::set_logger(&Logger).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+
+        decode(&damaged());
+
+        let records = RECORDS.lock().unwrap();
+        let (level, target, chunk, message) = &records[0];
+        assert_eq!(*level, Level::Warn);
+        assert_eq!(target, "libpng");
+        assert_eq!(chunk.as_deref(), Some("tEXt"));
+        assert!(message.contains("CRC"), "{message}");
+    }
+}
+
+#[cfg(feature = "tracing")]
+mod tracing_events {
+    use std::{
+        fmt::Debug,
+        sync::{Arc, Mutex},
+    };
+
+    use tracing::{
+        field::{Field, Visit},
+        span::{Attributes, Id, Record},
+        Event, Level, Metadata, Subscriber,
+    };
+
+    use super::*;
+
+    /// Level, target and fields of events.
+    type Events = Arc<Mutex<Vec<(Level, String, Vec<(String, String)>)>>>;
+
+    struct Collector(Events);
+
+    struct Fields(Vec<(String, String)>);
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.0.push((field.name().to_owned(), format!("{value:?}")));
+        }
+    }
+
+    impl Subscriber for Collector {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _: &Attributes<'_>) -> Id {
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Fields(Vec::new());
+            event.record(&mut fields);
+
+            let metadata = event.metadata();
+            self.0.lock().unwrap().push((
+                *metadata.level(),
+                metadata.target().to_owned(),
+                fields.0,
+            ));
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    struct Panicking;
+
+    impl Subscriber for Panicking {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _: &Attributes<'_>) -> Id {
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, _: &Event<'_>) {
+            panic!("Subscriber panic");
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn test_tracing_panic() {
+        // Dropped instead of aborting in the libpng warning callback
+        tracing::subscriber::with_default(Panicking, || decode(&damaged()));
+    }
+
+    #[test]
+    fn test_tracing_warning() {
+        let events = Events::default();
+        tracing::subscriber::with_default(Collector(events.clone()), || decode(&damaged()));
+
+        let events = events.lock().unwrap();
+        let (level, target, fields) = &events[0];
+        assert_eq!(*level, Level::WARN);
+        assert_eq!(target, "libpng");
+        assert!(
+            fields.contains(&(String::from("chunk"), String::from("\"tEXt\""))),
+            "{fields:?}"
+        );
+        assert!(!fields.iter().any(|(name, _)| name == "row"), "{fields:?}");
+    }
+}
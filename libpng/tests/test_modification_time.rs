@@ -0,0 +1,193 @@
+use std::{
+    io::Cursor,
+    ops::ControlFlow,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use libpng::{
+    ColorType, Error, ModificationTime, ProgressiveDecoder, StreamDecoder, StreamEncoder,
+    StreamHeader,
+};
+
+#[test]
+fn test_unix_timestamp() {
+    let expected = [
+        (0, ModificationTime::new(1970, 1, 1, 0, 0, 0)),
+        (-1, ModificationTime::new(1969, 12, 31, 23, 59, 59)),
+        (951_782_400, ModificationTime::new(2000, 2, 29, 0, 0, 0)),
+        (
+            1_709_251_199,
+            ModificationTime::new(2024, 2, 29, 23, 59, 59),
+        ),
+        (4_107_542_400, ModificationTime::new(2100, 3, 1, 0, 0, 0)),
+        (-62_167_219_200, ModificationTime::new(0, 1, 1, 0, 0, 0)),
+        (
+            2_005_949_145_599,
+            ModificationTime::new(65535, 12, 31, 23, 59, 59),
+        ),
+    ];
+
+    for (timestamp, time) in expected {
+        assert_eq!(
+            ModificationTime::from_unix_timestamp(timestamp).unwrap(),
+            time
+        );
+        assert_eq!(time.unix_timestamp().unwrap(), timestamp);
+    }
+
+    for timestamp in (-62_167_219_200..2_005_949_145_600).step_by(987_654_321) {
+        let time = ModificationTime::from_unix_timestamp(timestamp).unwrap();
+        assert_eq!(time.unix_timestamp().unwrap(), timestamp);
+    }
+
+    for timestamp in [-62_167_219_201, 2_005_949_145_600, i64::MIN, i64::MAX] {
+        assert!(matches!(
+            ModificationTime::from_unix_timestamp(timestamp),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    // A leap second is the first second of the next minute
+    let leap_second = ModificationTime::new(2016, 12, 31, 23, 59, 60);
+    assert_eq!(
+        leap_second.unix_timestamp().unwrap(),
+        ModificationTime::new(2017, 1, 1, 0, 0, 0)
+            .unix_timestamp()
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_system_time() {
+    let system_time = UNIX_EPOCH + Duration::new(1_700_000_000, 999_999_999);
+    let time = ModificationTime::try_from(system_time).unwrap();
+    assert_eq!(time, ModificationTime::new(2023, 11, 14, 22, 13, 20));
+    assert_eq!(
+        SystemTime::try_from(time).unwrap(),
+        UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+    );
+
+    // Truncated towards the past before the epoch as well
+    let system_time = UNIX_EPOCH - Duration::from_millis(1500);
+    let time = ModificationTime::try_from(system_time).unwrap();
+    assert_eq!(time, ModificationTime::new(1969, 12, 31, 23, 59, 58));
+    assert_eq!(
+        SystemTime::try_from(time).unwrap(),
+        UNIX_EPOCH - Duration::from_secs(2)
+    );
+
+    let now = ModificationTime::now().unwrap();
+    assert!(now.year >= 2024);
+}
+
+#[test]
+fn test_rfc1123() {
+    let expected = [
+        (
+            ModificationTime::new(2024, 1, 1, 9, 5, 0),
+            "1 Jan 2024 09:05:00 +0000",
+        ),
+        (
+            ModificationTime::new(1999, 12, 31, 23, 59, 60),
+            "31 Dec 1999 23:59:60 +0000",
+        ),
+    ];
+
+    for (time, rfc1123) in expected {
+        assert_eq!(time.to_rfc1123().unwrap(), rfc1123);
+    }
+}
+
+#[test]
+fn test_invalid_modification_time() {
+    let invalid = [
+        ModificationTime::new(2023, 2, 29, 0, 0, 0),
+        ModificationTime::new(1900, 2, 29, 0, 0, 0),
+        ModificationTime::new(2024, 4, 31, 0, 0, 0),
+        ModificationTime::new(2024, 0, 1, 0, 0, 0),
+        ModificationTime::new(2024, 13, 1, 0, 0, 0),
+        ModificationTime::new(2024, 1, 0, 0, 0, 0),
+        ModificationTime::new(2024, 1, 1, 24, 0, 0),
+        ModificationTime::new(2024, 1, 1, 0, 60, 0),
+        ModificationTime::new(2024, 1, 1, 0, 0, 61),
+    ];
+
+    for time in invalid {
+        assert!(matches!(time.validate(), Err(Error::InvalidInput(_))));
+        assert!(matches!(time.to_rfc1123(), Err(Error::InvalidInput(_))));
+        assert!(matches!(
+            SystemTime::try_from(time),
+            Err(Error::InvalidInput(_))
+        ));
+
+        let header = StreamHeader::new(1, 1, ColorType::Gray).with_modification_time(time);
+        assert!(matches!(
+            StreamEncoder::new(Vec::new(), header),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    assert!(ModificationTime::new(2000, 2, 29, 0, 0, 0)
+        .validate()
+        .is_ok());
+}
+
+#[test]
+fn test_modification_time_round_trip() {
+    let time = ModificationTime::new(2024, 5, 17, 14, 3, 59);
+
+    let header = StreamHeader::new(1, 1, ColorType::Gray).with_modification_time(time);
+    let mut encoder = StreamEncoder::new(Vec::new(), header).unwrap();
+    encoder.write_row(&[0]).unwrap();
+    let encoded = encoder.finish().unwrap();
+
+    let decoder = StreamDecoder::new(Cursor::new(&encoded)).unwrap();
+    assert_eq!(decoder.modification_time(), Some(time));
+
+    let mut decoder = ProgressiveDecoder::new().unwrap();
+    decoder
+        .feed(&encoded, |_| ControlFlow::Continue(()))
+        .unwrap();
+    assert_eq!(decoder.modification_time(), Some(time));
+
+    let header = StreamHeader::new(1, 1, ColorType::Gray);
+    let mut encoder = StreamEncoder::new(Vec::new(), header).unwrap();
+    encoder.write_row(&[0]).unwrap();
+    let encoded = encoder.finish().unwrap();
+
+    let decoder = StreamDecoder::new(Cursor::new(&encoded)).unwrap();
+    assert_eq!(decoder.modification_time(), None);
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_chrono() {
+    use chrono::{DateTime, TimeZone, Utc};
+
+    let date_time = Utc.with_ymd_and_hms(2024, 5, 17, 14, 3, 59).unwrap();
+    let time = ModificationTime::try_from(date_time).unwrap();
+    assert_eq!(time, ModificationTime::new(2024, 5, 17, 14, 3, 59));
+    assert_eq!(DateTime::<Utc>::try_from(time).unwrap(), date_time);
+
+    let date_time = Utc.with_ymd_and_hms(70000, 1, 1, 0, 0, 0).unwrap();
+    assert!(ModificationTime::try_from(date_time).is_err());
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn test_time() {
+    use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+    let date_time = PrimitiveDateTime::new(
+        Date::from_calendar_date(2024, Month::May, 17).unwrap(),
+        Time::from_hms(14, 3, 59).unwrap(),
+    )
+    .assume_utc();
+    let time = ModificationTime::try_from(date_time).unwrap();
+    assert_eq!(time, ModificationTime::new(2024, 5, 17, 14, 3, 59));
+    assert_eq!(OffsetDateTime::try_from(time).unwrap(), date_time);
+
+    // Converted to UTC
+    let offset = date_time.to_offset(UtcOffset::from_hms(2, 0, 0).unwrap());
+    assert_eq!(ModificationTime::try_from(offset).unwrap(), time);
+}
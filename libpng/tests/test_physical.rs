@@ -0,0 +1,183 @@
+use std::io::Cursor;
+
+use libpng::{
+    Calibration, ColorType, Equation, Error, Offset, OffsetUnit, PhysicalInfo, PhysicalScale,
+    PixelsPerUnit, ResolutionUnit, ScaleUnit, StreamDecoder, StreamEncoder, StreamHeader,
+};
+
+#[test]
+fn test_physical_round_trip() {
+    let physical_info = PhysicalInfo {
+        pixels_per_unit: Some(PixelsPerUnit::from_dpi(300)),
+        offset: Some(Offset {
+            x: -1500,
+            y: 250_000,
+            unit: OffsetUnit::Micrometer,
+        }),
+        scale: Some(PhysicalScale {
+            unit: ScaleUnit::Meter,
+            width: 0.25,
+            height: 1.5e-3,
+        }),
+        calibration: Some(Calibration {
+            purpose: String::from("Elevation"),
+            x0: -500,
+            x1: 9000,
+            equation: Equation::Hyperbolic,
+            units: String::from("m"),
+            parameters: vec![0.0, 1.25, -3.5e-2, 100.0],
+        }),
+    };
+
+    assert_eq!(round_trip(&physical_info), physical_info);
+    assert!(round_trip(&PhysicalInfo::default()).is_empty());
+
+    let aspect_ratio = PhysicalInfo {
+        pixels_per_unit: Some(PixelsPerUnit {
+            x: 2,
+            y: 1,
+            unit: ResolutionUnit::Unknown,
+        }),
+        offset: Some(Offset {
+            x: 10,
+            y: 20,
+            unit: OffsetUnit::Pixel,
+        }),
+        scale: Some(PhysicalScale {
+            unit: ScaleUnit::Radian,
+            width: 1e-5,
+            height: 2e-5,
+        }),
+        calibration: None,
+    };
+
+    assert_eq!(round_trip(&aspect_ratio), aspect_ratio);
+}
+
+#[test]
+fn test_dpi() {
+    let pixels_per_unit = PixelsPerUnit::from_dpi(300);
+
+    assert_eq!(pixels_per_unit.x, 11811);
+    assert_eq!(pixels_per_unit.dpi(), Some((300, 300)));
+
+    let pixels_per_unit = PixelsPerUnit {
+        x: 3780,
+        y: 2835,
+        unit: ResolutionUnit::Meter,
+    };
+    assert_eq!(pixels_per_unit.dpi(), Some((96, 72)));
+
+    let pixels_per_unit = PixelsPerUnit {
+        unit: ResolutionUnit::Unknown,
+        ..pixels_per_unit
+    };
+    assert_eq!(pixels_per_unit.dpi(), None);
+}
+
+#[test]
+fn test_calibration_evaluate() {
+    let calibration = |equation, parameters: &[f64]| Calibration {
+        purpose: String::from("Test"),
+        x0: 0,
+        x1: 100,
+        equation,
+        units: String::new(),
+        parameters: parameters.to_vec(),
+    };
+
+    let linear = calibration(Equation::Linear, &[10.0, 2.0]);
+    assert_eq!(linear.evaluate(0, 8), 10.0);
+    assert_eq!(linear.evaluate(255, 8), 12.0);
+    // Original sample is (128 * 100 + 127) / 255 = 50
+    assert_eq!(linear.evaluate(128, 8), 11.0);
+
+    let base_e = calibration(Equation::BaseE, &[1.0, 2.0, 3.0]);
+    assert!((base_e.evaluate(255, 8) - (1.0 + 2.0 * 3.0f64.exp())).abs() < 1e-12);
+
+    let arbitrary = calibration(Equation::Arbitrary, &[0.0, 1.0, 10.0]);
+    assert!((arbitrary.evaluate(65535, 16) - 10.0).abs() < 1e-12);
+
+    let hyperbolic = calibration(Equation::Hyperbolic, &[5.0, 1.0, 2.0, 100.0]);
+    assert_eq!(hyperbolic.evaluate(1, 1), 5.0);
+    assert!((hyperbolic.evaluate(0, 1) - (5.0 + (-2.0f64).sinh())).abs() < 1e-12);
+}
+
+#[test]
+fn test_invalid_physical_info() {
+    let calibration = Calibration {
+        purpose: String::from("Test"),
+        x0: 0,
+        x1: 1,
+        equation: Equation::Linear,
+        units: String::new(),
+        parameters: vec![0.0, 1.0],
+    };
+
+    let with_scale = |scale| PhysicalInfo {
+        scale: Some(scale),
+        ..PhysicalInfo::default()
+    };
+    let with_calibration = |calibration| PhysicalInfo {
+        calibration: Some(calibration),
+        ..PhysicalInfo::default()
+    };
+
+    let invalid = [
+        with_scale(PhysicalScale {
+            unit: ScaleUnit::Meter,
+            width: 0.0,
+            height: 1.0,
+        }),
+        with_scale(PhysicalScale {
+            unit: ScaleUnit::Meter,
+            width: 1.0,
+            height: f64::INFINITY,
+        }),
+        with_calibration(Calibration {
+            purpose: String::new(),
+            ..calibration.clone()
+        }),
+        with_calibration(Calibration {
+            x1: 0,
+            ..calibration.clone()
+        }),
+        with_calibration(Calibration {
+            units: String::from("°C\0"),
+            ..calibration.clone()
+        }),
+        with_calibration(Calibration {
+            equation: Equation::BaseE,
+            ..calibration.clone()
+        }),
+        with_calibration(Calibration {
+            parameters: vec![0.0, f64::NAN],
+            ..calibration.clone()
+        }),
+    ];
+
+    for physical_info in invalid {
+        assert!(matches!(
+            physical_info.validate(),
+            Err(Error::InvalidInput(_))
+        ));
+
+        let header = StreamHeader::new(1, 1, ColorType::Gray).with_physical_info(physical_info);
+        assert!(matches!(
+            StreamEncoder::new(Vec::new(), header),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+}
+
+fn round_trip(physical_info: &PhysicalInfo) -> PhysicalInfo {
+    let header = StreamHeader::new(1, 1, ColorType::Gray).with_physical_info(physical_info.clone());
+    let mut encoder = StreamEncoder::new(Vec::new(), header).unwrap();
+
+    encoder.write_row(&[0]).unwrap();
+    let encoded = encoder.finish().unwrap();
+
+    StreamDecoder::new(Cursor::new(encoded))
+        .unwrap()
+        .physical_info()
+}
@@ -0,0 +1,390 @@
+use std::{io::Cursor, ops::ControlFlow};
+
+use libpng::{
+    Background, Color, ColorType, Decoder, Error, Histogram, Palette, PixelFormat, PixelInfo,
+    ProgressiveDecoder, SignificantBits, StreamDecoder, StreamEncoder, StreamHeader,
+    SuggestedPalette, SuggestedPaletteEntry, TransparentColor,
+};
+
+#[test]
+fn test_palette_round_trip() {
+    let pixel_info = PixelInfo {
+        palette: Some(
+            Palette::new(vec![
+                Color::new(255, 0, 0),
+                Color::new(0, 255, 0),
+                Color::new(0, 0, 255),
+            ])
+            .with_alpha(vec![0, 128]),
+        ),
+        background: Some(Background::Index(2)),
+        significant_bits: Some(SignificantBits::Rgb {
+            red: 5,
+            green: 6,
+            blue: 5,
+            alpha: None,
+        }),
+        histogram: Some(Histogram {
+            frequencies: vec![1, 2, 1],
+        }),
+        suggested_palettes: vec![SuggestedPalette {
+            name: String::from("Web colors"),
+            depth: 16,
+            entries: vec![SuggestedPaletteEntry {
+                red: 0xffff,
+                green: 0x8000,
+                blue: 0,
+                alpha: 0xffff,
+                frequency: 10,
+            }],
+        }],
+        ..PixelInfo::default()
+    };
+
+    let header = StreamHeader::new(4, 1, ColorType::Palette)
+        .with_bit_depth(2)
+        .with_pixel_info(pixel_info.clone());
+    let mut encoder = StreamEncoder::new(Vec::new(), header).unwrap();
+    // Indices 0, 1, 2, 2 packed into a byte
+    encoder.write_row(&[0b0001_1010]).unwrap();
+    let encoded = encoder.finish().unwrap();
+
+    let decoder = StreamDecoder::new(Cursor::new(&encoded)).unwrap();
+    assert_eq!(decoder.pixel_info(), pixel_info);
+
+    let mut decoder = ProgressiveDecoder::new().unwrap();
+    decoder
+        .feed(&encoded, |_| ControlFlow::Continue(()))
+        .unwrap();
+    assert_eq!(decoder.pixel_info(), pixel_info);
+
+    let image = Decoder::from_slice(&encoded)
+        .unwrap()
+        .with_format(PixelFormat::RGBA)
+        .decode()
+        .unwrap();
+    assert_eq!(
+        image.data,
+        [255, 0, 0, 0, 0, 255, 0, 128, 0, 0, 255, 255, 0, 0, 255, 255]
+    );
+}
+
+#[test]
+fn test_pixel_info_round_trip() {
+    let cases = [
+        (
+            ColorType::Gray,
+            16,
+            PixelInfo {
+                transparent_color: Some(TransparentColor::Gray(1000)),
+                background: Some(Background::Gray(0xffff)),
+                significant_bits: Some(SignificantBits::Gray {
+                    gray: 12,
+                    alpha: None,
+                }),
+                ..PixelInfo::default()
+            },
+        ),
+        (
+            ColorType::Rgb,
+            8,
+            PixelInfo {
+                palette: Some(Palette::new(vec![Color::new(1, 2, 3)])),
+                transparent_color: Some(TransparentColor::Rgb {
+                    red: 1,
+                    green: 2,
+                    blue: 3,
+                }),
+                background: Some(Background::Rgb {
+                    red: 255,
+                    green: 255,
+                    blue: 255,
+                }),
+                ..PixelInfo::default()
+            },
+        ),
+        (
+            ColorType::Rgba,
+            8,
+            PixelInfo {
+                significant_bits: Some(SignificantBits::Rgb {
+                    red: 8,
+                    green: 8,
+                    blue: 8,
+                    alpha: Some(1),
+                }),
+                ..PixelInfo::default()
+            },
+        ),
+        (
+            ColorType::GrayAlpha,
+            8,
+            PixelInfo {
+                significant_bits: Some(SignificantBits::Gray {
+                    gray: 4,
+                    alpha: Some(8),
+                }),
+                suggested_palettes: vec![
+                    SuggestedPalette {
+                        name: String::from("Grays"),
+                        depth: 8,
+                        entries: vec![SuggestedPaletteEntry {
+                            red: 128,
+                            green: 128,
+                            blue: 128,
+                            alpha: 255,
+                            frequency: 0,
+                        }],
+                    },
+                    SuggestedPalette {
+                        name: String::from("Deep grays"),
+                        depth: 16,
+                        entries: vec![SuggestedPaletteEntry {
+                            red: 1000,
+                            green: 1000,
+                            blue: 1000,
+                            alpha: 0xffff,
+                            frequency: 5,
+                        }],
+                    },
+                ],
+                ..PixelInfo::default()
+            },
+        ),
+        (ColorType::Gray, 8, PixelInfo::default()),
+    ];
+
+    for (color_type, bit_depth, pixel_info) in cases {
+        let header = StreamHeader::new(1, 1, color_type)
+            .with_bit_depth(bit_depth)
+            .with_pixel_info(pixel_info.clone());
+        let mut encoder = StreamEncoder::new(Vec::new(), header).unwrap();
+
+        let row = vec![0; encoder.row_bytes()];
+        encoder.write_row(&row).unwrap();
+        let encoded = encoder.finish().unwrap();
+
+        let decoder = StreamDecoder::new(Cursor::new(encoded)).unwrap();
+        assert_eq!(decoder.pixel_info(), pixel_info, "{color_type:?}");
+    }
+}
+
+#[test]
+fn test_invalid_pixel_info() {
+    let palette = || Some(Palette::new(vec![Color::new(0, 0, 0); 4]));
+    let suggested_palette = |name: &str, depth, sample| SuggestedPalette {
+        name: String::from(name),
+        depth,
+        entries: vec![SuggestedPaletteEntry {
+            red: sample,
+            green: 0,
+            blue: 0,
+            alpha: 0,
+            frequency: 0,
+        }],
+    };
+
+    let invalid = [
+        (ColorType::Palette, 8, PixelInfo::default()),
+        (
+            ColorType::Gray,
+            8,
+            PixelInfo {
+                palette: palette(),
+                ..PixelInfo::default()
+            },
+        ),
+        (
+            ColorType::Palette,
+            1,
+            PixelInfo {
+                palette: palette(),
+                ..PixelInfo::default()
+            },
+        ),
+        (
+            ColorType::Palette,
+            8,
+            PixelInfo {
+                palette: Some(Palette::new(vec![])),
+                ..PixelInfo::default()
+            },
+        ),
+        (
+            ColorType::Palette,
+            8,
+            PixelInfo {
+                palette: Some(Palette::new(vec![Color::new(0, 0, 0)]).with_alpha(vec![0, 0])),
+                ..PixelInfo::default()
+            },
+        ),
+        (
+            ColorType::Rgb,
+            8,
+            PixelInfo {
+                palette: Some(Palette::new(vec![Color::new(0, 0, 0)]).with_alpha(vec![0])),
+                ..PixelInfo::default()
+            },
+        ),
+        (
+            ColorType::Gray,
+            4,
+            PixelInfo {
+                transparent_color: Some(TransparentColor::Gray(16)),
+                ..PixelInfo::default()
+            },
+        ),
+        (
+            ColorType::Rgba,
+            8,
+            PixelInfo {
+                transparent_color: Some(TransparentColor::Rgb {
+                    red: 0,
+                    green: 0,
+                    blue: 0,
+                }),
+                ..PixelInfo::default()
+            },
+        ),
+        (
+            ColorType::Palette,
+            8,
+            PixelInfo {
+                palette: palette(),
+                background: Some(Background::Index(4)),
+                ..PixelInfo::default()
+            },
+        ),
+        (
+            ColorType::Rgb,
+            8,
+            PixelInfo {
+                background: Some(Background::Gray(0)),
+                ..PixelInfo::default()
+            },
+        ),
+        (
+            ColorType::Rgb,
+            8,
+            PixelInfo {
+                background: Some(Background::Rgb {
+                    red: 256,
+                    green: 0,
+                    blue: 0,
+                }),
+                ..PixelInfo::default()
+            },
+        ),
+        (
+            ColorType::Gray,
+            8,
+            PixelInfo {
+                significant_bits: Some(SignificantBits::Gray {
+                    gray: 9,
+                    alpha: None,
+                }),
+                ..PixelInfo::default()
+            },
+        ),
+        (
+            ColorType::GrayAlpha,
+            8,
+            PixelInfo {
+                significant_bits: Some(SignificantBits::Gray {
+                    gray: 8,
+                    alpha: None,
+                }),
+                ..PixelInfo::default()
+            },
+        ),
+        (
+            ColorType::Rgb,
+            8,
+            PixelInfo {
+                significant_bits: Some(SignificantBits::Rgb {
+                    red: 0,
+                    green: 8,
+                    blue: 8,
+                    alpha: None,
+                }),
+                ..PixelInfo::default()
+            },
+        ),
+        (
+            ColorType::Palette,
+            8,
+            PixelInfo {
+                palette: palette(),
+                histogram: Some(Histogram {
+                    frequencies: vec![1; 3],
+                }),
+                ..PixelInfo::default()
+            },
+        ),
+        (
+            ColorType::Rgb,
+            8,
+            PixelInfo {
+                suggested_palettes: vec![suggested_palette("Colors", 8, 256)],
+                ..PixelInfo::default()
+            },
+        ),
+        (
+            ColorType::Rgb,
+            8,
+            PixelInfo {
+                suggested_palettes: vec![suggested_palette("Colors", 4, 0)],
+                ..PixelInfo::default()
+            },
+        ),
+        (
+            ColorType::Rgb,
+            8,
+            PixelInfo {
+                suggested_palettes: vec![suggested_palette(" Colors", 8, 0)],
+                ..PixelInfo::default()
+            },
+        ),
+        (
+            ColorType::Rgb,
+            8,
+            PixelInfo {
+                suggested_palettes: vec![SuggestedPalette {
+                    name: String::from("Empty"),
+                    depth: 8,
+                    entries: vec![],
+                }],
+                ..PixelInfo::default()
+            },
+        ),
+        (
+            ColorType::Rgb,
+            8,
+            PixelInfo {
+                suggested_palettes: vec![
+                    suggested_palette("Colors", 8, 0),
+                    suggested_palette("Colors", 16, 0),
+                ],
+                ..PixelInfo::default()
+            },
+        ),
+    ];
+
+    for (color_type, bit_depth, pixel_info) in invalid {
+        assert!(
+            matches!(
+                pixel_info.validate(color_type, bit_depth),
+                Err(Error::InvalidInput(_))
+            ),
+            "{pixel_info:?}"
+        );
+
+        let header = StreamHeader::new(1, 1, color_type)
+            .with_bit_depth(bit_depth)
+            .with_pixel_info(pixel_info);
+        assert!(matches!(
+            StreamEncoder::new(Vec::new(), header),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+}
@@ -0,0 +1,256 @@
+mod common;
+
+use std::{
+    fs::read,
+    ops::ControlFlow,
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+};
+
+use libpng::{
+    ColorType, Decoder, Error, PixelFormat, ProgressiveDecoder, ProgressiveEvent, RowPosition,
+};
+
+use common::{test_image_path, test_png_path};
+
+#[test]
+fn test_progressive_rows() {
+    let path = test_png_path();
+    let data = read(&path).unwrap();
+    let expected = decode(&path);
+
+    let mut decoder = ProgressiveDecoder::new().unwrap();
+    let mut events = Vec::new();
+
+    for chunk in data.chunks(1000) {
+        assert!(!decoder.is_finished());
+
+        decoder
+            .feed(chunk, |event| {
+                match event {
+                    ProgressiveEvent::Header(info) => {
+                        assert_eq!(info.width, 640);
+                        assert_eq!(info.height, 480);
+                        assert_eq!(info.color_type, ColorType::Rgba);
+                        assert!(!info.interlaced);
+                        events.push(String::from("header"));
+                    }
+                    ProgressiveEvent::Row { position, data } => {
+                        assert_eq!(
+                            position,
+                            RowPosition {
+                                pass: 0,
+                                y: position.y
+                            }
+                        );
+                        assert_eq!(data, expected.row(position.y));
+                        events.push(format!("row {}", position.y));
+                    }
+                    ProgressiveEvent::ImageComplete => events.push(String::from("complete")),
+                    ProgressiveEvent::End => events.push(String::from("end")),
+                }
+
+                ControlFlow::Continue(())
+            })
+            .unwrap();
+    }
+
+    assert!(decoder.is_finished());
+    assert_eq!(decoder.info().unwrap().row_bytes, 640 * 4);
+
+    let expected_events: Vec<_> = ["header".to_string()]
+        .into_iter()
+        .chain((0..480).map(|y| format!("row {y}")))
+        .chain(["complete".to_string(), "end".to_string()])
+        .collect();
+
+    assert_eq!(events, expected_events);
+
+    // Trailing data is ignored
+    let consumed = decoder
+        .feed(&data, |_| panic!("No events expected"))
+        .unwrap();
+    assert_eq!(consumed, data.len());
+}
+
+#[test]
+fn test_progressive_interlaced() {
+    let path = test_image_path("test_interlaced.png");
+    let data = read(&path).unwrap();
+    let expected = decode(&path);
+
+    // Byte by byte to split every chunk
+    let (image, passes, complete) = decode_progressively(&data, 1);
+
+    for (y, row) in image.iter().enumerate() {
+        assert_eq!(row, expected.row(y as u32));
+    }
+
+    assert_eq!(passes, (0..7).collect::<Vec<_>>());
+    assert!(complete);
+}
+
+#[test]
+fn test_progressive_pause_after_header() {
+    let data = read(test_png_path()).unwrap();
+    let mut decoder = ProgressiveDecoder::new().unwrap();
+    let mut rows = 0;
+
+    let consumed = decoder
+        .feed(&data, |event| match event {
+            ProgressiveEvent::Header(_) => ControlFlow::Break(()),
+            ProgressiveEvent::Row { .. } => panic!("No rows expected before resuming"),
+            _ => ControlFlow::Continue(()),
+        })
+        .unwrap();
+
+    // Paused right before image data
+    assert!(consumed < data.len());
+    assert_eq!(&data[consumed - 4..consumed], b"IDAT");
+    assert!(decoder.info().is_some());
+
+    let rest = decoder
+        .feed(&data[consumed..], |event| {
+            if let ProgressiveEvent::Row { .. } = event {
+                rows += 1;
+            }
+
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+
+    assert_eq!(rest, data.len() - consumed);
+    assert!(decoder.is_finished());
+    assert_eq!(rows, 480);
+}
+
+#[test]
+fn test_progressive_pause_rows() {
+    let path = test_png_path();
+    let data = read(&path).unwrap();
+    let expected = decode(&path);
+
+    let mut decoder = ProgressiveDecoder::new().unwrap();
+    let mut rows = 0;
+    let mut offset = 0;
+    let mut feeds = 0;
+
+    while !decoder.is_finished() {
+        assert!(offset < data.len());
+
+        offset += decoder
+            .feed(&data[offset..], |event| {
+                if let ProgressiveEvent::Row { position, data } = event {
+                    assert_eq!(position.y, rows);
+                    assert_eq!(data, expected.row(position.y));
+                    rows += 1;
+
+                    return ControlFlow::Break(());
+                }
+
+                ControlFlow::Continue(())
+            })
+            .unwrap();
+
+        feeds += 1;
+    }
+
+    assert_eq!(rows, 480);
+    assert!(feeds > 2);
+}
+
+#[test]
+fn test_progressive_truncated() {
+    let data = read(test_png_path()).unwrap();
+
+    let mut decoder = ProgressiveDecoder::new().unwrap();
+    let mut rows = 0;
+
+    decoder
+        .feed(&data[..data.len() / 2], |event| {
+            if let ProgressiveEvent::Row { .. } = event {
+                rows += 1;
+            }
+
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+
+    assert!(!decoder.is_finished());
+    assert!(decoder.info().is_some());
+    assert!(rows > 0 && rows < 480);
+}
+
+#[test]
+fn test_progressive_invalid_input() {
+    let mut decoder = ProgressiveDecoder::new().unwrap();
+
+    let result = decoder.feed(b"definitely not a PNG file", |_| ControlFlow::Continue(()));
+    assert!(matches!(result, Err(Error::Png(ref message)) if message == "Not a PNG file"));
+
+    // The decoder cannot continue after libpng error
+    let result = decoder.feed(&[], |_| ControlFlow::Continue(()));
+    assert!(matches!(result, Err(Error::Png(_))));
+}
+
+#[test]
+fn test_progressive_handler_panic() {
+    let data = read(test_png_path()).unwrap();
+    let mut decoder = ProgressiveDecoder::new().unwrap();
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        decoder.feed(&data, |event| match event {
+            ProgressiveEvent::Row { .. } => panic!("Handler panic"),
+            _ => ControlFlow::Continue(()),
+        })
+    }));
+
+    let panic = result.unwrap_err();
+    assert_eq!(panic.downcast_ref::<&str>(), Some(&"Handler panic"));
+
+    let result = decoder.feed(&[], |_| ControlFlow::Continue(()));
+    assert!(matches!(result, Err(Error::Png(_))));
+}
+
+/// Returns the final rows, passes of reported rows and whether the image was complete before the end.
+fn decode_progressively(data: &[u8], chunk_size: usize) -> (Vec<Vec<u8>>, Vec<u32>, bool) {
+    let mut decoder = ProgressiveDecoder::new().unwrap();
+    let mut image = Vec::new();
+    let mut passes = Vec::new();
+    let mut complete = false;
+
+    for chunk in data.chunks(chunk_size) {
+        decoder
+            .feed(chunk, |event| {
+                match event {
+                    ProgressiveEvent::Header(info) => {
+                        image = vec![Vec::new(); info.height as usize];
+                    }
+                    ProgressiveEvent::Row { position, data } => {
+                        image[position.y as usize] = data.to_vec();
+
+                        if passes.last() != Some(&position.pass) {
+                            passes.push(position.pass);
+                        }
+                    }
+                    ProgressiveEvent::ImageComplete => complete = true,
+                    ProgressiveEvent::End => assert!(complete),
+                }
+
+                ControlFlow::Continue(())
+            })
+            .unwrap();
+    }
+
+    assert!(decoder.is_finished());
+
+    (image, passes, complete)
+}
+
+fn decode(path: &PathBuf) -> libpng::Image {
+    Decoder::from_path(path)
+        .unwrap()
+        .with_format(PixelFormat::RGBA)
+        .decode()
+        .unwrap()
+}
@@ -0,0 +1,216 @@
+mod common;
+
+use std::{
+    fs::{read, File},
+    io::{self, Cursor, Read},
+    panic::{self, AssertUnwindSafe},
+};
+
+use libpng::{ColorType, Decoder, Error, PixelFormat, RowPosition, StreamDecoder};
+
+use common::{test_image_path, test_png_path};
+
+#[test]
+fn test_stream_info() {
+    let decoder = StreamDecoder::new(File::open(test_png_path()).unwrap()).unwrap();
+    let info = decoder.info();
+
+    assert_eq!(info.width, 640);
+    assert_eq!(info.height, 480);
+    assert_eq!(info.bit_depth, 8);
+    assert_eq!(info.color_type, ColorType::Rgba);
+    assert_eq!(info.channels, 4);
+    assert_eq!(info.row_bytes, 640 * 4);
+    assert!(!info.interlaced);
+    assert_eq!(info.passes, 1);
+}
+
+#[test]
+fn test_stream_rows() {
+    let path = test_png_path();
+    let mut decoder = StreamDecoder::new(File::open(&path).unwrap()).unwrap();
+
+    let expected = Decoder::from_path(&path)
+        .unwrap()
+        .with_format(PixelFormat::RGBA)
+        .decode()
+        .unwrap();
+
+    let mut row = vec![0; decoder.info().row_bytes];
+    let mut y = 0;
+
+    while let Some(position) = decoder.next_row(&mut row).unwrap() {
+        assert_eq!(position, RowPosition { pass: 0, y });
+        assert_eq!(row, expected.row(y));
+        y += 1;
+    }
+
+    assert_eq!(y, 480);
+    assert_eq!(decoder.next_row(&mut row).unwrap(), None);
+}
+
+#[test]
+fn test_stream_interlaced() {
+    let path = test_image_path("test_interlaced.png");
+    let mut decoder = StreamDecoder::new(File::open(&path).unwrap()).unwrap();
+
+    let info = decoder.info().clone();
+    assert!(info.interlaced);
+    assert_eq!(info.passes, 7);
+
+    let expected = Decoder::from_path(&path)
+        .unwrap()
+        .with_format(PixelFormat::RGBA)
+        .decode()
+        .unwrap();
+
+    // Rows are kept between passes to combine pixels
+    let mut image = vec![vec![0; info.row_bytes]; info.height as usize];
+    let mut rows_per_pass = [0; 7];
+
+    while let Some(next) = decoder.next_position() {
+        let position = decoder
+            .next_row(&mut image[next.y as usize])
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(position, next);
+        rows_per_pass[position.pass as usize] += 1;
+    }
+
+    assert_eq!(decoder.next_row(&mut image[0]).unwrap(), None);
+
+    // 37x29 image
+    assert_eq!(rows_per_pass, [4, 4, 4, 8, 7, 15, 14]);
+
+    for (y, row) in image.iter().enumerate() {
+        assert_eq!(row, expected.row(y as u32));
+    }
+}
+
+#[test]
+fn test_stream_into_inner() {
+    let data = read(test_png_path()).unwrap();
+    let mut decoder = StreamDecoder::new(Cursor::new(&data)).unwrap();
+
+    let mut row = vec![0; decoder.info().row_bytes];
+    while decoder.next_row(&mut row).unwrap().is_some() {}
+
+    let reader = decoder.into_inner();
+    assert_eq!(reader.position(), data.len() as u64);
+}
+
+#[test]
+fn test_stream_io_error() {
+    let data = read(test_png_path()).unwrap();
+    let reader = FailingReader {
+        inner: Cursor::new(data),
+        remaining: 1000,
+    };
+
+    let mut decoder = StreamDecoder::new(reader).unwrap();
+    let mut row = vec![0; decoder.info().row_bytes];
+
+    let error = loop {
+        match decoder.next_row(&mut row) {
+            Ok(Some(_)) => continue,
+            Ok(None) => panic!("Decoding must fail"),
+            Err(error) => break error,
+        }
+    };
+
+    assert!(matches!(error, Error::Io(ref error) if error.kind() == io::ErrorKind::Other));
+
+    // The decoder cannot continue after libpng error
+    assert!(matches!(decoder.next_row(&mut row), Err(Error::Png(_))));
+}
+
+#[test]
+fn test_stream_reader_panic() {
+    let data = read(test_png_path()).unwrap();
+
+    // Propagated instead of aborting in the libpng read callback
+    let result = panic::catch_unwind(|| {
+        StreamDecoder::new(PanickingReader {
+            inner: Cursor::new(data[..20].to_vec()),
+        })
+    });
+    let payload = result.err().expect("The reader panic must be propagated");
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"Reader panic"));
+
+    let reader = PanickingReader {
+        inner: Cursor::new(data[..1000].to_vec()),
+    };
+    let mut decoder = StreamDecoder::new(reader).unwrap();
+    let mut row = vec![0; decoder.info().row_bytes];
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        while decoder.next_row(&mut row).unwrap().is_some() {}
+    }));
+    assert!(result.is_err());
+
+    assert!(matches!(decoder.next_row(&mut row), Err(Error::Png(_))));
+}
+
+#[test]
+fn test_stream_truncated() {
+    let data = read(test_png_path()).unwrap();
+
+    let result = StreamDecoder::new(Cursor::new(&data[..20]));
+
+    assert!(
+        matches!(result, Err(Error::Io(ref error)) if error.kind() == io::ErrorKind::UnexpectedEof)
+    );
+}
+
+#[test]
+fn test_stream_invalid_input() {
+    let result = StreamDecoder::new(Cursor::new(b"definitely not a PNG file"));
+
+    assert!(matches!(result, Err(Error::Png(ref message)) if message == "Not a PNG file"));
+}
+
+#[test]
+fn test_stream_short_row_buffer() {
+    let mut decoder = StreamDecoder::new(File::open(test_png_path()).unwrap()).unwrap();
+    let mut row = vec![0; decoder.info().row_bytes - 1];
+
+    assert!(matches!(
+        decoder.next_row(&mut row),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+/// Fails with [io::ErrorKind::Other] after `remaining` bytes.
+struct FailingReader {
+    inner: Cursor<Vec<u8>>,
+    remaining: usize,
+}
+
+impl Read for FailingReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.remaining == 0 {
+            return Err(io::Error::other("Test failure"));
+        }
+
+        let length = buf.len().min(self.remaining);
+        let read = self.inner.read(&mut buf[..length])?;
+        self.remaining -= read;
+
+        Ok(read)
+    }
+}
+
+/// Panics at the end of data.
+struct PanickingReader {
+    inner: Cursor<Vec<u8>>,
+}
+
+impl Read for PanickingReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.inner.read(buf)? {
+            0 => panic!("Reader panic"),
+            read => Ok(read),
+        }
+    }
+}
@@ -0,0 +1,235 @@
+mod common;
+
+use std::{
+    io::{self, Cursor, Write},
+    panic::{self, AssertUnwindSafe},
+};
+
+use libpng::{ColorType, Decoder, Error, PixelFormat, StreamDecoder, StreamEncoder, StreamHeader};
+
+use common::test_png_path;
+
+#[test]
+fn test_stream_round_trip() {
+    let expected = Decoder::from_path(test_png_path())
+        .unwrap()
+        .with_format(PixelFormat::RGBA)
+        .decode()
+        .unwrap();
+
+    let header = StreamHeader::new(expected.width, expected.height, ColorType::Rgba);
+    let mut encoder = StreamEncoder::new(Vec::new(), header.clone()).unwrap();
+
+    assert_eq!(encoder.header(), &header);
+    assert_eq!(encoder.row_bytes(), expected.width as usize * 4);
+
+    for y in 0..expected.height {
+        encoder.write_row(expected.row(y)).unwrap();
+    }
+
+    let encoded = encoder.finish().unwrap();
+    let decoded = Decoder::from_slice(&encoded).unwrap().decode().unwrap();
+
+    assert_eq!(decoded, expected);
+}
+
+#[test]
+fn test_stream_16_bit() {
+    let header = StreamHeader::new(3, 2, ColorType::Gray).with_bit_depth(16);
+    let rows: [[u8; 6]; 2] = [[0, 1, 2, 3, 4, 5], [255, 254, 253, 252, 251, 250]];
+
+    let mut encoder = StreamEncoder::new(Vec::new(), header).unwrap();
+    assert_eq!(encoder.row_bytes(), 6);
+
+    for row in &rows {
+        encoder.write_row(row).unwrap();
+    }
+
+    let encoded = encoder.finish().unwrap();
+    let mut decoder = StreamDecoder::new(Cursor::new(encoded)).unwrap();
+
+    assert_eq!(decoder.info().bit_depth, 16);
+    assert_eq!(decoder.info().color_type, ColorType::Gray);
+
+    let mut row = [0; 6];
+    for expected in &rows {
+        decoder.next_row(&mut row).unwrap().unwrap();
+        assert_eq!(&row, expected);
+    }
+}
+
+#[test]
+fn test_stream_invalid_header() {
+    let header = StreamHeader::new(16, 16, ColorType::Rgb).with_bit_depth(4);
+
+    let result = StreamEncoder::new(Vec::new(), header);
+
+    assert!(matches!(result, Err(Error::Png(_))));
+}
+
+#[test]
+fn test_stream_invalid_rows() {
+    let header = StreamHeader::new(4, 2, ColorType::Gray).with_bit_depth(1);
+    let mut encoder = StreamEncoder::new(Vec::new(), header).unwrap();
+
+    // 4 pixels packed in a single byte
+    assert_eq!(encoder.row_bytes(), 1);
+    assert!(matches!(
+        encoder.write_row(&[0, 0]),
+        Err(Error::InvalidInput(_))
+    ));
+
+    encoder.write_row(&[0b1010_0000]).unwrap();
+
+    let encoder = {
+        let mut encoder = encoder;
+        encoder.write_row(&[0b0101_0000]).unwrap();
+        assert!(matches!(
+            encoder.write_row(&[0]),
+            Err(Error::InvalidInput(_))
+        ));
+        encoder
+    };
+
+    encoder.finish().unwrap();
+}
+
+#[test]
+fn test_stream_finish_early() {
+    let header = StreamHeader::new(4, 2, ColorType::Gray);
+    let mut encoder = StreamEncoder::new(Vec::new(), header).unwrap();
+
+    encoder.write_row(&[0; 4]).unwrap();
+
+    assert!(matches!(encoder.finish(), Err(Error::InvalidInput(_))));
+}
+
+#[test]
+fn test_stream_io_error() {
+    let writer = FailingWriter {
+        remaining: 100,
+        flushes: 0,
+    };
+    let header = StreamHeader::new(256, 256, ColorType::Rgb);
+
+    let mut encoder = StreamEncoder::new(writer, header).unwrap();
+    encoder.set_flush_interval(1);
+
+    let row: Vec<u8> = (0..256 * 3).map(|value| (value * 7 % 251) as u8).collect();
+
+    let error = (0..256)
+        .find_map(|_| encoder.write_row(&row).err())
+        .expect("Writing must fail");
+
+    assert!(matches!(error, Error::Io(ref error) if error.kind() == io::ErrorKind::WriteZero));
+
+    // The encoder cannot continue after libpng error
+    assert!(matches!(encoder.write_row(&row), Err(Error::Png(_))));
+}
+
+#[test]
+fn test_stream_writer_panic() {
+    let header = StreamHeader::new(16, 16, ColorType::Gray);
+
+    // Propagated instead of aborting in the libpng write callback
+    let result = panic::catch_unwind(|| {
+        StreamEncoder::new(
+            PanickingWriter {
+                panic_on_flush: false,
+            },
+            header.clone(),
+        )
+    });
+    let payload = result.err().expect("The writer panic must be propagated");
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"Write panic"));
+
+    let writer = PanickingWriter {
+        panic_on_flush: true,
+    };
+    let mut encoder = StreamEncoder::new(writer, header).unwrap();
+    encoder.set_flush_interval(1);
+
+    // Periodic flushes are called back by libpng
+    let result = panic::catch_unwind(AssertUnwindSafe(|| encoder.write_row(&[0; 16])));
+    let payload = result.expect_err("The flush panic must be propagated");
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"Flush panic"));
+
+    assert!(matches!(encoder.write_row(&[0; 16]), Err(Error::Png(_))));
+}
+
+#[test]
+fn test_stream_flush() {
+    let header = StreamHeader::new(16, 16, ColorType::Gray);
+    let writer = FailingWriter {
+        remaining: usize::MAX,
+        flushes: 0,
+    };
+
+    let mut encoder = StreamEncoder::new(writer, header).unwrap();
+    encoder.set_flush_interval(4);
+
+    for _ in 0..8 {
+        encoder.write_row(&[0; 16]).unwrap();
+    }
+
+    // Right after a periodic flush only the writer is flushed
+    encoder.flush().unwrap();
+
+    for _ in 0..2 {
+        encoder.write_row(&[0; 16]).unwrap();
+    }
+
+    // Restarts the periodic counter
+    encoder.flush().unwrap();
+
+    for _ in 0..6 {
+        encoder.write_row(&[0; 16]).unwrap();
+    }
+
+    let writer = encoder.finish().unwrap();
+
+    // Periodic flushes after rows 4, 8 and 14, explicit ones after rows 8 and 10, and the final one.
+    // libpng skips the periodic flush after the last row
+    assert_eq!(writer.flushes, 6);
+}
+
+/// Accepts `remaining` bytes, then fails with [io::ErrorKind::WriteZero]. Counts flushes.
+struct FailingWriter {
+    remaining: usize,
+    flushes: usize,
+}
+
+impl Write for FailingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let length = buf.len().min(self.remaining);
+        self.remaining -= length;
+
+        Ok(length)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.flushes += 1;
+        Ok(())
+    }
+}
+
+/// Panics on write, or on flush if `panic_on_flush` is set.
+struct PanickingWriter {
+    panic_on_flush: bool,
+}
+
+impl Write for PanickingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.panic_on_flush {
+            true => Ok(buf.len()),
+            false => panic!("Write panic"),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.panic_on_flush {
+            true => panic!("Flush panic"),
+            false => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,146 @@
+mod common;
+
+use std::ops::ControlFlow;
+
+use libpng::{
+    ColorType, Error, ProgressiveDecoder, ReadOptions, StreamHeader, Strictness, TextChunk, Warning,
+};
+
+use common::{decode_rows, encode};
+
+const WIDTH: u32 = 4;
+const HEIGHT: u32 = 4;
+
+fn encode_with_text() -> Vec<u8> {
+    let header = StreamHeader::new(WIDTH, HEIGHT, ColorType::Gray)
+        .with_text(TextChunk::new("Comment", "Damaged"));
+    let rows = expected_rows();
+
+    encode(header, &rows.chunks(WIDTH as usize).collect::<Vec<_>>())
+}
+
+/// Offset and data length of the first chunk named `name`.
+fn find_chunk(encoded: &[u8], name: &[u8; 4]) -> (usize, usize) {
+    let mut offset = 8;
+    loop {
+        let length = u32::from_be_bytes(encoded[offset..offset + 4].try_into().unwrap()) as usize;
+        if &encoded[offset + 4..offset + 8] == name {
+            return (offset, length);
+        }
+        offset += length + 12;
+    }
+}
+
+/// Flips a bit of the CRC of the first chunk named `name`.
+fn damage_crc(encoded: &mut [u8], name: &[u8; 4]) {
+    let (offset, length) = find_chunk(encoded, name);
+    encoded[offset + 8 + length] ^= 1;
+}
+
+/// Decodes via the streaming and progressive decoders, returning rows, text and warnings of both.
+fn decode(encoded: &[u8], strictness: Strictness) -> Result<(Vec<u8>, usize, Vec<Warning>), Error> {
+    let (rows, decoder) = decode_rows(encoded, ReadOptions::new().with_strictness(strictness))?;
+    let warnings = decoder.warnings();
+
+    let options = ReadOptions::new().with_strictness(strictness);
+    let mut progressive = ProgressiveDecoder::with_options(options)?;
+    progressive.feed(encoded, |_| ControlFlow::Continue(()))?;
+    assert!(progressive.is_finished());
+    assert_eq!(progressive.warnings(), warnings);
+    assert_eq!(progressive.text(), decoder.text());
+
+    Ok((rows, decoder.text().len(), warnings))
+}
+
+fn expected_rows() -> Vec<u8> {
+    (0..HEIGHT as u8)
+        .flat_map(|row| [row; WIDTH as usize])
+        .collect()
+}
+
+#[test]
+fn test_intact() {
+    let encoded = encode_with_text();
+
+    for strictness in [Strictness::Strict, Strictness::Lenient, Strictness::Recover] {
+        let (rows, text_count, warnings) = decode(&encoded, strictness).unwrap();
+        assert_eq!(rows, expected_rows());
+        assert_eq!(text_count, 1);
+        assert_eq!(warnings, []);
+    }
+}
+
+#[test]
+fn test_ancillary_crc_error() {
+    let mut encoded = encode_with_text();
+    damage_crc(&mut encoded, b"tEXt");
+
+    assert!(matches!(
+        decode(&encoded, Strictness::Strict),
+        Err(Error::Png(_))
+    ));
+
+    // The chunk is discarded
+    for strictness in [Strictness::Lenient, Strictness::Recover] {
+        let (rows, text_count, warnings) = decode(&encoded, strictness).unwrap();
+        assert_eq!(rows, expected_rows());
+        assert_eq!(text_count, 0);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].chunk, Some("tEXt".parse().unwrap()));
+        assert!(warnings[0].message.contains("CRC"), "{warnings:?}");
+    }
+}
+
+#[test]
+fn test_critical_crc_error() {
+    let mut encoded = encode_with_text();
+    damage_crc(&mut encoded, b"IDAT");
+
+    for strictness in [Strictness::Strict, Strictness::Lenient] {
+        assert!(matches!(decode(&encoded, strictness), Err(Error::Png(_))));
+    }
+
+    let (rows, _, warnings) = decode(&encoded, Strictness::Recover).unwrap();
+    assert_eq!(rows, expected_rows());
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].chunk, Some("IDAT".parse().unwrap()));
+}
+
+#[test]
+fn test_default_strictness() {
+    assert_eq!(Strictness::default(), Strictness::Lenient);
+}
+
+#[test]
+fn test_adler32_error() {
+    let mut encoded = encode_with_text();
+    // The last byte of the Adler-32 checksum ends the only IDAT chunk, its CRC is updated
+    let (offset, length) = find_chunk(&encoded, b"IDAT");
+    let crc_offset = offset + 8 + length;
+    encoded[crc_offset - 1] ^= 1;
+    let crc = crc32(&encoded[offset + 4..crc_offset]);
+    encoded[crc_offset..crc_offset + 4].copy_from_slice(&crc.to_be_bytes());
+
+    for strictness in [Strictness::Strict, Strictness::Lenient] {
+        assert!(matches!(
+            decode(&encoded, strictness),
+            Err(Error::Png(message)) if message.contains("incorrect data check")
+        ));
+    }
+
+    let (rows, _, warnings) = decode(&encoded, Strictness::Recover).unwrap();
+    assert_eq!(rows, expected_rows());
+    assert_eq!(warnings, []);
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+
+    !crc
+}
@@ -0,0 +1,159 @@
+mod common;
+
+use std::{fs::File, io::Cursor, ops::ControlFlow};
+
+use libpng::{
+    ColorType, Error, ProgressiveDecoder, StreamDecoder, StreamEncoder, StreamHeader, TextChunk,
+    TEXT_COMPRESSION_THRESHOLD,
+};
+
+use common::test_png_path;
+
+#[test]
+fn test_text_after_image_data() {
+    let mut decoder = StreamDecoder::new(File::open(test_png_path()).unwrap()).unwrap();
+    assert_eq!(decoder.text(), []);
+
+    let mut row = vec![0; decoder.info().row_bytes];
+    while decoder.next_row(&mut row).unwrap().is_some() {}
+
+    assert_eq!(
+        decoder.text(),
+        [
+            TextChunk::Text {
+                keyword: String::from("date:create"),
+                text: String::from("2022-06-14T02:42:30+00:00"),
+            },
+            TextChunk::Text {
+                keyword: String::from("date:modify"),
+                text: String::from("2022-06-14T02:42:30+00:00"),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_text_round_trip() {
+    let before = [
+        TextChunk::Text {
+            keyword: String::from("Title"),
+            text: String::from("Crème brûlée"),
+        },
+        TextChunk::CompressedText {
+            keyword: String::from("Description"),
+            text: "Long text. ".repeat(200),
+        },
+        TextChunk::International {
+            keyword: String::from("Author"),
+            language: String::from("ja-JP"),
+            translated_keyword: String::from("著者"),
+            text: String::from("山田太郎"),
+            compressed: false,
+        },
+    ];
+    let after = TextChunk::International {
+        keyword: String::from("Comment"),
+        language: String::new(),
+        translated_keyword: String::new(),
+        text: "Generated ✓ ".repeat(100),
+        compressed: true,
+    };
+
+    let header = before
+        .iter()
+        .cloned()
+        .fold(StreamHeader::new(2, 2, ColorType::Gray), |header, chunk| {
+            header.with_text(chunk)
+        });
+
+    let mut encoder = StreamEncoder::new(Vec::new(), header).unwrap();
+    encoder.write_row(&[0, 1]).unwrap();
+    encoder.write_row(&[2, 3]).unwrap();
+    encoder.add_text(after.clone()).unwrap();
+    let encoded = encoder.finish().unwrap();
+
+    let mut decoder = StreamDecoder::new(Cursor::new(&encoded)).unwrap();
+    assert_eq!(decoder.text(), before);
+
+    let mut row = [0; 2];
+    while decoder.next_row(&mut row).unwrap().is_some() {}
+
+    let mut expected = before.to_vec();
+    expected.push(after);
+    assert_eq!(decoder.text(), expected);
+
+    let mut decoder = ProgressiveDecoder::new().unwrap();
+    decoder
+        .feed(&encoded, |_| ControlFlow::Continue(()))
+        .unwrap();
+
+    assert!(decoder.is_finished());
+    assert_eq!(decoder.text(), expected);
+}
+
+#[test]
+fn test_text_new() {
+    assert!(matches!(
+        TextChunk::new("Software", "libpng"),
+        TextChunk::Text { .. }
+    ));
+    assert!(matches!(
+        TextChunk::new("Comment", "a".repeat(TEXT_COMPRESSION_THRESHOLD)),
+        TextChunk::CompressedText { .. }
+    ));
+    assert!(matches!(
+        TextChunk::new("Comment", "Привет"),
+        TextChunk::International {
+            compressed: false,
+            ..
+        }
+    ));
+
+    let chunk = TextChunk::new("Comment", "text");
+    assert_eq!(chunk.keyword(), "Comment");
+    assert_eq!(chunk.text(), "text");
+}
+
+#[test]
+fn test_invalid_text() {
+    let invalid = [
+        TextChunk::new("", "text"),
+        TextChunk::new("k".repeat(80), "text"),
+        TextChunk::new(" Title", "text"),
+        TextChunk::new("Title ", "text"),
+        TextChunk::new("Image  title", "text"),
+        TextChunk::new("Title\n", "text"),
+        TextChunk::new("Заголовок", "text"),
+        TextChunk::new("Title", "te\0xt"),
+        TextChunk::Text {
+            keyword: String::from("Title"),
+            text: String::from("Заголовок"),
+        },
+        TextChunk::International {
+            keyword: String::from("Title"),
+            language: String::from("en US"),
+            translated_keyword: String::new(),
+            text: String::from("text"),
+            compressed: false,
+        },
+    ];
+
+    for chunk in invalid {
+        assert!(matches!(chunk.validate(), Err(Error::InvalidInput(_))));
+
+        let header = StreamHeader::new(1, 1, ColorType::Gray).with_text(chunk.clone());
+        assert!(matches!(
+            StreamEncoder::new(Vec::new(), header),
+            Err(Error::InvalidInput(_))
+        ));
+
+        let header = StreamHeader::new(1, 1, ColorType::Gray);
+        let mut encoder = StreamEncoder::new(Vec::new(), header).unwrap();
+        assert!(matches!(
+            encoder.add_text(chunk),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    assert!(TextChunk::new("k".repeat(79), "text").validate().is_ok());
+}
@@ -0,0 +1,302 @@
+mod common;
+
+use std::{fs::File, io::Cursor, ops::ControlFlow};
+
+use libpng::{
+    BackgroundColor, BackgroundGamma, Color, ColorType, Decoder, Error, FillerPosition, Gamma,
+    PixelFormat, ProgressiveDecoder, ProgressiveEvent, StreamDecoder, StreamHeader, Transforms,
+};
+
+use common::{encode, test_image_path, test_png_path};
+
+#[test]
+fn test_strip_16() {
+    let encoded = encode(
+        StreamHeader::new(2, 1, ColorType::Rgb).with_bit_depth(16),
+        &[&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]],
+    );
+
+    let transforms = Transforms::new().strip_16();
+    let mut decoder = StreamDecoder::with_transforms(Cursor::new(encoded), transforms).unwrap();
+
+    assert_eq!(decoder.info().bit_depth, 8);
+    assert_eq!(decoder.info().row_bytes, 6);
+
+    let mut row = [0; 6];
+    decoder.next_row(&mut row).unwrap().unwrap();
+    assert_eq!(row, [1, 3, 5, 7, 9, 11]);
+}
+
+#[test]
+fn test_swap() {
+    let encoded = encode(
+        StreamHeader::new(2, 1, ColorType::Gray).with_bit_depth(16),
+        &[&[1, 2, 3, 4]],
+    );
+
+    let transforms = Transforms::new().swap();
+    let mut decoder = StreamDecoder::with_transforms(Cursor::new(encoded), transforms).unwrap();
+
+    let mut row = [0; 4];
+    decoder.next_row(&mut row).unwrap().unwrap();
+    assert_eq!(row, [2, 1, 4, 3]);
+}
+
+#[test]
+fn test_expand_gray() {
+    // 2-bit samples 0, 1, 2, 3
+    let encoded = encode(
+        StreamHeader::new(4, 1, ColorType::Gray).with_bit_depth(2),
+        &[&[0b00_01_10_11]],
+    );
+
+    let transforms = Transforms::new().expand();
+    let mut decoder = StreamDecoder::with_transforms(Cursor::new(encoded), transforms).unwrap();
+
+    assert_eq!(decoder.info().bit_depth, 8);
+    assert_eq!(decoder.info().row_bytes, 4);
+
+    let mut row = [0; 4];
+    decoder.next_row(&mut row).unwrap().unwrap();
+    assert_eq!(row, [0, 85, 170, 255]);
+}
+
+#[test]
+fn test_gray_to_rgb_add_alpha() {
+    let encoded = encode(StreamHeader::new(2, 1, ColorType::Gray), &[&[10, 20]]);
+
+    let transforms = Transforms::new()
+        .gray_to_rgb()
+        .add_alpha(0xff, FillerPosition::After);
+    let mut decoder = StreamDecoder::with_transforms(Cursor::new(encoded), transforms).unwrap();
+
+    let info = decoder.info().clone();
+    assert_eq!(info.color_type, ColorType::Rgba);
+    assert_eq!(info.channels, 4);
+    assert_eq!(info.row_bytes, 8);
+
+    let mut row = [0; 8];
+    decoder.next_row(&mut row).unwrap().unwrap();
+    assert_eq!(row, [10, 10, 10, 255, 20, 20, 20, 255]);
+}
+
+#[test]
+fn test_background() {
+    let encoded = encode(
+        StreamHeader::new(2, 1, ColorType::GrayAlpha),
+        &[&[10, 255, 10, 0]],
+    );
+
+    let transforms =
+        Transforms::new().background(BackgroundColor::Gray(200), BackgroundGamma::File);
+    let mut decoder = StreamDecoder::with_transforms(Cursor::new(encoded), transforms).unwrap();
+
+    assert_eq!(decoder.info().color_type, ColorType::Gray);
+
+    let mut row = [0; 2];
+    decoder.next_row(&mut row).unwrap().unwrap();
+    assert_eq!(row, [10, 200]);
+}
+
+#[test]
+fn test_quantize() {
+    let encoded = encode(
+        StreamHeader::new(4, 1, ColorType::Rgba),
+        &[&[
+            10, 10, 10, 255, 250, 250, 250, 255, 200, 20, 20, 0, 0, 0, 0, 255,
+        ]],
+    );
+    let palette = vec![
+        Color::new(0, 0, 0),
+        Color::new(255, 255, 255),
+        Color::new(255, 0, 0),
+    ];
+
+    let transforms = Transforms::new().quantize(palette.clone(), 3);
+    let mut decoder =
+        StreamDecoder::with_transforms(Cursor::new(&encoded), transforms.clone()).unwrap();
+
+    let info = decoder.info().clone();
+    assert_eq!(info.color_type, ColorType::Palette);
+    assert_eq!(info.channels, 1);
+    assert_eq!(info.row_bytes, 4);
+    assert_eq!(info.palette, Some(palette));
+
+    let mut row = [0; 4];
+    decoder.next_row(&mut row).unwrap().unwrap();
+    assert_eq!(row, [0, 1, 2, 0]);
+
+    let mut progressive = ProgressiveDecoder::new()
+        .unwrap()
+        .with_transforms(transforms);
+    let mut rows = vec![];
+    progressive
+        .feed(&encoded, |event| {
+            if let ProgressiveEvent::Row { data, .. } = event {
+                rows.push(data.to_vec());
+            }
+
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+
+    assert_eq!(progressive.info(), Some(&info));
+    assert_eq!(rows, [row]);
+}
+
+#[test]
+fn test_quantize_maximum_colors() {
+    let encoded = encode(
+        StreamHeader::new(4, 1, ColorType::Rgb),
+        &[&[0, 0, 0, 10, 10, 10, 240, 240, 240, 255, 255, 255]],
+    );
+    let palette = vec![
+        Color::new(0, 0, 0),
+        Color::new(8, 8, 8),
+        Color::new(248, 248, 248),
+        Color::new(255, 255, 255),
+    ];
+
+    let transforms = Transforms::new().quantize(palette.clone(), 2);
+    let mut decoder = StreamDecoder::with_transforms(Cursor::new(encoded), transforms).unwrap();
+
+    // libpng drops one of the closest colors in each pair, rows index the remaining ones
+    let reduced = decoder.info().palette.clone().unwrap();
+    assert_eq!(reduced.len(), 2);
+    assert!(reduced.iter().all(|color| palette.contains(color)));
+
+    let mut row = [0; 4];
+    decoder.next_row(&mut row).unwrap().unwrap();
+    let reds: Vec<u8> = row
+        .iter()
+        .map(|index| reduced[*index as usize].red)
+        .collect();
+    assert!(reds[0] < 128 && reds[1] < 128, "{reds:?}");
+    assert!(reds[2] >= 128 && reds[3] >= 128, "{reds:?}");
+}
+
+#[test]
+fn test_bgr() {
+    let path = test_png_path();
+    let expected = Decoder::from_path(&path)
+        .unwrap()
+        .with_format(PixelFormat::BGRA)
+        .decode()
+        .unwrap();
+
+    let transforms = Transforms::new().bgr();
+    let mut decoder =
+        StreamDecoder::with_transforms(File::open(&path).unwrap(), transforms).unwrap();
+
+    let mut row = vec![0; decoder.info().row_bytes];
+    while let Some(position) = decoder.next_row(&mut row).unwrap() {
+        assert_eq!(row, expected.row(position.y));
+    }
+}
+
+#[test]
+fn test_invalid_transforms() {
+    let invalid = [
+        Transforms::new().strip_16().scale_16(),
+        Transforms::new().gray_to_rgb().rgb_to_gray(),
+        Transforms::new()
+            .strip_alpha()
+            .add_alpha(0, FillerPosition::Before),
+        Transforms::new().rgb_to_gray_with_coefficients(0.7, 0.7),
+        Transforms::new().gamma(Gamma::Value(0.0), Gamma::Srgb),
+        Transforms::new().quantize(vec![], 1),
+        Transforms::new().quantize(vec![Color::new(0, 0, 0)], 0),
+        Transforms::new()
+            .quantize(vec![Color::new(0, 0, 0)], 1)
+            .add_alpha(0xff, FillerPosition::After),
+    ];
+
+    for transforms in invalid {
+        assert!(matches!(transforms.validate(), Err(Error::InvalidInput(_))));
+
+        let file = File::open(test_png_path()).unwrap();
+        assert!(matches!(
+            StreamDecoder::with_transforms(file, transforms),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+}
+
+#[test]
+fn test_invalid_background() {
+    // RGBA image needs an RGB background
+    let transforms =
+        Transforms::new().background(BackgroundColor::Gray(0), BackgroundGamma::Screen);
+    assert!(transforms.validate().is_ok());
+
+    let file = File::open(test_png_path()).unwrap();
+    assert!(matches!(
+        StreamDecoder::with_transforms(file, transforms),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+#[test]
+fn test_invalid_quantize() {
+    let palette = vec![Color::new(0, 0, 0)];
+
+    // Gray rows need gray_to_rgb, 16-bit ones scale_16 or strip_16
+    let gray = encode(StreamHeader::new(1, 1, ColorType::Gray), &[&[0]]);
+    let rgb_16 = encode(
+        StreamHeader::new(1, 1, ColorType::Rgb).with_bit_depth(16),
+        &[&[0; 6]],
+    );
+
+    for encoded in [&gray, &rgb_16] {
+        let transforms = Transforms::new().quantize(palette.clone(), 1);
+        assert!(matches!(
+            StreamDecoder::with_transforms(Cursor::new(encoded), transforms),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    let transforms = Transforms::new().gray_to_rgb().quantize(palette.clone(), 1);
+    assert!(StreamDecoder::with_transforms(Cursor::new(&gray), transforms).is_ok());
+
+    let transforms = Transforms::new().scale_16().quantize(palette, 1);
+    assert!(StreamDecoder::with_transforms(Cursor::new(&rgb_16), transforms).is_ok());
+}
+
+#[test]
+fn test_progressive_transforms() {
+    let path = test_image_path("test_interlaced.png");
+    let expected = Decoder::from_path(&path)
+        .unwrap()
+        .with_format(PixelFormat::RGBA)
+        .decode()
+        .unwrap();
+
+    let mut decoder = ProgressiveDecoder::new()
+        .unwrap()
+        .with_transforms(Transforms::new().strip_alpha());
+    let mut rows = vec![Vec::new(); expected.height as usize];
+
+    decoder
+        .feed(&std::fs::read(&path).unwrap(), |event| {
+            if let ProgressiveEvent::Row { position, data } = event {
+                rows[position.y as usize] = data.to_vec();
+            }
+
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+
+    assert!(decoder.is_finished());
+    assert_eq!(decoder.info().unwrap().color_type, ColorType::Rgb);
+
+    for (y, row) in rows.iter().enumerate() {
+        let expected: Vec<u8> = expected
+            .row(y as u32)
+            .chunks(4)
+            .flat_map(|pixel| &pixel[..3])
+            .copied()
+            .collect();
+
+        assert_eq!(row, &expected);
+    }
+}
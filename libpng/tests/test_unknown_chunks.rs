@@ -0,0 +1,195 @@
+mod common;
+
+use std::{
+    io::Cursor,
+    ops::ControlFlow,
+    panic::{self, AssertUnwindSafe},
+    slice,
+    sync::{Arc, Mutex},
+};
+
+use libpng::{
+    ChunkLocation, ChunkName, ColorType, Error, KeepPolicy, ProgressiveDecoder, ReadOptions,
+    StreamDecoder, StreamEncoder, StreamHeader, TextChunk, UnknownChunk,
+};
+
+use common::{decode_rows, encode_blank};
+
+fn name(name: &str) -> ChunkName {
+    name.parse().unwrap()
+}
+
+fn chunk(chunk_name: &str, data: &[u8], location: ChunkLocation) -> UnknownChunk {
+    UnknownChunk::new(name(chunk_name), data.to_vec(), location)
+}
+
+fn decode(encoded: &[u8], options: ReadOptions) -> Result<Vec<UnknownChunk>, Error> {
+    let (_, decoder) = decode_rows(encoded, options)?;
+
+    Ok(decoder.unknown_chunks())
+}
+
+#[test]
+fn test_chunk_name() {
+    let expected = [
+        ("prVt", false, false, true),
+        ("tEXt", false, true, true),
+        ("IDAT", true, true, false),
+        ("CrIT", true, false, false),
+    ];
+
+    for (chunk_name, critical, public, safe_to_copy) in expected {
+        let name = name(chunk_name);
+        assert_eq!(name.bytes(), chunk_name.as_bytes());
+        assert_eq!(name.to_string(), chunk_name);
+        assert_eq!(name.is_critical(), critical, "{name:?}");
+        assert_eq!(name.is_public(), public, "{name:?}");
+        assert_eq!(name.is_safe_to_copy(), safe_to_copy, "{name:?}");
+    }
+
+    assert!(name("IEND").is_structural());
+    assert!(!name("prVt").is_structural());
+
+    for invalid in ["prvt", "pr1t", "prV", "prVtx", "prÄt", ""] {
+        assert!(
+            matches!(invalid.parse::<ChunkName>(), Err(Error::InvalidInput(_))),
+            "{invalid}"
+        );
+    }
+    assert!(ChunkName::new(*b"pr\0t").is_err());
+}
+
+#[test]
+fn test_unknown_chunks_round_trip() {
+    let before = chunk("prVt", b"private", ChunkLocation::BeforePlte);
+    let unsafe_to_copy = chunk("edIT", &[1, 2, 3], ChunkLocation::BeforePlte);
+    let after = chunk("afTr", b"", ChunkLocation::AfterIdat);
+
+    let header = StreamHeader::new(1, 1, ColorType::Gray)
+        .with_unknown_chunk(before.clone())
+        .with_unknown_chunk(unsafe_to_copy.clone())
+        .with_unknown_chunk(after.clone());
+    let encoded = encode_blank(header);
+
+    assert_eq!(decode(&encoded, ReadOptions::new()).unwrap(), []);
+
+    // Ancillary chunks are kept whether safe to copy or not
+    for keep in [KeepPolicy::IfSafe, KeepPolicy::Always] {
+        let options = ReadOptions::new().with_unknown_chunks(keep);
+        assert_eq!(
+            decode(&encoded, options).unwrap(),
+            [before.clone(), unsafe_to_copy.clone(), after.clone()]
+        );
+    }
+
+    // A policy of a chunk overrides the default one
+    let options = ReadOptions::new()
+        .with_unknown_chunks(KeepPolicy::Always)
+        .with_chunk_policy(name("prVt"), KeepPolicy::Never);
+    assert_eq!(
+        decode(&encoded, options).unwrap(),
+        [unsafe_to_copy.clone(), after.clone()]
+    );
+
+    let options = ReadOptions::new().with_chunk_policy(name("afTr"), KeepPolicy::Always);
+    let mut decoder = ProgressiveDecoder::with_options(options).unwrap();
+    decoder
+        .feed(&encoded, |_| ControlFlow::Continue(()))
+        .unwrap();
+    assert!(decoder.is_finished());
+    assert_eq!(decoder.unknown_chunks(), [after]);
+}
+
+#[test]
+fn test_known_chunk_as_unknown() {
+    let header =
+        StreamHeader::new(1, 1, ColorType::Gray).with_text(TextChunk::new("Comment", "Kept raw"));
+    let encoded = encode_blank(header);
+
+    let options = ReadOptions::new().with_chunk_policy(name("tEXt"), KeepPolicy::Always);
+    let decoder =
+        StreamDecoder::with_options(Cursor::new(&encoded), Default::default(), options).unwrap();
+    assert_eq!(decoder.text(), []);
+    assert_eq!(
+        decoder.unknown_chunks(),
+        [chunk(
+            "tEXt",
+            b"Comment\0Kept raw",
+            ChunkLocation::BeforePlte
+        )]
+    );
+}
+
+#[test]
+fn test_user_chunk_callback() {
+    let critical = chunk("CrIT", b"critical", ChunkLocation::BeforePlte);
+    let header = StreamHeader::new(1, 1, ColorType::Gray).with_unknown_chunk(critical.clone());
+    let encoded = encode_blank(header);
+
+    // Unknown critical chunks fail decoding unless handled or kept
+    assert!(matches!(
+        decode(&encoded, ReadOptions::new()),
+        Err(Error::Png(_))
+    ));
+
+    let options = ReadOptions::new().with_chunk_policy(name("CrIT"), KeepPolicy::Always);
+    assert_eq!(
+        decode(&encoded, options).unwrap(),
+        slice::from_ref(&critical)
+    );
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let options = ReadOptions::new().with_user_chunk_callback({
+        let seen = seen.clone();
+        move |chunk| {
+            seen.lock().unwrap().push(chunk.clone());
+            Ok(true)
+        }
+    });
+    assert_eq!(decode(&encoded, options).unwrap(), []);
+    assert_eq!(*seen.lock().unwrap(), [critical]);
+
+    let options = ReadOptions::new().with_user_chunk_callback(|chunk| {
+        Err(Error::InvalidInput(format!("Unexpected {}", chunk.name)))
+    });
+    assert!(matches!(
+        decode(&encoded, options),
+        Err(Error::InvalidInput(message)) if message == "Unexpected CrIT"
+    ));
+
+    let options = ReadOptions::new()
+        .with_user_chunk_callback(|_| Err(Error::InvalidInput(String::from("Progressive"))));
+    let mut decoder = ProgressiveDecoder::with_options(options).unwrap();
+    assert!(matches!(
+        decoder.feed(&encoded, |_| ControlFlow::Continue(())),
+        Err(Error::InvalidInput(_))
+    ));
+
+    let options = ReadOptions::new().with_user_chunk_callback(|_| panic!("Callback panic"));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| decode(&encoded, options)));
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_invalid_unknown_chunks() {
+    for structural in ["IHDR", "PLTE", "IDAT", "IEND"] {
+        let options = ReadOptions::new().with_chunk_policy(name(structural), KeepPolicy::Always);
+        assert!(matches!(options.validate(), Err(Error::InvalidInput(_))));
+        assert!(matches!(
+            ProgressiveDecoder::with_options(options),
+            Err(Error::InvalidInput(_))
+        ));
+
+        let unknown_chunk = chunk(structural, &[], ChunkLocation::BeforeIdat);
+        assert!(matches!(
+            unknown_chunk.validate(),
+            Err(Error::InvalidInput(_))
+        ));
+
+        let header = StreamHeader::new(1, 1, ColorType::Gray).with_unknown_chunk(unknown_chunk);
+        assert!(matches!(
+            StreamEncoder::new(Vec::new(), header),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+}
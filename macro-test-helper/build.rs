@@ -0,0 +1,14 @@
+use std::env::var;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=c_macro_shim.c");
+
+    // Exported by 'libpng-vendored-sys' via its `links` key
+    let include_dir = var("DEP_PNG_INCLUDE").unwrap();
+
+    cc::Build::new()
+        .file("c_macro_shim.c")
+        .include(include_dir)
+        .compile("png_rs_test_macro_shim");
+}
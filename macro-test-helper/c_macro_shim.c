@@ -0,0 +1,68 @@
+/* Evaluates the real libpng macros for comparison with Rust translations in 'test_c_macro_helpers.rs'.
+ * Compiled by 'build.rs' of 'macro-test-helper', a dev-dependency of 'libpng-vendored-sys'.
+ */
+
+#include "png.h"
+
+/* From 'pngpriv.h', not available in public headers. */
+#define PNG_ROWBYTES(pixel_bits, width) \
+    ((pixel_bits) >= 8 ? \
+    ((size_t)(width) * (((size_t)(pixel_bits)) >> 3)) : \
+    (( ((size_t)(width) * ((size_t)(pixel_bits))) + 7) >> 3) )
+
+png_uint_32 png_rs_test_uint_31_max(void) { return PNG_UINT_31_MAX; }
+png_uint_32 png_rs_test_uint_32_max(void) { return PNG_UINT_32_MAX; }
+size_t png_rs_test_size_max(void) { return PNG_SIZE_MAX; }
+png_fixed_point png_rs_test_fp_max(void) { return PNG_FP_MAX; }
+png_fixed_point png_rs_test_fp_min(void) { return PNG_FP_MIN; }
+size_t png_rs_test_zbuf_size(void) { return PNG_ZBUF_SIZE; }
+
+int png_rs_test_image_failed(const png_image *image) { return PNG_IMAGE_FAILED(*image); }
+size_t png_rs_test_image_sample_channels(png_uint_32 fmt) { return PNG_IMAGE_SAMPLE_CHANNELS(fmt); }
+size_t png_rs_test_image_sample_component_size(png_uint_32 fmt) { return PNG_IMAGE_SAMPLE_COMPONENT_SIZE(fmt); }
+size_t png_rs_test_image_sample_size(png_uint_32 fmt) { return PNG_IMAGE_SAMPLE_SIZE(fmt); }
+size_t png_rs_test_image_maximum_colormap_components(png_uint_32 fmt) { return PNG_IMAGE_MAXIMUM_COLORMAP_COMPONENTS(fmt); }
+size_t png_rs_test_image_pixel_channels(png_uint_32 fmt) { return PNG_IMAGE_PIXEL_CHANNELS(fmt); }
+size_t png_rs_test_image_pixel_component_size(png_uint_32 fmt) { return PNG_IMAGE_PIXEL_COMPONENT_SIZE(fmt); }
+size_t png_rs_test_image_pixel_size(png_uint_32 fmt) { return PNG_IMAGE_PIXEL_SIZE(fmt); }
+size_t png_rs_test_image_row_stride(const png_image *image) { return PNG_IMAGE_ROW_STRIDE(*image); }
+size_t png_rs_test_image_buffer_size(const png_image *image, size_t row_stride) { return PNG_IMAGE_BUFFER_SIZE(*image, row_stride); }
+size_t png_rs_test_image_size(const png_image *image) { return PNG_IMAGE_SIZE(*image); }
+size_t png_rs_test_image_colormap_size(const png_image *image) { return PNG_IMAGE_COLORMAP_SIZE(*image); }
+size_t png_rs_test_image_data_size(const png_image *image) { return PNG_IMAGE_DATA_SIZE(*image); }
+size_t png_rs_test_zlib_max_size(png_alloc_size_t b) { return PNG_ZLIB_MAX_SIZE(b); }
+size_t png_rs_test_image_compressed_size_max(const png_image *image) { return PNG_IMAGE_COMPRESSED_SIZE_MAX(*image); }
+size_t png_rs_test_image_png_size_max(const png_image *image) { return PNG_IMAGE_PNG_SIZE_MAX(*image); }
+
+unsigned int png_rs_test_pass_start_row(int pass) { return PNG_PASS_START_ROW(pass); }
+unsigned int png_rs_test_pass_start_col(int pass) { return PNG_PASS_START_COL(pass); }
+unsigned int png_rs_test_pass_row_offset(int pass) { return PNG_PASS_ROW_OFFSET(pass); }
+unsigned int png_rs_test_pass_col_offset(int pass) { return PNG_PASS_COL_OFFSET(pass); }
+unsigned int png_rs_test_pass_row_shift(int pass) { return PNG_PASS_ROW_SHIFT(pass); }
+unsigned int png_rs_test_pass_col_shift(int pass) { return PNG_PASS_COL_SHIFT(pass); }
+png_uint_32 png_rs_test_pass_rows(png_uint_32 height, int pass) { return PNG_PASS_ROWS(height, pass); }
+png_uint_32 png_rs_test_pass_cols(png_uint_32 width, int pass) { return PNG_PASS_COLS(width, pass); }
+png_uint_32 png_rs_test_row_from_pass_row(png_uint_32 y, int pass) { return PNG_ROW_FROM_PASS_ROW(y, pass); }
+png_uint_32 png_rs_test_col_from_pass_col(png_uint_32 x, int pass) { return PNG_COL_FROM_PASS_COL(x, pass); }
+int png_rs_test_row_in_interlace_pass(png_uint_32 y, int pass) { return PNG_ROW_IN_INTERLACE_PASS(y, pass); }
+int png_rs_test_col_in_interlace_pass(png_uint_32 x, int pass) { return PNG_COL_IN_INTERLACE_PASS(x, pass); }
+
+size_t png_rs_test_rowbytes(unsigned int pixel_bits, png_uint_32 width) { return PNG_ROWBYTES(pixel_bits, width); }
+
+png_byte png_rs_test_composite(png_byte fg, png_byte alpha, png_byte bg)
+{
+   png_byte composite;
+   png_composite(composite, fg, alpha, bg);
+   return composite;
+}
+
+png_uint_16 png_rs_test_composite_16(png_uint_16 fg, png_uint_16 alpha, png_uint_16 bg)
+{
+   png_uint_16 composite;
+   png_composite_16(composite, fg, alpha, bg);
+   return composite;
+}
+
+png_uint_32 png_rs_test_get_uint_32(png_const_bytep buf) { return PNG_get_uint_32(buf); }
+png_uint_16 png_rs_test_get_uint_16(png_const_bytep buf) { return PNG_get_uint_16(buf); }
+png_int_32 png_rs_test_get_int_32(png_const_bytep buf) { return PNG_get_int_32(buf); }
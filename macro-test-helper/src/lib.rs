@@ -0,0 +1,57 @@
+//! Real **libpng** macros evaluated by 'c_macro_shim.c', for comparison with their Rust translations
+//! in 'libpng-vendored-sys' tests. Kept out of its build script, so that its users don't compile test code.
+use libc::{c_int, c_uint, size_t};
+
+use libpng_vendored_sys::*;
+
+extern "C" {
+    pub fn png_rs_test_uint_31_max() -> png_uint_32;
+    pub fn png_rs_test_uint_32_max() -> png_uint_32;
+    pub fn png_rs_test_size_max() -> size_t;
+    pub fn png_rs_test_fp_max() -> png_fixed_point;
+    pub fn png_rs_test_fp_min() -> png_fixed_point;
+    pub fn png_rs_test_zbuf_size() -> size_t;
+
+    pub fn png_rs_test_image_failed(image: *const png_image) -> c_int;
+    pub fn png_rs_test_image_sample_channels(fmt: png_uint_32) -> size_t;
+    pub fn png_rs_test_image_sample_component_size(fmt: png_uint_32) -> size_t;
+    pub fn png_rs_test_image_sample_size(fmt: png_uint_32) -> size_t;
+    pub fn png_rs_test_image_maximum_colormap_components(fmt: png_uint_32) -> size_t;
+    pub fn png_rs_test_image_pixel_channels(fmt: png_uint_32) -> size_t;
+    pub fn png_rs_test_image_pixel_component_size(fmt: png_uint_32) -> size_t;
+    pub fn png_rs_test_image_pixel_size(fmt: png_uint_32) -> size_t;
+    pub fn png_rs_test_image_row_stride(image: *const png_image) -> size_t;
+    pub fn png_rs_test_image_buffer_size(image: *const png_image, row_stride: size_t) -> size_t;
+    pub fn png_rs_test_image_size(image: *const png_image) -> size_t;
+    pub fn png_rs_test_image_colormap_size(image: *const png_image) -> size_t;
+    pub fn png_rs_test_image_data_size(image: *const png_image) -> size_t;
+    pub fn png_rs_test_zlib_max_size(b: png_alloc_size_t) -> size_t;
+    pub fn png_rs_test_image_compressed_size_max(image: *const png_image) -> size_t;
+    pub fn png_rs_test_image_png_size_max(image: *const png_image) -> size_t;
+
+    pub fn png_rs_test_pass_start_row(pass: c_int) -> c_uint;
+    pub fn png_rs_test_pass_start_col(pass: c_int) -> c_uint;
+    pub fn png_rs_test_pass_row_offset(pass: c_int) -> c_uint;
+    pub fn png_rs_test_pass_col_offset(pass: c_int) -> c_uint;
+    pub fn png_rs_test_pass_row_shift(pass: c_int) -> c_uint;
+    pub fn png_rs_test_pass_col_shift(pass: c_int) -> c_uint;
+    pub fn png_rs_test_pass_rows(height: png_uint_32, pass: c_int) -> png_uint_32;
+    pub fn png_rs_test_pass_cols(width: png_uint_32, pass: c_int) -> png_uint_32;
+    pub fn png_rs_test_row_from_pass_row(y: png_uint_32, pass: c_int) -> png_uint_32;
+    pub fn png_rs_test_col_from_pass_col(x: png_uint_32, pass: c_int) -> png_uint_32;
+    pub fn png_rs_test_row_in_interlace_pass(y: png_uint_32, pass: c_int) -> c_int;
+    pub fn png_rs_test_col_in_interlace_pass(x: png_uint_32, pass: c_int) -> c_int;
+
+    pub fn png_rs_test_rowbytes(pixel_bits: c_uint, width: png_uint_32) -> size_t;
+
+    pub fn png_rs_test_composite(fg: png_byte, alpha: png_byte, bg: png_byte) -> png_byte;
+    pub fn png_rs_test_composite_16(
+        fg: png_uint_16,
+        alpha: png_uint_16,
+        bg: png_uint_16,
+    ) -> png_uint_16;
+
+    pub fn png_rs_test_get_uint_32(buf: png_const_bytep) -> png_uint_32;
+    pub fn png_rs_test_get_uint_16(buf: png_const_bytep) -> png_uint_16;
+    pub fn png_rs_test_get_int_32(buf: png_const_bytep) -> png_int_32;
+}